@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use crate::schema::{BadgeConfig, GenerateConfig};
+use crate::template::render;
+use crate::vfs::Vfs;
+
+/// A shields.io endpoint badge for the version,
+/// see https://shields.io/badges/endpoint-badge
+pub fn badge_json(badge: &BadgeConfig, version: &str) -> String {
+    let mut json = serde_json::json!({
+        "schemaVersion": 1,
+        "label": badge.label,
+        "message": version,
+    });
+    if let Some(color) = &badge.color {
+        json["color"] = color.as_str().into();
+    }
+    serde_json::to_string_pretty(&json).unwrap() + "\n"
+}
+
+/// The content of a `[[generate]]` file for a version change
+pub fn render_generated(
+    fs: &dyn Vfs,
+    project_root: &Path,
+    generate: &GenerateConfig,
+    current_version: &str,
+    new_version: &str,
+) -> Result<String, String> {
+    let template = match (&generate.template, &generate.template_file) {
+        (Some(template), None) => template.clone(),
+        (None, Some(file)) => fs.read_to_string(&project_root.join(file))?,
+        _ => {
+            return Err(format!(
+                "[[generate]] entry for {} needs exactly one of `template` or `template-file`",
+                generate.path.display()
+            ));
+        }
+    };
+    Ok(render(&template, current_version, new_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_badge_json() {
+        let mut badge: BadgeConfig = toml::from_str(r#"path = "badge.json""#).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&badge_json(&badge, "1.2.4")).unwrap();
+        assert_eq!(parsed, serde_json::json!({"schemaVersion": 1, "label": "version", "message": "1.2.4"}));
+
+        badge.color = Some("blue".to_string());
+        let parsed: serde_json::Value = serde_json::from_str(&badge_json(&badge, "1.2.4")).unwrap();
+        assert_eq!(parsed["color"], "blue");
+    }
+
+    #[test]
+    fn test_render_generated() {
+        let root = Path::new("/project");
+        let fs = MemoryFs::new(root).with_file("/project/version.py.in", "__version__ = \"{new-version}\"\n");
+        let generate = |content: &str| -> GenerateConfig { toml::from_str(content).unwrap() };
+
+        let inline = generate(
+            r#"
+path = "src/version.rs"
+template = 'pub const VERSION: &str = "{new-version}";'
+"#,
+        );
+        assert_eq!(
+            render_generated(&fs, root, &inline, "1.2.3", "1.2.4").unwrap(),
+            "pub const VERSION: &str = \"1.2.4\";"
+        );
+
+        let from_file = generate("path = \"version.py\"\ntemplate-file = \"version.py.in\"");
+        assert_eq!(render_generated(&fs, root, &from_file, "1.2.3", "1.2.4").unwrap(), "__version__ = \"1.2.4\"\n");
+
+        assert!(render_generated(&fs, root, &generate("path = \"x\""), "1.2.3", "1.2.4").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use crate::vfs::Vfs;
+
+/// An in-memory copy of a file that is read once, edited in place, and written once
+pub struct FileBuffer {
+    path: PathBuf,
+    lines: Vec<String>,
+    line_ending: &'static str,
+    trailing_newline: bool,
+    dirty: bool,
+}
+
+impl FileBuffer {
+    /// Read a file into memory
+    pub fn read(fs: &dyn Vfs, path: &Path) -> Result<Self, String> {
+        let content = fs.read_to_string(path)?;
+        Ok(Self::from_content(path, &content))
+    }
+
+    /// Build a buffer from already-loaded content
+    pub fn from_content(path: &Path, content: &str) -> Self {
+        let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        Self {
+            path: path.to_path_buf(),
+            lines: content.lines().map(String::from).collect(),
+            line_ending,
+            trailing_newline: content.ends_with('\n'),
+            dirty: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Replace a single line, marking the buffer as modified
+    pub fn set_line(&mut self, idx: usize, line: &str) -> Result<(), String> {
+        let slot = self.lines.get_mut(idx).ok_or_else(|| {
+            format!("Line {} is out of range for {}", idx + 1, self.path.display())
+        })?;
+        if slot != line {
+            *slot = line.to_string();
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Render the buffer back to text, preserving line endings and the trailing newline
+    pub fn content(&self) -> String {
+        let mut content = self.lines.join(self.line_ending);
+        if self.trailing_newline {
+            content.push_str(self.line_ending);
+        }
+        content
+    }
+
+    /// Write the buffer back to disk if it was modified
+    pub fn write(&mut self, fs: &dyn Vfs) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        fs.write(&self.path, &self.content())?;
+        self.dirty = false;
+        Ok(())
+    }
+}
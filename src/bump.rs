@@ -1,22 +1,46 @@
-use std::fs;
-use std::path::Path;
-
-use crate::cast::cast_version;
-use crate::finders::find_project_root;
-use crate::git::{maybe_run_pre_commit, run_git_actions};
-use crate::schema::{Config, FileKind, OnInvalidVersion};
-use crate::tui::{select_changes, ProposedChange};
+use std::cmp::Ordering;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use ignore::overrides::Override;
+
+use crate::artifacts::{badge_json, render_generated};
+use crate::buffer::FileBuffer;
+use crate::cast::{apply_prerelease_style, cast_with_rules, lost_information, CastRules};
+use crate::engine::{ReplaceOptions, check_line_spans, find_version, replace_line, replace_lines};
+use crate::finders::{expand_glob, find_project_root, is_glob, native_path, path_matcher};
+use crate::git::{git_plan, maybe_run_pre_commit, run_git_actions};
+use crate::interrupt;
+use crate::manifest::{propose_field_update, read_field_version};
+use crate::pager::page;
+use crate::pins::replace_requirements;
+use crate::process::run_captured;
+use crate::kind::VersionKind;
+use crate::pep440::{normalize, Pep440Version, PreRelease};
+use crate::sort::compare_versions;
+use crate::schema::{Config, FileConfig, OnInvalidVersion, OnWrite, VersionField, ZeroVer};
+use crate::template::{render, Components};
+use crate::timings::Timings;
+use crate::tui::{select_changes, BumpSummary, ProposedChange, ScanProgress};
 use crate::version::validate_version;
+use crate::vfs::Vfs;
 
 const DEFAULT_CONTEXT_LINES: usize = 3;
 
+/// Files larger than this (in bytes) are scanned and rewritten line-by-line instead of in memory
+const STREAMING_THRESHOLD: u64 = 8 * 1024 * 1024;
+
 /// Format a path relative to project root, bold, with folder and filename in different colors
-fn pretty_path(path: &Path) -> String {
-    let rel_path = if let Some(root) = find_project_root() {
-        path.strip_prefix(&root).unwrap_or(path)
-    } else {
-        path
-    };
+/// (plain without `color`)
+fn pretty_path(project_root: &Path, path: &Path, color: bool) -> String {
+    if !color {
+        return relative_path(project_root, path);
+    }
+    let rel_path = path.strip_prefix(project_root).unwrap_or(path);
 
     let parent = rel_path.parent().map(|p| p.to_string_lossy()).unwrap_or_default();
     let filename = rel_path.file_name().map(|f| f.to_string_lossy()).unwrap_or_default();
@@ -29,39 +53,310 @@ fn pretty_path(path: &Path) -> String {
     }
 }
 
-pub fn bump_version(config: &Config, target: &str, force: bool) -> Result<(), String> {
+/// A configured file as listed by `bver files`
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileReport {
+    /// Relative to the project root (the configured `src` if it can't be resolved)
+    pub path: String,
+    pub kind: VersionKind,
+    /// The current version as written to this file (cast for its kind)
+    pub version: Option<String>,
+    /// The number of lines the version was found on, or why the file can't be scanned
+    pub matches: Result<usize, String>,
+}
+
+/// Every configured file (globs expanded) and synced manifest, with how often the current version
+/// is found in it, as a health check of the config
+pub fn list_files(fs: &dyn Vfs, config: &Config) -> Result<Vec<FileReport>, String> {
     let current_version = config
         .current_version
         .as_ref()
         .ok_or("No current_version found in config")?;
+    let project_root = find_project_root(fs).ok_or("Could not find project root")?;
+    let default_files;
+    let files = if config.files.is_empty() {
+        default_files = config.default_files(&project_root);
+        &default_files
+    } else {
+        &config.files
+    };
 
-    let new_version = if is_version_string(target) {
-        target.to_string()
+    let mut buffers = BTreeMap::new();
+    let mut reports = Vec::new();
+    for file_config in files.iter().flat_map(FileConfig::expand) {
+        let kind = file_config.resolve_kind(config.default_kind);
+        let on_invalid = file_config.resolve_on_invalid(config.on_invalid_version);
+        let rules = file_config.resolve_cast(config.cast.get(&kind));
+        let failed = |path: String, e: String| FileReport { path, kind, version: None, matches: Err(e) };
+        let src = match file_config.resolve_src(fs, &project_root) {
+            Ok(src) => src,
+            Err(e) => {
+                reports.push(failed("-".to_string(), e));
+                continue;
+            }
+        };
+        let version = match get_file_version(current_version, kind, on_invalid, config.prerelease_style.get(&kind), &rules, &src) {
+            Ok(version) => version,
+            Err(e) => {
+                reports.push(failed(src.display().to_string(), e));
+                continue;
+            }
+        };
+        let sources = match resolve_sources(fs, &project_root, &src, &config.exclude) {
+            Ok(sources) => sources,
+            Err(e) => {
+                reports.push(failed(src.display().to_string(), e));
+                continue;
+            }
+        };
+        for path in sources {
+            // Replacing the version with itself proposes a change on every line it's found on
+            let matches = collect_file_changes(fs, &path, &version, &version, 0, &file_config, &mut buffers);
+            reports.push(FileReport {
+                path: relative_path(&project_root, &path),
+                kind,
+                version: Some(version.clone()),
+                matches: matches.map(|changes| changes.len()),
+            });
+        }
+    }
+
+    for field in &config.version_fields {
+        let path = relative_path(&project_root, &field.path);
+        let rules = config.cast.get(&field.kind).cloned().unwrap_or_default();
+        let version = get_file_version(current_version, field.kind, config.on_invalid_version, None, &rules, &field.path);
+        let matches = version
+            .as_ref()
+            .map_err(Clone::clone)
+            .and_then(|version| {
+                // A field is updated structurally, so it holds the version once or not at all
+                let content = fs.read_to_string(&field.path)?;
+                Ok(usize::from(read_field_version(&content, field)?.as_deref() == Some(version.as_str())))
+            });
+        reports.push(FileReport { path, kind: field.kind, version: version.ok(), matches });
+    }
+    Ok(reports)
+}
+
+/// The version a configured file actually holds (`bver current --from`): read structurally from
+/// a synced manifest, or found through the search template of a plain `[[file]]` entry
+pub fn read_version_from(fs: &dyn Vfs, config: &Config, path: &Path) -> Result<String, String> {
+    let current_version = config.current_version.as_deref().unwrap_or("0.0.0");
+    let project_root = find_project_root(fs).ok_or("Could not find project root")?;
+    let cwd = fs.current_dir().unwrap_or_else(|| project_root.clone());
+    let target = relative_path(&project_root, &cwd.join(path));
+    let not_found = || format!("Version not found in {target}");
+
+    if let Some(field) = config.version_fields.iter().find(|f| relative_path(&project_root, &f.path) == target) {
+        let content = fs.read_to_string(&field.path)?;
+        return read_field_version(&content, field)?.ok_or_else(not_found);
+    }
+
+    let default_files;
+    let files = if config.files.is_empty() {
+        default_files = config.default_files(&project_root);
+        &default_files
     } else {
-        compute_new_version(current_version, target)?
+        &config.files
     };
-    let context_lines = config.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
-    let project_root = find_project_root().ok_or("Could not find project root")?;
+    for file_config in files.iter().flat_map(FileConfig::expand) {
+        let src = file_config.resolve_src(fs, &project_root)?;
+        let sources = resolve_sources(fs, &project_root, &src, &config.exclude)?;
+        let Some(source) = sources.iter().find(|source| relative_path(&project_root, source) == target) else {
+            continue;
+        };
+        if file_config.preset.is_some() {
+            return Err(format!("Can't read the version from {target}: it's updated by a preset"));
+        }
+        let options = ReplaceOptions {
+            replace: None,
+            ..file_config.replace_options()
+        };
+        let content = fs.read_to_string(source)?;
+        return find_version(&content, current_version, &options)?.ok_or_else(not_found);
+    }
+    Err(format!("{target} is not a configured file"))
+}
 
-    let default_kind = config.default_kind;
+/// How the new version is determined
+pub enum BumpTarget {
+    /// Components to bump (e.g. `minor` or `minor,alpha`); anything else is taken as a literal version
+    Auto(String),
+    /// An explicit new version (`--new-version`)
+    Version(String),
+}
 
-    // Collect all proposed changes
-    let mut proposed_changes: Vec<ProposedChange> = Vec::new();
+/// Per-run options for `bump_version`
+#[derive(Debug, Default)]
+pub struct BumpOptions {
+    /// Force git operations (tag, push)
+    pub force: bool,
+    /// Allow casts that drop parts of the version in every file
+    pub allow_lossy: bool,
+    /// Allow an explicit new version that isn't newer than the current one
+    pub allow_downgrade: bool,
+    /// Only update these files (paths or globs relative to the project root)
+    pub files: Vec<String>,
+    /// Leave these files alone (paths or globs relative to the project root)
+    pub exclude: Vec<String>,
+    /// Only update files of this kind
+    pub only_kind: Option<VersionKind>,
+    /// Print the proposed changes as JSON instead of applying them
+    pub plan: bool,
+    /// Print the proposed changes as a unified diff instead of applying them
+    pub diff: bool,
+    /// Color paths in console output
+    pub color: bool,
+    /// Run a major bump under `protect-major` without asking for confirmation
+    pub confirm_major: bool,
+    /// Page long `plan` / `diff` output through `$PAGER` on a terminal
+    pub pager: bool,
+    /// Write the proposed changes to this patch file (for `git apply`) instead of applying them
+    pub emit_patch: Option<PathBuf>,
+    /// Per-phase timings, reported with `--timings`
+    pub timings: Timings,
+}
+
+/// Restricts a run to some of the configured files (`--files` / `--exclude` / `--only-kind`),
+/// remembering which files were left out
+struct FileFilter {
+    include: Option<Override>,
+    exclude: Option<Override>,
+    only_kind: Option<VersionKind>,
+    skipped: BTreeSet<PathBuf>,
+}
 
-    for file_config in &config.files {
-        let file_path = project_root.join(&file_config.src);
-        if !file_path.exists() {
-            return Err(format!("File not found: {}", pretty_path(&file_path)));
+impl FileFilter {
+    fn new(project_root: &Path, options: &BumpOptions) -> Result<Self, String> {
+        let matcher = |patterns: &[String]| -> Result<Option<Override>, String> {
+            if patterns.is_empty() {
+                Ok(None)
+            } else {
+                path_matcher(project_root, patterns).map(Some)
+            }
+        };
+        Ok(Self {
+            include: matcher(&options.files)?,
+            exclude: matcher(&options.exclude)?,
+            only_kind: options.only_kind,
+            skipped: BTreeSet::new(),
+        })
+    }
+
+    /// Whether a file of the given kind takes part in this run
+    fn allows(&mut self, path: &Path, kind: VersionKind) -> bool {
+        let matches = |matcher: &Override| matcher.matched(path, false).is_whitelist();
+        let allowed = self.include.as_ref().is_none_or(matches)
+            && !self.exclude.as_ref().is_some_and(matches)
+            && self.only_kind.is_none_or(|only| only == kind);
+        if !allowed {
+            self.skipped.insert(path.to_path_buf());
         }
+        allowed
+    }
+}
+
+/// The version a bump to `target` would produce
+pub fn new_version(config: &Config, current_version: &str, target: &BumpTarget) -> Result<String, String> {
+    let new_version = match target {
+        BumpTarget::Version(version) => {
+            validate_version(version, config.default_kind)
+                .map_err(|e| format!("Invalid --new-version '{version}': {e}"))?;
+            version.clone()
+        }
+        BumpTarget::Auto(target) if is_version_string(target) => {
+            if target.contains(',') {
+                return Err(format!("Invalid target: {target}. Only components can be chained (e.g. minor,alpha)"));
+            }
+            target.clone()
+        }
+        BumpTarget::Auto(target) => {
+            let computed = compute_new_version(current_version, &apply_zero_ver(current_version, target, config.zero_ver)?)?;
+            match config.prerelease_style.get(&config.default_kind) {
+                Some(style) => apply_prerelease_style(&computed, style),
+                None => computed,
+            }
+        }
+    };
+    if new_version.trim().is_empty() {
+        return Err("The new version cannot be empty".to_string());
+    }
+    Ok(new_version)
+}
+
+pub fn bump_version(fs: &dyn Vfs, config: &Config, target: &BumpTarget, options: &BumpOptions) -> Result<(), String> {
+    let current_version = config
+        .current_version
+        .as_ref()
+        .ok_or("No current_version found in config")?;
 
-        let kind = file_config.kind.unwrap_or(default_kind);
+    let new_version = new_version(config, current_version, target)?;
+    check_allowed_targets(config, current_version, &new_version, target)?;
+    check_version_bounds(config, &new_version)?;
+    let explicit = match target {
+        BumpTarget::Version(_) => true,
+        BumpTarget::Auto(target) => is_version_string(target),
+    };
+    if explicit && !options.allow_downgrade {
+        check_forward(config, current_version, &new_version)?;
+    }
+    // An unconfirmed major bump under `protect-major` has to be typed in the TUI, so it can't
+    // go to a patch that is applied later
+    let confirm_major = config.protect_major && !options.confirm_major && is_major_bump(current_version, &new_version);
+    if confirm_major && options.emit_patch.is_some() {
+        return Err(format!(
+            "{current_version} -> {new_version} is a major bump and `protect-major` is set (use --confirm-major to write the patch)"
+        ));
+    }
+    let context_lines = config.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let project_root = find_project_root(fs).ok_or("Could not find project root")?;
+
+    let scan = Scan {
+        fs,
+        config,
+        options,
+        project_root: &project_root,
+        current_version,
+        new_version: &new_version,
+        context_lines,
+    };
+    let mut filter = FileFilter::new(&project_root, options)?;
+    // Every (non-streamed) file stays in memory until it's written
+    let mut buffers: BTreeMap<PathBuf, FileBuffer> = BTreeMap::new();
+    // Count scanned files on stderr, then collect the changes and the `post-hook` commands
+    // to run once their file is written
+    let mut progress = ScanProgress::new(std::io::stderr().is_terminal());
+    let scanned = scan.collect(&mut filter, None, &mut buffers, &mut progress);
+    progress.finish();
+    let (mut proposed_changes, post_hooks) = scanned?;
+
+    if options.plan {
+        let plan = plan_json(&project_root, current_version, &new_version, &proposed_changes, &filter.skipped);
+        page(&format!("{}\n", serde_json::to_string_pretty(&plan).unwrap()), options.pager);
+        return Ok(());
+    }
 
-        // Get the versions to use for this file (possibly casted)
-        let old_file_version = get_file_version(current_version, kind, config.on_invalid_version, &file_config.src)?;
-        let new_file_version = get_file_version(&new_version, kind, config.on_invalid_version, &file_config.src)?;
+    if options.diff {
+        page(&unified_diff(fs, &project_root, &proposed_changes)?, options.pager);
+        return Ok(());
+    }
+
+    if let Some(patch) = &options.emit_patch {
+        fs.write(patch, &unified_diff(fs, &project_root, &proposed_changes)?)?;
+        println!(
+            "Wrote {} change(s) to {} (apply with: git apply {})",
+            proposed_changes.len(),
+            patch.display(),
+            patch.display()
+        );
+        return Ok(());
+    }
 
-        let file_changes = collect_file_changes(&file_path, &old_file_version, &new_file_version, context_lines)?;
-        proposed_changes.extend(file_changes);
+    if !filter.skipped.is_empty() {
+        println!("Skipping {} file(s) for this run:", filter.skipped.len());
+        for path in &filter.skipped {
+            println!("  {}", pretty_path(&project_root, path, options.color));
+        }
     }
 
     if proposed_changes.is_empty() {
@@ -69,10 +364,33 @@ pub fn bump_version(config: &Config, target: &str, force: bool) -> Result<(), St
         return Ok(());
     }
 
-    // Show TUI to select changes
-    let confirmed = select_changes(&mut proposed_changes)
+    // Show TUI to select changes and the git actions to run
+    let mut git_steps = git_plan(&config.git, current_version, &new_version);
+    let summary = BumpSummary {
+        current_version,
+        new_version: &new_version,
+        kind: config.default_kind,
+        confirm_major,
+    };
+    let mut rescan = |path: &Path| {
+        // The file was edited on disk, so its buffer is stale
+        buffers.remove(path);
+        scan.collect(&mut filter, Some(path), &mut buffers, &mut ScanProgress::new(false))
+            .map(|(changes, _)| changes)
+    };
+    // Ctrl-C until the files are written rolls back what this run wrote instead of leaving files
+    // half-updated
+    let catching = interrupt::catch();
+    let confirmed = options
+        .timings
+        .time("select changes (TUI)", || {
+            select_changes(&mut proposed_changes, &mut git_steps, &summary, &mut rescan)
+        })
         .map_err(|e| format!("TUI error: {e}"))?;
 
+    if interrupt::interrupted() {
+        return Err("Interrupted, no files were changed".to_string());
+    }
     if !confirmed {
         println!("Cancelled.");
         return Ok(());
@@ -86,38 +404,633 @@ pub fn bump_version(config: &Config, target: &str, force: bool) -> Result<(), St
     }
 
     println!("Applying {} change(s)...", selected.len());
+    let mut changes_by_file: BTreeMap<&Path, Vec<&ProposedChange>> = BTreeMap::new();
     for change in &selected {
-        apply_change(change)?;
+        changes_by_file.entry(change.path.as_path()).or_default().push(change);
     }
+    // What was written so far, to roll back if the run is interrupted
+    let mut written: Vec<&Path> = Vec::new();
+    let mut generated: Vec<PathBuf> = Vec::new();
+    let mut originals: Vec<(PathBuf, String)> = Vec::new();
+    let applied = (|| -> Result<(), String> {
+        for (path, changes) in &changes_by_file {
+            interrupt::check()?;
+            options.timings.time(format!("write {}", relative_path(&project_root, path)), || {
+                apply_changes(fs, &project_root, path, changes, buffers.get_mut(*path), options.color)
+            })?;
+            written.push(path);
+        }
 
-    // Validate git config before running any git operations
-    config.git.validate()?;
+        for (path, command) in &post_hooks {
+            if changes_by_file.contains_key(path.as_path()) {
+                interrupt::check()?;
+                options.timings.time(format!("post-hook {}", relative_path(&project_root, path)), || {
+                    run_post_hook(command, &project_root, config.hook_timeout())
+                })?;
+            }
+        }
 
-    // Run pre-commit hooks if configured
-    maybe_run_pre_commit(config.git.run_pre_commit)?;
+        // Regenerate artifacts derived from the version, so they're committed with the bump
+        let mut artifacts: Vec<(PathBuf, String)> = Vec::new();
+        if let Some(badge) = &config.badge {
+            artifacts.push((project_root.join(&badge.path), badge_json(badge, &new_version)));
+        }
+        for generate in &config.generated {
+            let content = render_generated(fs, &project_root, generate, current_version, &new_version)?;
+            artifacts.push((project_root.join(&generate.path), content));
+        }
+        for (path, content) in artifacts {
+            interrupt::check()?;
+            if let Ok(original) = fs.read_to_string(&path) {
+                originals.push((path.clone(), original));
+            }
+            fs.write(&path, &content)?;
+            println!("  Updated {}", pretty_path(&project_root, &path, options.color));
+            generated.push(path);
+        }
+        interrupt::check()
+    })();
+    if let Err(e) = applied {
+        if !interrupt::interrupted() {
+            return Err(e);
+        }
+        for path in &written {
+            revert_changes(fs, path, &changes_by_file[path], buffers.get_mut(*path))?;
+        }
+        for (path, original) in &originals {
+            fs.write(path, original)?;
+        }
+        return Err(format!("Interrupted, restored {} file(s)", written.len() + originals.len()));
+    }
+    // Hooks and git run with the usual Ctrl-C handling, so an interrupted commit stops right away
+    drop(catching);
+
+    // Only the git actions left enabled in the TUI run
+    let mut git_config = config.git.clone();
+    git_config
+        .actions
+        .retain(|action| git_steps.iter().any(|step| step.action == *action && step.enabled));
+
+    // Validate git config before running any git operations
+    git_config.validate()?;
 
     // Collect unique changed file paths
-    let changed_files: Vec<&std::path::Path> = selected
-        .iter()
-        .map(|c| c.path.as_path())
-        .collect::<std::collections::BTreeSet<_>>()
-        .into_iter()
+    let changed_files: Vec<&Path> = changes_by_file
+        .keys()
+        .copied()
+        .chain(generated.iter().map(PathBuf::as_path))
         .collect();
 
+    // Run pre-commit hooks if configured
+    options.timings.time("pre-commit", || maybe_run_pre_commit(&git_config, &changed_files, config.hook_timeout()))?;
+
     // Run git actions if configured
-    run_git_actions(&config.git, current_version, &new_version, force, &changed_files)?;
+    options.timings.time("git", || {
+        run_git_actions(&git_config, current_version, &new_version, options.force, &changed_files)
+    })?;
 
     Ok(())
 }
 
+/// `post-hook` commands, with the file that triggered them
+type PostHooks = Vec<(PathBuf, String)>;
+
+/// What a scan for proposed changes needs, so a file can be scanned again after it was edited
+#[derive(Clone, Copy)]
+struct Scan<'a> {
+    fs: &'a dyn Vfs,
+    config: &'a Config,
+    options: &'a BumpOptions,
+    project_root: &'a Path,
+    current_version: &'a str,
+    new_version: &'a str,
+    context_lines: usize,
+}
+
+impl Scan<'_> {
+    /// The proposed changes of every configured file (or only of `only`), sorted by file and line,
+    /// with the `post-hook` commands of the files that have changes
+    fn collect(
+        &self,
+        filter: &mut FileFilter,
+        only: Option<&Path>,
+        buffers: &mut BTreeMap<PathBuf, FileBuffer>,
+        progress: &mut ScanProgress,
+    ) -> Result<(Vec<ProposedChange>, PostHooks), String> {
+        let Scan {
+            fs,
+            config,
+            options,
+            project_root,
+            current_version,
+            new_version,
+            context_lines,
+        } = *self;
+        let default_kind = config.default_kind;
+        // Only scan `only`, if given, without touching the filter's record of skipped files
+        let mut wanted = |path: &Path, kind| only.is_none_or(|only| path == only) && filter.allows(path, kind);
+
+        let mut proposed_changes: Vec<ProposedChange> = Vec::new();
+        let mut post_hooks: PostHooks = Vec::new();
+
+        // Without explicit [[file]] entries, update the manifest the config came from
+        let default_files;
+        let files = if config.files.is_empty() {
+            default_files = config.default_files(project_root);
+            &default_files
+        } else {
+            &config.files
+        };
+
+        for file_config in files.iter().flat_map(FileConfig::expand) {
+            let file_config = &file_config;
+            let src = file_config.resolve_src(fs, project_root)?;
+            let kind = file_config.resolve_kind(default_kind);
+            let policy = CastPolicy {
+                on_invalid: file_config.resolve_on_invalid(config.on_invalid_version),
+                allow_lossy: options.allow_lossy || file_config.resolve_allow_lossy(),
+                rules: file_config.resolve_cast(config.cast.get(&kind)),
+            };
+            let context_lines = file_config.context_lines.unwrap_or(context_lines);
+
+            let sources: Vec<PathBuf> = resolve_sources(fs, project_root, &src, &config.exclude)?
+                .into_iter()
+                .filter(|path| wanted(path, kind))
+                .collect();
+            if sources.is_empty() {
+                continue;
+            }
+
+            // Get the versions to use for this file (possibly casted)
+            let (old_file_version, new_file_version) =
+                file_versions(config, current_version, new_version, kind, &policy, &src)?;
+
+            let mut entry_changes = Vec::new();
+            for file_path in &sources {
+                let changes = options.timings.time(format!("scan {}", relative_path(project_root, file_path)), || {
+                    collect_file_changes(
+                        fs,
+                        file_path,
+                        &old_file_version,
+                        &new_file_version,
+                        context_lines,
+                        file_config,
+                        buffers,
+                    )
+                })?;
+                progress.scanned(changes.len());
+                if let Some(hook) = &file_config.post_hook
+                    && !changes.is_empty()
+                {
+                    let command = render(hook, &old_file_version, &new_file_version)
+                        .replace("{file}", &relative_path(project_root, file_path));
+                    post_hooks.push((file_path.clone(), command));
+                }
+                entry_changes.extend(changes);
+            }
+
+            if entry_changes.is_empty() {
+                // A rescanned file may no longer hold the version, which is fine
+                if only.is_some() {
+                    continue;
+                }
+                if let Some(preset) = file_config.preset
+                    && let Some((path, note)) = sources.iter().find_map(|path| {
+                        let buffer = buffers.get(path)?;
+                        Some((path, preset.missing_field_note(buffer.lines())?))
+                    })
+                {
+                    eprintln!("Warning: {note}, skipping {}", pretty_path(project_root, path, options.color));
+                    continue;
+                }
+                let target = project_root.join(native_path(&src));
+                let needle = match (&file_config.search, file_config.preset) {
+                    (Some(search), None) => render(search, &old_file_version, &new_file_version),
+                    _ => old_file_version,
+                };
+                return Err(format!(
+                    "Version '{}' not found in {}",
+                    needle,
+                    relative_path(project_root, &target)
+                ));
+            }
+            proposed_changes.extend(entry_changes);
+        }
+
+        // Keep the manifest(s) the version was read from in sync. Only their version field is edited,
+        // so textual [[file]] matches there (e.g. a dependency pinned to the same version) are dropped.
+        let synced: BTreeSet<&Path> = config
+            .version_fields
+            .iter()
+            .filter(|field| wanted(&field.path, field.kind))
+            .map(|field| field.path.as_path())
+            .collect();
+        proposed_changes.retain(|c| !synced.contains(c.path.as_path()));
+        for field in &config.version_fields {
+            if !wanted(&field.path, field.kind) {
+                continue;
+            }
+            let policy = CastPolicy::global(config, options, field.kind);
+            let (old_field_version, new_field_version) =
+                file_versions(config, current_version, new_version, field.kind, &policy, &field.path)?;
+            let changes = options.timings.time(format!("scan {}", relative_path(project_root, &field.path)), || {
+                collect_field_changes(fs, field, &old_field_version, &new_field_version, context_lines, buffers)
+            })?;
+            progress.scanned(changes.len());
+            for change in changes {
+                let covered = proposed_changes
+                    .iter()
+                    .any(|c| c.path == change.path && c.line_idx == change.line_idx);
+                if !covered {
+                    proposed_changes.push(change);
+                }
+            }
+        }
+
+        // Rewrite requirements on this package in other files
+        for pin in &config.pins {
+            let kind = pin.kind.unwrap_or(default_kind);
+            let sources: Vec<PathBuf> = resolve_sources(fs, project_root, &pin.src, &config.exclude)?
+                .into_iter()
+                .filter(|path| wanted(path, kind))
+                .collect();
+            if sources.is_empty() {
+                continue;
+            }
+            let policy = CastPolicy::global(config, options, kind);
+            let (old_pin_version, new_pin_version) =
+                file_versions(config, current_version, new_version, kind, &policy, &pin.src)?;
+            let range = render(&pin.range, &old_pin_version, &new_pin_version);
+            for file_path in sources {
+                let started = Instant::now();
+                let buffer = buffer_for(fs, &file_path, buffers)?;
+                let edits = replace_requirements(buffer.lines(), &pin.package, &range);
+                options.timings.add(format!("scan {}", relative_path(project_root, &file_path)), started.elapsed());
+                progress.scanned(edits.len());
+                for (line_idx, new_line) in edits {
+                    let covered = proposed_changes
+                        .iter()
+                        .any(|c| c.path == file_path && c.line_idx == line_idx);
+                    if !covered {
+                        proposed_changes.push(make_change(buffer, line_idx, new_line, context_lines));
+                    }
+                }
+            }
+        }
+
+        // Present changes by file, then by line, regardless of config or scan order
+        proposed_changes.sort_by(|a, b| (&a.path, a.line_idx).cmp(&(&b.path, b.line_idx)));
+        Ok((proposed_changes, post_hooks))
+    }
+}
+
+/// Run a file's `post-hook` in the project root, reporting its output if it fails or times out
+fn run_post_hook(command: &str, project_root: &Path, timeout: Duration) -> Result<(), String> {
+    println!("Running: {command}");
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let run = run_captured(Command::new(shell).args([flag, command]).current_dir(project_root), timeout)
+        .map_err(|e| format!("Failed to run post-hook '{command}': {e}"))?;
+    if !run.success() {
+        return Err(format!("post-hook '{command}' {}", run.failure(timeout)));
+    }
+    Ok(())
+}
+
+/// A path relative to the project root, with `/` separators on every platform
+fn relative_path(project_root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(project_root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The proposed changes as JSON, with paths relative to the project root (using `/`).
+/// Changes are expected in path, then line order.
+fn plan_json(
+    project_root: &Path,
+    current_version: &str,
+    new_version: &str,
+    changes: &[ProposedChange],
+    skipped: &BTreeSet<PathBuf>,
+) -> serde_json::Value {
+    let relative = |path: &Path| relative_path(project_root, path);
+    serde_json::json!({
+        "current-version": current_version,
+        "new-version": new_version,
+        "changes": changes
+            .iter()
+            .map(|c| serde_json::json!({
+                "path": relative(&c.path),
+                "line": c.line_idx + 1,
+                "old": c.old_line,
+                "new": c.new_line,
+            }))
+            .collect::<Vec<_>>(),
+        "skipped": skipped.iter().map(|p| relative(p)).collect::<Vec<_>>(),
+    })
+}
+
+/// The changes as a unified diff with `diff --git` headers, so `git apply` and diff viewers take it
+fn unified_diff(fs: &dyn Vfs, project_root: &Path, changes: &[ProposedChange]) -> Result<String, String> {
+    let mut out = String::new();
+    for file_changes in changes.chunk_by(|a, b| a.path == b.path) {
+        let path = relative_path(project_root, &file_changes[0].path);
+        // The changes hold lines without their terminator, so take each line's from the file
+        let content = fs.read_to_string(&file_changes[0].path)?;
+        let endings: Vec<&str> = content
+            .split_inclusive('\n')
+            .map(|line| &line[strip_line_ending(line).len()..])
+            .collect();
+        let push_line = |out: &mut String, marker: char, line: &str, line_idx: usize| {
+            out.push(marker);
+            out.push_str(line);
+            match endings.get(line_idx).copied().unwrap_or("\n") {
+                "" => out.push_str("\n\\ No newline at end of file\n"),
+                ending => out.push_str(ending),
+            }
+        };
+        // The old text of every line the changes know about, and the new text of the changed ones
+        let mut old: BTreeMap<usize, &str> = BTreeMap::new();
+        let mut new: HashMap<usize, &str> = HashMap::new();
+        let mut hunks: Vec<std::ops::Range<usize>> = Vec::new();
+        for change in file_changes {
+            let start = change.line_idx - change.context_before.len();
+            let end = change.line_idx + 1 + change.context_after.len();
+            let known = change.context_before.iter().chain([&change.old_line]).chain(&change.context_after);
+            for (line_idx, line) in (start..end).zip(known) {
+                old.entry(line_idx).or_insert(line);
+            }
+            new.insert(change.line_idx, &change.new_line);
+            match hunks.last_mut() {
+                Some(last) if start <= last.end => last.end = last.end.max(end),
+                _ => hunks.push(start..end),
+            }
+        }
+
+        out.push_str(&format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n"));
+        for hunk in hunks {
+            let start = hunk.start + 1;
+            out.push_str(&format!("@@ -{start},{len} +{start},{len} @@\n", len = hunk.len()));
+            // Like git, list a run of changed lines as all removals, then all additions
+            let mut added: Vec<(usize, &str)> = Vec::new();
+            for line_idx in hunk {
+                if let Some(new_line) = new.get(&line_idx) {
+                    push_line(&mut out, '-', old[&line_idx], line_idx);
+                    added.push((line_idx, new_line));
+                    continue;
+                }
+                for (added_idx, new_line) in added.drain(..) {
+                    push_line(&mut out, '+', new_line, added_idx);
+                }
+                push_line(&mut out, ' ', old[&line_idx], line_idx);
+            }
+            for (added_idx, new_line) in added {
+                push_line(&mut out, '+', new_line, added_idx);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Enforce `allowed-targets`. Explicit versions are checked by the targets they amount to.
+fn check_allowed_targets(config: &Config, current_version: &str, new_version: &str, target: &BumpTarget) -> Result<(), String> {
+    if config.allowed_targets.is_empty() {
+        return Ok(());
+    }
+    let (requested, implied) = match target {
+        BumpTarget::Auto(target) if !is_version_string(target) => (target.split(',').map(str::trim).collect(), false),
+        _ => (implied_targets(current_version, new_version)?, true),
+    };
+    let Some(denied) = requested.into_iter().find(|t| !config.allowed_targets.iter().any(|a| a == t)) else {
+        return Ok(());
+    };
+    let reason = match implied {
+        true => format!(" (a {current_version} -> {new_version} bump)"),
+        false => String::new(),
+    };
+    let source = match &config.branch_pattern {
+        Some(pattern) => format!("the `[[branch]]` policy for '{pattern}'"),
+        None => "`allowed-targets`".to_string(),
+    };
+    Err(format!(
+        "Bump target '{denied}'{reason} is not allowed by {source} ({})",
+        config.allowed_targets.join(", ")
+    ))
+}
+
+/// Enforce `min-version` (inclusive) and `max-version` (exclusive)
+fn check_version_bounds(config: &Config, new_version: &str) -> Result<(), String> {
+    let bounds: Vec<String> = [(">=", &config.min_version), ("<", &config.max_version)]
+        .into_iter()
+        .filter_map(|(op, bound)| Some(format!("{op}{}", bound.as_deref()?)))
+        .collect();
+    if bounds.is_empty() {
+        return Ok(());
+    }
+    // Compared under the default kind's ordering, so a prerelease inside the range passes
+    let compare = |bound: &Option<String>| match bound {
+        Some(bound) => compare_versions(new_version, bound, config.default_kind).map(Some),
+        None => Ok(None),
+    };
+    let check = || -> Result<bool, String> {
+        let above_min = compare(&config.min_version)?.is_none_or(|o| o != Ordering::Less);
+        let below_max = compare(&config.max_version)?.is_none_or(|o| o == Ordering::Less);
+        Ok(above_min && below_max)
+    };
+    let range = bounds.join(", ");
+    match check() {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!(
+            "The new version {new_version} is outside the allowed range ({range}) set by `min-version` / `max-version`"
+        )),
+        Err(e) => Err(format!("Can't check {new_version} against `min-version` / `max-version`: {e}")),
+    }
+}
+
+/// Refuse an explicit new version that isn't newer than the current one under the default kind's ordering.
+/// Versions that can't be compared (e.g. date strings) are let through.
+fn check_forward(config: &Config, current: &str, new: &str) -> Result<(), String> {
+    match compare_versions(current, new, config.default_kind) {
+        Ok(Ordering::Greater) => Err(format!(
+            "The new version {new} is older than the current version {current} (use --allow-downgrade to bump anyway)"
+        )),
+        Ok(Ordering::Equal) => Err(format!(
+            "The new version {new} is the same as the current version {current} (use --allow-downgrade to bump anyway)"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether going from `current` to `new` changes the major version
+fn is_major_bump(current: &str, new: &str) -> bool {
+    Components::parse(current).major != Components::parse(new).major
+}
+
+/// The bump targets a change from `current` to `new` amounts to, e.g. `["minor", "alpha"]`
+/// for 1.2.3 -> 1.3.0a1, or `["release"]` for 1.3.0rc2 -> 1.3.0. Going back (or nowhere) is
+/// `["downgrade"]`, which only passes if allowed explicitly.
+fn implied_targets(current: &str, new: &str) -> Result<Vec<&'static str>, String> {
+    let unknown = |_| format!("Can't tell which bump '{current}' -> '{new}' is, to check `allowed-targets`");
+    let old = parse_version(current).map_err(unknown)?;
+    let parsed = parse_version(new).map_err(unknown)?;
+    if parsed <= old {
+        return Ok(vec!["downgrade"]);
+    }
+
+    let ([major, minor, patch], [old_major, old_minor, old_patch]) = (release_triple(&parsed), release_triple(&old));
+    let mut targets = Vec::new();
+    if major != old_major {
+        targets.push("major");
+    } else if minor != old_minor {
+        targets.push("minor");
+    } else if patch != old_patch {
+        targets.push("patch");
+    }
+    match parsed.pre.map(|(phase, _)| phase) {
+        Some(PreRelease::Alpha) => targets.push("alpha"),
+        Some(PreRelease::Beta) => targets.push("beta"),
+        Some(PreRelease::Rc) => targets.push("rc"),
+        None => {}
+    }
+    if parsed.post.is_some() {
+        targets.push("post");
+    }
+    if parsed.dev.is_some() {
+        targets.push("dev");
+    }
+    if targets.is_empty() {
+        targets.push("release");
+    }
+    Ok(targets)
+}
+
+/// Resolve a configured `src` entry (a path or a glob pattern) to the files it refers to
+fn resolve_sources(
+    fs: &dyn Vfs,
+    project_root: &Path,
+    src: &Path,
+    excludes: &[String]) -> Result<Vec<PathBuf>, String> {
+    let pattern = src.to_string_lossy();
+    if is_glob(&pattern) {
+        let paths = expand_glob(fs, project_root, &pattern, excludes)?;
+        if paths.is_empty() {
+            return Err(format!("No files match pattern: {pattern}"));
+        }
+        return Ok(paths);
+    }
+
+    let file_path = project_root.join(native_path(src));
+    if !fs.exists(&file_path) {
+        return Err(format!("File not found: {}", relative_path(project_root, &file_path)));
+    }
+    Ok(vec![file_path])
+}
+
+/// The version as it will be written to a file of the given kind
+fn written_version(version: String, kind: VersionKind, on_write: OnWrite) -> String {
+    match (on_write, kind) {
+        (OnWrite::Normalize, VersionKind::Python) => normalize(&version).unwrap_or(version),
+        _ => version,
+    }
+}
+
 fn is_version_string(s: &str) -> bool {
-    !matches!(s, "major" | "minor" | "patch" | "alpha" | "beta" | "rc" | "post" | "dev" | "release")
+    !s.split(',').all(|part| is_component(part) || is_release_component(part))
+}
+
+/// Components that change the release number (and drop any suffix)
+fn is_release_component(s: &str) -> bool {
+    matches!(s, "major" | "minor" | "patch" | "release")
+}
+
+/// Components that add or increment a suffix
+fn is_component(s: &str) -> bool {
+    matches!(s, "alpha" | "beta" | "rc" | "post" | "dev")
 }
 
+/// How versions are fitted to a file's kind
+struct CastPolicy {
+    on_invalid: OnInvalidVersion,
+    allow_lossy: bool,
+    rules: CastRules,
+}
+
+impl CastPolicy {
+    /// The policy of files without a `[[file]]` entry of their own
+    fn global(config: &Config, options: &BumpOptions, kind: VersionKind) -> Self {
+        CastPolicy {
+            on_invalid: config.on_invalid_version,
+            allow_lossy: options.allow_lossy,
+            rules: config.cast.get(&kind).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// The current and new version as written to a file of the given kind.
+/// Unless lossy casts are allowed, casting the new version must not drop any of its parts
+/// (other than those the cast rules write explicitly).
+fn file_versions(
+    config: &Config,
+    current_version: &str,
+    new_version: &str,
+    kind: VersionKind,
+    policy: &CastPolicy,
+    src: &Path,
+) -> Result<(String, String), String> {
+    let style = config.prerelease_style.get(&kind);
+    let old_file_version = get_file_version(current_version, kind, policy.on_invalid, style, &policy.rules, src)?;
+    let new_file_version = get_file_version(new_version, kind, policy.on_invalid, style, &policy.rules, src)?;
+
+    let lost = lost_information(&policy.rules.strip(new_version), &new_file_version);
+    if !policy.allow_lossy && !lost.is_empty() {
+        return Err(format!(
+            "Casting '{}' to '{}' for file '{}' (kind: {}) would drop the {}. \
+             Pass --allow-lossy or set `allow-lossy = true` on the file entry to accept this",
+            new_version,
+            new_file_version,
+            src.display(),
+            kind.name(),
+            lost.join(", ")
+        ));
+    }
+
+    Ok((old_file_version, written_version(new_file_version, kind, config.on_write)))
+}
+
+/// The version as it appears in a file of the given kind: cast if needed, then rendered
+/// with the kind's configured prerelease style
 fn get_file_version(
     version: &str,
-    kind: FileKind,
+    kind: VersionKind,
     on_invalid: OnInvalidVersion,
+    style: Option<&String>,
+    rules: &CastRules,
+    src: &Path,
+) -> Result<String, String> {
+    let version = cast_file_version(version, kind, on_invalid, rules, src)?;
+    let Some(style) = style else {
+        return Ok(version);
+    };
+    let styled = apply_prerelease_style(&version, style);
+    validate_version(&styled, kind).map_err(|e| {
+        format!(
+            "Prerelease style '{}' gives an invalid version '{}' for file '{}' (kind: {}): {}",
+            style,
+            styled,
+            src.display(),
+            kind.name(),
+            e
+        )
+    })?;
+    Ok(styled)
+}
+
+fn cast_file_version(
+    version: &str,
+    kind: VersionKind,
+    on_invalid: OnInvalidVersion,
+    rules: &CastRules,
     src: &Path,
 ) -> Result<String, String> {
     // First, check if the version is already valid for this kind
@@ -130,20 +1043,20 @@ fn get_file_version(
         OnInvalidVersion::Error => {
             let err = validate_version(version, kind).unwrap_err();
             Err(format!(
-                "Invalid version '{}' for file '{}' (kind: {:?}): {}",
+                "Invalid version '{}' for file '{}' (kind: {}): {}",
                 version,
                 src.display(),
-                kind,
+                kind.name(),
                 err
             ))
         }
         OnInvalidVersion::Cast => {
-            let casted = cast_version(version, kind).map_err(|e| {
+            let casted = cast_with_rules(version, kind, rules).map_err(|e| {
                 format!(
-                    "Cannot cast version '{}' for file '{}' (kind: {:?}): {}",
+                    "Cannot cast version '{}' for file '{}' (kind: {}): {}",
                     version,
                     src.display(),
-                    kind,
+                    kind.name(),
                     e
                 )
             })?;
@@ -151,10 +1064,10 @@ fn get_file_version(
             // Validate the casted version
             validate_version(&casted, kind).map_err(|e| {
                 format!(
-                    "Casted version '{}' is still invalid for file '{}' (kind: {:?}): {}",
+                    "Casted version '{}' is still invalid for file '{}' (kind: {}): {}",
                     casted,
                     src.display(),
-                    kind,
+                    kind.name(),
                     e
                 )
             })?;
@@ -164,300 +1077,336 @@ fn get_file_version(
     }
 }
 
-fn compute_new_version(current: &str, component: &str) -> Result<String, String> {
-    let parsed = parse_version(current)?;
-
-    match component {
-        "major" => Ok(format!("{}.0.0", parsed.major + 1)),
-        "minor" => Ok(format!("{}.{}.0", parsed.major, parsed.minor + 1)),
-        "patch" => {
-            // If we have a prerelease, just drop it (1.0.0a1 -> 1.0.0)
-            if parsed.prerelease.is_some() || parsed.post.is_some() || parsed.dev.is_some() {
-                Ok(format!("{}.{}.{}", parsed.major, parsed.minor, parsed.patch))
-            } else {
-                Ok(format!("{}.{}.{}", parsed.major, parsed.minor, parsed.patch + 1))
-            }
-        }
-        "release" => {
-            // Drop all prerelease/post/dev suffixes
-            Ok(format!("{}.{}.{}", parsed.major, parsed.minor, parsed.patch))
-        }
-        "alpha" => {
-            let num = match &parsed.prerelease {
-                Some((kind, n)) if kind == "alpha" => n + 1,
-                _ => 1,
-            };
-            Ok(format!("{}.{}.{}a{}", parsed.major, parsed.minor, parsed.patch, num))
-        }
-        "beta" => {
-            let num = match &parsed.prerelease {
-                Some((kind, n)) if kind == "beta" => n + 1,
-                _ => 1,
-            };
-            Ok(format!("{}.{}.{}b{}", parsed.major, parsed.minor, parsed.patch, num))
-        }
-        "rc" => {
-            let num = match &parsed.prerelease {
-                Some((kind, n)) if kind == "rc" => n + 1,
-                _ => 1,
-            };
-            Ok(format!("{}.{}.{}rc{}", parsed.major, parsed.minor, parsed.patch, num))
-        }
-        "post" => {
-            let num = parsed.post.map(|n| n + 1).unwrap_or(1);
-            let base = format!("{}.{}.{}", parsed.major, parsed.minor, parsed.patch);
-            let pre = match &parsed.prerelease {
-                Some((kind, n)) => format!("{}{}", prerelease_prefix(kind), n),
-                None => String::new(),
-            };
-            Ok(format!("{}{}.post{}", base, pre, num))
-        }
-        "dev" => {
-            let num = parsed.dev.map(|n| n + 1).unwrap_or(1);
-            let base = format!("{}.{}.{}", parsed.major, parsed.minor, parsed.patch);
-            let pre = match &parsed.prerelease {
-                Some((kind, n)) => format!("{}{}", prerelease_prefix(kind), n),
-                None => String::new(),
-            };
-            let post = parsed.post.map(|n| format!(".post{}", n)).unwrap_or_default();
-            Ok(format!("{}{}{}.dev{}", base, pre, post, num))
-        }
-        _ => Err(format!(
-            "Invalid component: {component}. Use major, minor, patch, release, alpha, beta, rc, post, or dev"
-        )),
+/// Under `zero-ver = "strict"`, a major bump of a 0.x version only bumps the minor version,
+/// so breaking changes stay under 0.x until 1.0.0 is set explicitly
+fn apply_zero_ver(current: &str, target: &str, zero_ver: ZeroVer) -> Result<String, String> {
+    if zero_ver == ZeroVer::Standard || release_triple(&parse_version(current)?)[0] != 0 {
+        return Ok(target.to_string());
     }
+    let components: Vec<&str> = target
+        .split(',')
+        .map(|c| if c.trim() == "major" { "minor" } else { c })
+        .collect();
+    Ok(components.join(","))
 }
 
-fn prerelease_prefix(kind: &str) -> &'static str {
-    match kind {
-        "alpha" => "a",
-        "beta" => "b",
-        "rc" => "rc",
-        _ => "",
+/// Apply a bump target, which may chain components (e.g. `minor,alpha`: 1.2.5 -> 1.3.0a1)
+fn compute_new_version(current: &str, target: &str) -> Result<String, String> {
+    let components: Vec<&str> = target.split(',').map(str::trim).collect();
+    if let Some(pos) = components.iter().position(|c| is_component(c))
+        && let Some(late) = components[pos..].iter().find(|c| is_release_component(c))
+    {
+        return Err(format!(
+            "Invalid target: {target}. '{late}' must come before '{}', as it would drop its suffix",
+            components[pos]
+        ));
+    }
+    if components.iter().filter(|c| is_release_component(c)).count() > 1 {
+        return Err(format!("Invalid target: {target}. Use at most one of major, minor, patch, or release"));
     }
-}
 
-#[derive(Debug, Default)]
-struct ParsedVersion {
-    major: u32,
-    minor: u32,
-    patch: u32,
-    prerelease: Option<(String, u32)>, // (kind, number) e.g., ("alpha", 1)
-    post: Option<u32>,
-    dev: Option<u32>,
+    components
+        .iter()
+        .try_fold(current.to_string(), |version, component| bump_component(&version, component))
 }
 
-fn parse_version(version: &str) -> Result<ParsedVersion, String> {
-    let version = version.to_lowercase();
-
-    // Remove epoch if present
-    let version = if let Some(pos) = version.find('!') {
-        &version[pos + 1..]
-    } else {
-        version.as_str()
-    };
-
-    // Remove local version if present
-    let version = if let Some(pos) = version.find('+') {
-        &version[..pos]
-    } else {
-        version
+fn bump_component(current: &str, component: &str) -> Result<String, String> {
+    let parsed = parse_version(current)?;
+    let [major, minor, patch] = release_triple(&parsed);
+    let base = [major, minor, patch];
+    let suffixed = parsed.pre.is_some() || parsed.post.is_some() || parsed.dev.is_some();
+    // The next number of a prerelease phase: continues the current one, otherwise starts at 1
+    let next_pre = |phase: PreRelease| match parsed.pre {
+        Some((current, n)) if current == phase => Some((phase, n + 1)),
+        _ => Some((phase, 1)),
     };
 
-    let mut parsed = ParsedVersion::default();
-
-    // Find dev suffix
-    let (version, dev) = if let Some(pos) = version.find(".dev") {
-        let dev_part = &version[pos + 4..];
-        let dev_num: u32 = dev_part.parse().unwrap_or(0);
-        (&version[..pos], Some(dev_num))
-    } else if let Some(pos) = version.find("dev") {
-        let dev_part = &version[pos + 3..];
-        let dev_num: u32 = dev_part.parse().unwrap_or(0);
-        (&version[..pos], Some(dev_num))
-    } else {
-        (version, None)
-    };
-    parsed.dev = dev;
-
-    // Find post suffix
-    let (version, post) = if let Some(pos) = version.find(".post") {
-        let post_part = &version[pos + 5..];
-        let post_num: u32 = post_part.parse().unwrap_or(0);
-        (&version[..pos], Some(post_num))
-    } else if let Some(pos) = version.find("post") {
-        let post_part = &version[pos + 4..];
-        let post_num: u32 = post_part.parse().unwrap_or(0);
-        (&version[..pos], Some(post_num))
-    } else {
-        (version, None)
-    };
-    parsed.post = post;
-
-    // Find prerelease suffix (alpha, beta, rc, a, b, c)
-    let prerelease_markers = [
-        ("alpha", "alpha"),
-        ("beta", "beta"),
-        ("preview", "rc"),
-        ("rc", "rc"),
-        ("a", "alpha"),
-        ("b", "beta"),
-        ("c", "rc"),
-    ];
-
-    let mut release = version;
-    for (marker, kind) in prerelease_markers {
-        if let Some(pos) = version.find(marker) {
-            let before = &version[..pos];
-            // Make sure it's at a valid position (after a digit or dot)
-            if before.is_empty() || (!before.ends_with('.') && !before.chars().last().unwrap().is_ascii_digit()) {
-                continue;
-            }
-            let after = &version[pos + marker.len()..];
-            let num: u32 = after
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect::<String>()
-                .parse()
-                .unwrap_or(0);
-            parsed.prerelease = Some((kind.to_string(), num));
-            release = before;
-            break;
+    let (release, pre, post, dev) = match component {
+        "major" => ([major + 1, 0, 0], None, None, None),
+        "minor" => ([major, minor + 1, 0], None, None, None),
+        // If we have a prerelease, just drop it (1.0.0a1 -> 1.0.0)
+        "patch" if suffixed => (base, None, None, None),
+        "patch" => ([major, minor, patch + 1], None, None, None),
+        // Drop all prerelease/post/dev suffixes
+        "release" => (base, None, None, None),
+        "alpha" => (base, next_pre(PreRelease::Alpha), None, None),
+        "beta" => (base, next_pre(PreRelease::Beta), None, None),
+        "rc" => (base, next_pre(PreRelease::Rc), None, None),
+        "post" => (base, parsed.pre, Some(parsed.post.map_or(1, |n| n + 1)), None),
+        "dev" => (base, parsed.pre, parsed.post, Some(parsed.dev.map_or(1, |n| n + 1))),
+        _ => {
+            return Err(format!(
+                "Invalid component: {component}. Use major, minor, patch, release, alpha, beta, rc, post, or dev"
+            ));
         }
-    }
-
-    // Also handle JS-style prerelease (1.0.0-alpha.1)
-    let release = if let Some(pos) = release.find('-') {
-        let pre_part = &release[pos + 1..];
-        if pre_part.starts_with("alpha") {
-            let num_part = pre_part.strip_prefix("alpha").unwrap_or("").trim_start_matches('.');
-            let num: u32 = num_part.parse().unwrap_or(0);
-            parsed.prerelease = Some(("alpha".to_string(), num));
-        } else if pre_part.starts_with("beta") {
-            let num_part = pre_part.strip_prefix("beta").unwrap_or("").trim_start_matches('.');
-            let num: u32 = num_part.parse().unwrap_or(0);
-            parsed.prerelease = Some(("beta".to_string(), num));
-        } else if pre_part.starts_with("rc") {
-            let num_part = pre_part.strip_prefix("rc").unwrap_or("").trim_start_matches('.');
-            let num: u32 = num_part.parse().unwrap_or(0);
-            parsed.prerelease = Some(("rc".to_string(), num));
-        }
-        &release[..pos]
-    } else {
-        release
     };
+    let bumped = Pep440Version { epoch: parsed.epoch, release: release.to_vec(), pre, post, dev, local: Vec::new() };
+    Ok(bumped.to_string())
+}
 
-    // Parse major.minor.patch
-    let parts: Vec<&str> = release.split('.').collect();
-    if parts.is_empty() {
-        return Err(format!("Invalid version format: {version}"));
-    }
+/// Parse a version to bump, with the same PEP 440 parser that validates versions
+/// (semver-style prereleases such as `1.0.0-alpha.1` are read as `1.0.0a1`)
+fn parse_version(version: &str) -> Result<Pep440Version, String> {
+    version.trim().parse()
+}
+
+/// The major, minor and patch numbers of a version, with missing ones as 0
+fn release_triple(version: &Pep440Version) -> [u64; 3] {
+    let part = |i: usize| version.release.get(i).copied().unwrap_or(0);
+    [part(0), part(1), part(2)]
+}
 
-    parsed.major = parts[0]
-        .parse()
-        .map_err(|_| format!("Invalid major version: {}", parts[0]))?;
-    parsed.minor = parts.get(1).unwrap_or(&"0")
-        .parse()
-        .map_err(|_| format!("Invalid minor version: {}", parts.get(1).unwrap_or(&"0")))?;
-    parsed.patch = parts.get(2).unwrap_or(&"0")
-        .parse()
-        .map_err(|_| format!("Invalid patch version: {}", parts.get(2).unwrap_or(&"0")))?;
+/// Whether a file is large enough to be processed line-by-line
+fn is_large_file(fs: &dyn Vfs, path: &Path) -> bool {
+    fs.file_size(path).is_some_and(|len| len > STREAMING_THRESHOLD)
+}
 
-    Ok(parsed)
+/// Strip the line terminator (`\n` or `\r\n`) from a line read with `read_line`
+fn strip_line_ending(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
 }
 
 fn collect_file_changes(
+    fs: &dyn Vfs,
     path: &Path,
     old_version: &str,
     new_version: &str,
     context_lines: usize,
+    file_config: &FileConfig,
+    buffers: &mut BTreeMap<PathBuf, FileBuffer>,
 ) -> Result<Vec<ProposedChange>, String> {
-    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
-    let lines: Vec<&str> = content.lines().collect();
+    if let Some(preset) = file_config.preset {
+        let buffer = buffer_for(fs, path, buffers)?;
+        let edits = preset.propose(file_config, buffer.lines(), old_version, new_version);
+        return Ok(edits
+            .into_iter()
+            .map(|(line_idx, new_line)| make_change(buffer, line_idx, new_line, context_lines))
+            .collect());
+    }
 
-    let occurrences: Vec<usize> = lines
-        .iter()
-        .enumerate()
-        .filter(|(_, line)| line.contains(old_version))
-        .map(|(i, _)| i)
-        .collect();
+    let options = file_config.replace_options();
+    let (search, replace) = options.render(old_version, new_version);
+    check_line_spans(&search, &replace).map_err(|e| format!("{}: {e}", path.display()))?;
 
-    if occurrences.is_empty() {
-        return Err(format!(
-            "Version '{}' not found in {}",
-            old_version,
-            pretty_path(path)
-        ));
+    // Restricted regions and multi-line searches need the whole file, so they're never streamed
+    if !options.is_restricted() && !search.contains('\n') && is_large_file(fs, path) {
+        return collect_file_changes_streaming(fs, path, &search, &replace, context_lines);
     }
 
-    let mut changes = Vec::new();
+    let buffer = buffer_for(fs, path, buffers)?;
+    let allowed = options.allowed_lines(buffer.lines()).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(collect_buffer_changes(buffer, &search, &replace, allowed.as_deref(), context_lines))
+}
 
-    for &line_idx in &occurrences {
-        let start = line_idx.saturating_sub(context_lines);
-        let end = (line_idx + context_lines + 1).min(lines.len());
+/// Get the in-memory buffer for a file, reading it on first use
+fn buffer_for<'a>(
+    fs: &dyn Vfs,
+    path: &Path,
+    buffers: &'a mut BTreeMap<PathBuf, FileBuffer>,
+) -> Result<&'a mut FileBuffer, String> {
+    Ok(match buffers.entry(path.to_path_buf()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(FileBuffer::read(fs, path)?),
+    })
+}
 
-        let context_before: Vec<String> = lines[start..line_idx]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let context_after: Vec<String> = lines[(line_idx + 1)..end]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+fn collect_buffer_changes(
+    buffer: &FileBuffer,
+    old_version: &str,
+    new_version: &str,
+    allowed: Option<&[bool]>,
+    context_lines: usize,
+) -> Vec<ProposedChange> {
+    replace_lines(buffer.lines(), old_version, new_version, allowed, false)
+        .into_iter()
+        .map(|(line_idx, new_line)| make_change(buffer, line_idx, new_line, context_lines))
+        .collect()
+}
 
-        let old_line = lines[line_idx].to_string();
-        let new_line = old_line.replace(old_version, new_version);
+/// Propose the structural update of the manifest field the version was read from
+fn collect_field_changes(
+    fs: &dyn Vfs,
+    field: &VersionField,
+    old_version: &str,
+    new_version: &str,
+    context_lines: usize,
+    buffers: &mut BTreeMap<PathBuf, FileBuffer>,
+) -> Result<Vec<ProposedChange>, String> {
+    let buffer = buffer_for(fs, &field.path, buffers)?;
+    let edits = propose_field_update(&buffer.content(), field, old_version, new_version)?;
+    Ok(edits
+        .into_iter()
+        .map(|(line_idx, new_line)| make_change(buffer, line_idx, new_line, context_lines))
+        .collect())
+}
 
-        changes.push(ProposedChange {
-            path: path.to_path_buf(),
-            line_idx,
-            old_line,
-            new_line,
-            context_before,
-            context_after,
-            selected: true,
+/// Build a proposed change for one line of a buffered file, with surrounding context
+fn make_change(buffer: &FileBuffer, line_idx: usize, new_line: String, context_lines: usize) -> ProposedChange {
+    let lines = buffer.lines();
+    let start = line_idx.saturating_sub(context_lines);
+    let end = (line_idx + context_lines + 1).min(lines.len());
+
+    ProposedChange {
+        path: buffer.path().to_path_buf(),
+        line_idx,
+        old_line: lines[line_idx].clone(),
+        new_line,
+        context_before: lines[start..line_idx].to_vec(),
+        context_after: lines[(line_idx + 1)..end].to_vec(),
+        selected: true,
+    }
+}
+
+/// Scan a large file line-by-line, keeping only a sliding window of context in memory
+fn collect_file_changes_streaming(
+    fs: &dyn Vfs,
+    path: &Path,
+    old_version: &str,
+    new_version: &str,
+    context_lines: usize,
+) -> Result<Vec<ProposedChange>, String> {
+    let mut reader = fs.open(path)?;
+
+    let mut changes: Vec<ProposedChange> = Vec::new();
+    let mut before: VecDeque<String> = VecDeque::with_capacity(context_lines + 1);
+    // Indices into `changes` that are still collecting trailing context
+    let mut pending: Vec<usize> = Vec::new();
+    let mut buf = String::new();
+    let mut line_idx = 0;
+
+    loop {
+        buf.clear();
+        let read = reader
+            .read_line(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        let line = strip_line_ending(&buf);
+
+        pending.retain(|&i| {
+            let change = &mut changes[i];
+            change.context_after.push(line.to_string());
+            change.context_after.len() < context_lines
         });
+
+        if let Some(new_line) = replace_line(line, old_version, new_version) {
+            changes.push(ProposedChange {
+                path: path.to_path_buf(),
+                line_idx,
+                old_line: line.to_string(),
+                new_line,
+                context_before: before.iter().cloned().collect(),
+                context_after: Vec::new(),
+                selected: true,
+            });
+            if context_lines > 0 {
+                pending.push(changes.len() - 1);
+            }
+        }
+
+        if context_lines > 0 {
+            before.push_back(line.to_string());
+            if before.len() > context_lines {
+                before.pop_front();
+            }
+        }
+        line_idx += 1;
     }
 
     Ok(changes)
 }
 
-fn apply_change(change: &ProposedChange) -> Result<(), String> {
-    let original = fs::read_to_string(&change.path)
-        .map_err(|e| format!("Failed to read {}: {e}", pretty_path(&change.path)))?;
-    let lines: Vec<&str> = original.lines().collect();
-
-    let new_content: Vec<String> = lines
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            if i == change.line_idx {
-                change.new_line.clone()
-            } else {
-                (*line).to_string()
+/// Apply all selected changes for a single file, writing it exactly once
+fn apply_changes(
+    fs: &dyn Vfs,
+    project_root: &Path,
+    path: &Path,
+    changes: &[&ProposedChange],
+    buffer: Option<&mut FileBuffer>,
+    color: bool,
+) -> Result<(), String> {
+    match buffer {
+        Some(buffer) => {
+            for change in changes {
+                buffer.set_line(change.line_idx, &change.new_line)?;
             }
-        })
-        .collect();
+            buffer.write(fs)?;
+        }
+        None => apply_changes_streaming(fs, path, changes)?,
+    }
 
-    let new_content = new_content.join("\n");
+    for change in changes {
+        println!("  Updated {}:{}", pretty_path(project_root, path, color), change.line_idx + 1);
+    }
+    Ok(())
+}
 
-    // Preserve trailing newline if original had one
-    let new_content = if original.ends_with('\n') {
-        new_content + "\n"
-    } else {
-        new_content
-    };
+/// Write the original lines of applied changes back, e.g. after an interrupted run
+fn revert_changes(
+    fs: &dyn Vfs,
+    path: &Path,
+    changes: &[&ProposedChange],
+    buffer: Option<&mut FileBuffer>,
+) -> Result<(), String> {
+    match buffer {
+        Some(buffer) => {
+            for change in changes {
+                buffer.set_line(change.line_idx, &change.old_line)?;
+            }
+            buffer.write(fs)
+        }
+        None => {
+            let reverted: Vec<ProposedChange> = changes
+                .iter()
+                .map(|&change| ProposedChange {
+                    old_line: change.new_line.clone(),
+                    new_line: change.old_line.clone(),
+                    ..change.clone()
+                })
+                .collect();
+            apply_changes_streaming(fs, path, &reverted.iter().collect::<Vec<_>>())
+        }
+    }
+}
 
-    fs::write(&change.path, &new_content)
-        .map_err(|e| format!("Failed to write {}: {e}", pretty_path(&change.path)))?;
+/// Rewrite a large file line-by-line, without holding it in memory
+fn apply_changes_streaming(fs: &dyn Vfs, path: &Path, changes: &[&ProposedChange]) -> Result<(), String> {
+    let read_err = |e: std::io::Error| format!("Failed to read {}: {e}", path.display());
+    let write_err = |e: std::io::Error| format!("Failed to write {}: {e}", path.display());
 
-    println!("  Updated {}:{}", pretty_path(&change.path), change.line_idx + 1);
-    Ok(())
+    let replacements: HashMap<usize, &str> = changes
+        .iter()
+        .map(|c| (c.line_idx, c.new_line.as_str()))
+        .collect();
+
+    fs.rewrite(path, &mut |reader, writer| {
+        let mut buf = String::new();
+        let mut line_idx = 0;
+        loop {
+            buf.clear();
+            if reader.read_line(&mut buf).map_err(read_err)? == 0 {
+                break;
+            }
+            match replacements.get(&line_idx) {
+                Some(new_line) => {
+                    let line = strip_line_ending(&buf);
+                    writer.write_all(new_line.as_bytes()).map_err(write_err)?;
+                    writer.write_all(&buf.as_bytes()[line.len()..]).map_err(write_err)?;
+                }
+                None => writer.write_all(buf.as_bytes()).map_err(write_err)?,
+            }
+            line_idx += 1;
+        }
+        Ok(())
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vfs::{MemoryFs, OsFs};
 
     #[test]
     fn test_bump_major() {
@@ -530,6 +1479,36 @@ mod tests {
         assert_eq!(compute_new_version("1.2.3.post1", "dev").unwrap(), "1.2.3.post1.dev1");
     }
 
+    #[test]
+    fn test_written_version_normalizes_python() {
+        let written = |v: &str, kind, on_write| written_version(v.to_string(), kind, on_write);
+        assert_eq!(written("1.0.0-alpha.1", VersionKind::Python, OnWrite::Normalize), "1.0.0a1");
+        assert_eq!(written("1.0.0-alpha.1", VersionKind::Python, OnWrite::Keep), "1.0.0-alpha.1");
+        assert_eq!(written("1.0.0-alpha.1", VersionKind::Semver, OnWrite::Normalize), "1.0.0-alpha.1");
+    }
+
+    #[test]
+    fn test_bump_compound() {
+        assert_eq!(compute_new_version("1.2.5", "minor,alpha").unwrap(), "1.3.0a1");
+        assert_eq!(compute_new_version("1.2.5", "major,rc").unwrap(), "2.0.0rc1");
+        assert_eq!(compute_new_version("1.2.5", "patch,dev").unwrap(), "1.2.6.dev1");
+        assert!(compute_new_version("1.2.5", "alpha,minor").is_err());
+        assert!(compute_new_version("1.2.5", "major,minor").is_err());
+        assert!(is_version_string("1.3.0"));
+        assert!(!is_version_string("minor,alpha"));
+    }
+
+    #[test]
+    fn test_zero_ver_strict() {
+        let bump = |current, target, zero_ver| {
+            compute_new_version(current, &apply_zero_ver(current, target, zero_ver).unwrap()).unwrap()
+        };
+        assert_eq!(bump("0.4.2", "major", ZeroVer::Strict), "0.5.0");
+        assert_eq!(bump("0.4.2", "major,alpha", ZeroVer::Strict), "0.5.0a1");
+        assert_eq!(bump("1.4.2", "major", ZeroVer::Strict), "2.0.0");
+        assert_eq!(bump("0.4.2", "major", ZeroVer::Standard), "1.0.0");
+    }
+
     #[test]
     fn test_bump_js_style_prerelease() {
         // JS style: 1.0.0-alpha.1
@@ -538,15 +1517,62 @@ mod tests {
         assert_eq!(compute_new_version("1.2.3-rc.1", "rc").unwrap(), "1.2.3rc2");
     }
 
+    #[test]
+    fn test_bump_follows_pep440() {
+        // Epochs are kept, local versions dropped
+        assert_eq!(compute_new_version("1!2.0.0", "minor").unwrap(), "1!2.1.0");
+        assert_eq!(compute_new_version("1.2.3+local.1", "patch").unwrap(), "1.2.4");
+        // Alternative spellings parse as in validation
+        assert_eq!(compute_new_version("1.2.3-post2", "post").unwrap(), "1.2.3.post3");
+        assert_eq!(compute_new_version("1.2.3c1", "rc").unwrap(), "1.2.3rc2");
+        assert_eq!(compute_new_version("1.2.3-alpha.2", "release").unwrap(), "1.2.3");
+        assert!(compute_new_version("1.2.3-foo", "patch").is_err());
+        assert_eq!(implied_targets("1.2.3rc1", "1.3.0a1").unwrap(), vec!["minor", "alpha"]);
+    }
+
+    fn check_streaming_matches_in_memory(fs: &dyn Vfs, path: &Path) {
+        fs.write(path, "a\nversion = 1.2.3\nb\nc\r\n1.2.3 1.2.3\nd\n").unwrap();
+
+        let buffer = FileBuffer::read(fs, path).unwrap();
+        let in_memory = collect_buffer_changes(&buffer, "1.2.3", "1.2.4", None, 2);
+        let streaming = collect_file_changes_streaming(fs, path, "1.2.3", "1.2.4", 2).unwrap();
+        assert_eq!(in_memory.len(), 2);
+        for (a, b) in in_memory.iter().zip(&streaming) {
+            assert_eq!(a.line_idx, b.line_idx);
+            assert_eq!(a.new_line, b.new_line);
+            assert_eq!(a.context_before, b.context_before);
+            assert_eq!(a.context_after, b.context_after);
+        }
+
+        let refs: Vec<&ProposedChange> = streaming.iter().collect();
+        apply_changes_streaming(fs, path, &refs).unwrap();
+        assert_eq!(
+            fs.read_to_string(path).unwrap(),
+            "a\nversion = 1.2.4\nb\nc\r\n1.2.4 1.2.4\nd\n"
+        );
+    }
+
+    #[test]
+    fn test_streaming_matches_in_memory() {
+        let path = std::env::temp_dir().join(format!("bver-streaming-{}.txt", std::process::id()));
+        check_streaming_matches_in_memory(&OsFs, &path);
+        std::fs::remove_file(&path).unwrap();
+
+        check_streaming_matches_in_memory(&MemoryFs::new("/project"), Path::new("/project/big.txt"));
+    }
+
     #[test]
     fn test_parse_version() {
         let p = parse_version("1.2.3").unwrap();
-        assert_eq!((p.major, p.minor, p.patch), (1, 2, 3));
-        assert!(p.prerelease.is_none());
+        assert_eq!(release_triple(&p), [1, 2, 3]);
+        assert!(p.pre.is_none());
 
         let p = parse_version("1.2.3a1").unwrap();
-        assert_eq!((p.major, p.minor, p.patch), (1, 2, 3));
-        assert_eq!(p.prerelease, Some(("alpha".to_string(), 1)));
+        assert_eq!(release_triple(&p), [1, 2, 3]);
+        assert_eq!(p.pre, Some((PreRelease::Alpha, 1)));
+
+        let p = parse_version("1.2").unwrap();
+        assert_eq!(release_triple(&p), [1, 2, 0]);
 
         let p = parse_version("1.2.3.post1").unwrap();
         assert_eq!(p.post, Some(1));
@@ -554,4 +1580,315 @@ mod tests {
         let p = parse_version("1.2.3.dev1").unwrap();
         assert_eq!(p.dev, Some(1));
     }
+
+    #[test]
+    fn test_file_filter() {
+        let root = Path::new("/project");
+        let options = BumpOptions {
+            files: vec!["pyproject.toml".to_string(), "docs/*.py".to_string()],
+            exclude: vec!["docs/skip.py".to_string()],
+            ..Default::default()
+        };
+        let mut filter = FileFilter::new(root, &options).unwrap();
+        let any = VersionKind::Any;
+        assert!(filter.allows(&root.join("pyproject.toml"), any));
+        assert!(filter.allows(&root.join("docs/conf.py"), any));
+        assert!(!filter.allows(&root.join("docs/skip.py"), any));
+        assert!(!filter.allows(&root.join("sub/pyproject.toml"), any));
+        assert_eq!(filter.skipped.len(), 2);
+    }
+
+    #[test]
+    fn test_plan_json() {
+        let root = Path::new("/project");
+        let change = |path: &str, line_idx| ProposedChange {
+            path: root.join(path),
+            line_idx,
+            old_line: "1.2.3".to_string(),
+            new_line: "1.2.4".to_string(),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            selected: true,
+        };
+        let skipped = BTreeSet::from([root.join("docs/CHANGELOG.md")]);
+        let plan = plan_json(root, "1.2.3", "1.2.4", &[change("a/b.txt", 4)], &skipped);
+        assert_eq!(
+            plan,
+            serde_json::json!({
+                "current-version": "1.2.3",
+                "new-version": "1.2.4",
+                "changes": [{"path": "a/b.txt", "line": 5, "old": "1.2.3", "new": "1.2.4"}],
+                "skipped": ["docs/CHANGELOG.md"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let root = Path::new("/project");
+        let lines = ["a", "v1.2.3", "b", "c", "v1.2.3", "d", "e", "f", "g", "v1.2.3"];
+        let change = |path: &str, line_idx: usize| ProposedChange {
+            path: root.join(path),
+            line_idx,
+            old_line: lines[line_idx].to_string(),
+            new_line: lines[line_idx].replace("1.2.3", "1.2.4"),
+            context_before: lines[line_idx.saturating_sub(2)..line_idx].iter().map(|l| l.to_string()).collect(),
+            context_after: lines[line_idx + 1..(line_idx + 3).min(lines.len())].iter().map(|l| l.to_string()).collect(),
+            selected: true,
+        };
+        // Hunks whose context touches are merged
+        let changes = [change("x.txt", 1), change("x.txt", 4), change("x.txt", 9), change("y.txt", 1)];
+        let content = format!("{}\n", lines.join("\n"));
+        let fs = MemoryFs::new(root).with_file(root.join("x.txt"), &content).with_file(root.join("y.txt"), &content);
+        let diff = unified_diff(&fs, root, &changes).unwrap();
+        let expected = "\
+diff --git a/x.txt b/x.txt
+--- a/x.txt
++++ b/x.txt
+@@ -1,10 +1,10 @@
+ a
+-v1.2.3
++v1.2.4
+ b
+ c
+-v1.2.3
++v1.2.4
+ d
+ e
+ f
+ g
+-v1.2.3
++v1.2.4
+diff --git a/y.txt b/y.txt
+--- a/y.txt
++++ b/y.txt
+@@ -1,4 +1,4 @@
+ a
+-v1.2.3
++v1.2.4
+ b
+ c
+";
+        assert_eq!(diff, expected);
+    }
+
+    #[test]
+    fn test_unified_diff_applies() {
+        let root = std::env::temp_dir().join(format!("bver-patch-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let files = [("crlf.txt", "a\r\nv1.2.3\r\nb\r\n"), ("tail.txt", "a\nb\nv1.2.3")];
+        let mut changes = Vec::new();
+        for (name, content) in files {
+            std::fs::write(root.join(name), content).unwrap();
+            let buffer = FileBuffer::from_content(&root.join(name), content);
+            changes.extend(collect_buffer_changes(&buffer, "1.2.3", "1.2.4", None, 2));
+        }
+        let diff = unified_diff(&OsFs, &root, &changes).unwrap();
+        assert!(diff.ends_with("-v1.2.3\n\\ No newline at end of file\n+v1.2.4\n\\ No newline at end of file\n"));
+
+        std::fs::write(root.join("bump.patch"), &diff).unwrap();
+        let applied = Command::new("git").args(["apply", "bump.patch"]).current_dir(&root).output().unwrap();
+        assert!(applied.status.success(), "{}", String::from_utf8_lossy(&applied.stderr));
+        assert_eq!(std::fs::read_to_string(root.join("crlf.txt")).unwrap(), "a\r\nv1.2.4\r\nb\r\n");
+        assert_eq!(std::fs::read_to_string(root.join("tail.txt")).unwrap(), "a\nb\nv1.2.4");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_list_files() {
+        let fs = MemoryFs::new("/project")
+            .with_file(
+                "/project/bver.toml",
+                "current-version = \"1.2.3\"\n\n[[file]]\nsrc = \"docs/*.md\"\n\n[[file]]\nsrc = \"missing.txt\"\n\n[[file]]\nsrc = \"Cargo.toml\"\nkind = \"semver\"\n",
+            )
+            .with_file("/project/docs/a.md", "1.2.3\nv1.2.3\n")
+            .with_file("/project/docs/b.md", "nothing\n")
+            .with_file("/project/Cargo.toml", "version = \"1.2.3\"\n");
+        let config = crate::loader::load_config(&fs).unwrap().unwrap();
+        let reports = list_files(&fs, &config).unwrap();
+        let summary: Vec<(&str, Result<usize, String>)> =
+            reports.iter().map(|r| (r.path.as_str(), r.matches.clone())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("docs/a.md", Ok(2)),
+                ("docs/b.md", Ok(0)),
+                ("missing.txt", Err("File not found: missing.txt".to_string())),
+                ("Cargo.toml", Ok(1)),
+            ]
+        );
+        assert_eq!(reports[3].version.as_deref(), Some("1.2.3"));
+    }
+
+    #[test]
+    fn test_read_version_from() {
+        let fs = MemoryFs::new("/project")
+            .with_file(
+                "/project/pyproject.toml",
+                "[project]\nversion = \"1.2.4\"\n\n[tool.bver]\ncurrent-version = \"1.2.3\"\n\n[[tool.bver.file]]\nsrc = \"docs/conf.py\"\nsearch = \"release = '{current-version}'\"\n",
+            )
+            .with_file("/project/docs/conf.py", "version = '1.2'\nrelease = '1.2.3rc1'\n");
+        let config = crate::loader::load_config(&fs).unwrap().unwrap();
+        assert_eq!(read_version_from(&fs, &config, Path::new("docs/conf.py")).unwrap(), "1.2.3rc1");
+        assert!(read_version_from(&fs, &config, Path::new("README.md")).is_err());
+    }
+
+    #[test]
+    fn test_allowed_targets() {
+        let config = Config {
+            allowed_targets: vec!["patch".to_string(), "minor".to_string()],
+            ..Config::default()
+        };
+        let check = |target: &str| {
+            let target = BumpTarget::Auto(target.to_string());
+            let new = new_version(&config, "1.2.3", &target).unwrap();
+            check_allowed_targets(&config, "1.2.3", &new, &target)
+        };
+        assert!(check("minor").is_ok());
+        assert!(check("1.2.4").is_ok());
+        assert!(check("major").unwrap_err().contains("'major'"));
+        assert!(check("minor,alpha").is_err());
+        assert!(check("2.0.0").unwrap_err().contains("1.2.3 -> 2.0.0"));
+        assert_eq!(implied_targets("1.3.0rc2", "1.3.0").unwrap(), vec!["release"]);
+        assert_eq!(implied_targets("1.3.0", "1.2.5").unwrap(), vec!["downgrade"]);
+        assert!(check("1.2.2").unwrap_err().contains("'downgrade'"));
+        assert!(is_major_bump("0.9.1", "v1.0.0") && !is_major_bump("1.2.3", "1.3.0a1"));
+    }
+
+    #[test]
+    fn test_version_bounds() {
+        let config = Config {
+            min_version: Some("2.0.0".to_string()),
+            max_version: Some("3.0.0".to_string()),
+            ..Config::default()
+        };
+        assert!(check_version_bounds(&config, "2.5.1").is_ok());
+        assert!(check_version_bounds(&config, "3.0.0").is_err());
+        // Prereleases are ordered before their release, so 3.0.0-rc.1 is still below 3.0.0
+        assert!(check_version_bounds(&config, "2.1.0-rc.1").is_ok());
+        assert!(check_version_bounds(&config, "3.0.0-rc.1").is_ok());
+        assert!(check_version_bounds(&config, "2.0.0-rc.1").is_err());
+        let npm = Config {
+            default_kind: VersionKind::Semver,
+            min_version: Some("2.0.0".to_string()),
+            max_version: Some("3.0.0".to_string()),
+            ..Config::default()
+        };
+        assert!(check_version_bounds(&npm, "2.1.0-rc.1").is_ok());
+        assert!(check_version_bounds(&npm, "3.0.0").is_err());
+        assert!(check_version_bounds(&config, "1.9.9").unwrap_err().contains(">=2.0.0, <3.0.0"));
+        assert!(check_version_bounds(&Config::default(), "0.1.0").is_ok());
+    }
+
+    #[test]
+    fn test_check_forward() {
+        let config = Config::default();
+        assert!(check_forward(&config, "1.2.0", "1.2.1").is_ok());
+        assert!(check_forward(&config, "1.2.0", "0.9.0").unwrap_err().contains("older"));
+        assert!(check_forward(&config, "1.2.0", "1.2.0rc1").is_err());
+        assert!(check_forward(&config, "1.2", "1.2.0").unwrap_err().contains("the same"));
+        assert!(check_forward(&config, "2024.01", "nightly").is_ok());
+    }
+
+    #[test]
+    fn test_revert_changes() {
+        let path = Path::new("/project/a.txt");
+        let fs = MemoryFs::new("/project").with_file(path, "name\nversion = 1.2.3\n");
+        let mut buffer = FileBuffer::read(&fs, path).unwrap();
+        let change = make_change(&buffer, 1, "version = 1.2.4".to_string(), 0);
+        apply_changes(&fs, Path::new("/project"), path, &[&change], Some(&mut buffer), false).unwrap();
+        assert_eq!(fs.file(path).unwrap(), "name\nversion = 1.2.4\n");
+
+        revert_changes(&fs, path, &[&change], Some(&mut buffer)).unwrap();
+        assert_eq!(fs.file(path).unwrap(), "name\nversion = 1.2.3\n");
+        // Files too large for memory are reverted line-by-line
+        apply_changes_streaming(&fs, path, &[&change]).unwrap();
+        revert_changes(&fs, path, &[&change], None).unwrap();
+        assert_eq!(fs.file(path).unwrap(), "name\nversion = 1.2.3\n");
+    }
+
+    #[test]
+    fn test_per_file_overrides() {
+        let fs = MemoryFs::new("/project")
+            .with_file(
+                "/project/bver.toml",
+                "current-version = \"1.2.3.post1\"\ncontext-lines = 1\n\n[[file]]\nsrc = \"a.txt\"\n\n[[file]]\nsrc = \"b.txt\"\nkind = \"semver\"\ncontext-lines = 0\non-invalid-version = \"cast\"\nallow-lossy = true\n",
+            )
+            .with_file("/project/a.txt", "a\n1.2.3.post1\nb\n")
+            .with_file("/project/b.txt", "a\n1.2.3\nb\n");
+        let config = crate::loader::load_config(&fs).unwrap().unwrap();
+        let options = BumpOptions::default();
+        let scan = Scan {
+            fs: &fs,
+            config: &config,
+            options: &options,
+            project_root: Path::new("/project"),
+            current_version: "1.2.3.post1",
+            new_version: "1.2.4",
+            context_lines: 1,
+        };
+        let mut filter = FileFilter::new(Path::new("/project"), &options).unwrap();
+        let (changes, _) = scan
+            .collect(&mut filter, None, &mut BTreeMap::new(), &mut ScanProgress::new(false))
+            .unwrap();
+        let context: Vec<(&str, usize)> =
+            changes.iter().map(|c| (c.new_line.as_str(), c.context_before.len())).collect();
+        assert_eq!(context, vec![("1.2.4", 1), ("1.2.4", 0)]);
+    }
+
+    #[test]
+    fn test_synced_manifest_keeps_dependency_pins() {
+        let fs = MemoryFs::new("/project").with_file(
+            "/project/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"1.2.3\"\n\n[dependencies]\nfoo = \"1.2.3\"\n\n[[package.metadata.bver.file]]\nsrc = \"Cargo.toml\"\n",
+        );
+        let config = crate::loader::load_config(&fs).unwrap().unwrap();
+        let options = BumpOptions::default();
+        let scan = Scan {
+            fs: &fs,
+            config: &config,
+            options: &options,
+            project_root: Path::new("/project"),
+            current_version: "1.2.3",
+            new_version: "1.2.4",
+            context_lines: 0,
+        };
+        let mut filter = FileFilter::new(Path::new("/project"), &options).unwrap();
+        let (changes, _) = scan
+            .collect(&mut filter, None, &mut BTreeMap::new(), &mut ScanProgress::new(false))
+            .unwrap();
+        let lines: Vec<(usize, &str)> = changes.iter().map(|c| (c.line_idx, c.new_line.as_str())).collect();
+        assert_eq!(lines, vec![(2, "version = \"1.2.4\"")]);
+    }
+
+    #[test]
+    fn test_protect_major_patch() {
+        let fs = MemoryFs::new("/project")
+            .with_file("/project/bver.toml", "current-version = \"1.2.3\"\nprotect-major = true\n\n[[file]]\nsrc = \"a.txt\"\n")
+            .with_file("/project/a.txt", "1.2.3\n");
+        let config = crate::loader::load_config(&fs).unwrap().unwrap();
+        let major = BumpTarget::Auto("major".to_string());
+        let mut options = BumpOptions {
+            emit_patch: Some(PathBuf::from("/project/bump.patch")),
+            ..Default::default()
+        };
+        assert!(bump_version(&fs, &config, &major, &options).unwrap_err().contains("--confirm-major"));
+        assert_eq!(fs.file(Path::new("/project/bump.patch")), None);
+        // Minor bumps and confirmed major bumps go through
+        bump_version(&fs, &config, &BumpTarget::Auto("minor".to_string()), &options).unwrap();
+        options.confirm_major = true;
+        bump_version(&fs, &config, &major, &options).unwrap();
+        assert!(fs.file(Path::new("/project/bump.patch")).unwrap().contains("+2.0.0"));
+    }
+
+    #[test]
+    fn test_only_kind_filter() {
+        let options = BumpOptions {
+            only_kind: Some(VersionKind::Python),
+            ..Default::default()
+        };
+        let mut filter = FileFilter::new(Path::new("/project"), &options).unwrap();
+        assert!(filter.allows(Path::new("/project/pyproject.toml"), VersionKind::Python));
+        assert!(!filter.allows(Path::new("/project/package.json"), VersionKind::Semver));
+    }
 }
@@ -1,156 +1,381 @@
-use crate::schema::FileKind;
+use serde::{Deserialize, Serialize};
+
+use crate::kind::VersionKind;
+use crate::pep440::{Pep440Version, PreRelease};
+use crate::version::validate_r;
 
 /// Cast a version string to the target kind, potentially losing information.
 /// Returns the casted version string or an error if casting is not possible.
-pub fn cast_version(version: &str, target_kind: FileKind) -> Result<String, String> {
-    match target_kind {
-        FileKind::Any => Ok(version.to_string()),
-        FileKind::Simple => cast_to_simple(version),
-        FileKind::Python => cast_to_python(version),
-        FileKind::Semver => cast_to_semver(version),
-    }
+pub fn cast_version(version: &str, target_kind: VersionKind) -> Result<String, String> {
+    (target_kind.spec().cast)(version)
 }
 
-/// Cast any version to simple semver (major.minor.patch).
-/// Strips pre-release, post-release, dev, local, and epoch information.
-fn cast_to_simple(version: &str) -> Result<String, String> {
-    let version = version.to_lowercase();
+/// How a cast writes the segments after the release (`[cast.<kind>]`, or `cast` on a `[[file]]`
+/// entry), as templates. An empty template drops the segment; segments without one are
+/// written the way the kind's own cast writes them.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CastRules {
+    /// Prereleases, with `{label}` (`alpha`/`beta`/`rc`), `{short}` (`a`/`b`/`rc`) and `{n}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<String>,
+    /// Post releases, with `{n}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<String>,
+    /// Dev releases, with `{n}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev: Option<String>,
+    /// Local versions, with `{local}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<String>,
+}
 
-    // Remove epoch (e.g., "1!1.0" -> "1.0")
-    let version = if let Some(pos) = version.find('!') {
-        &version[pos + 1..]
-    } else {
-        version.as_str()
-    };
+impl CastRules {
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_none() && self.post.is_none() && self.dev.is_none() && self.local.is_none()
+    }
 
-    // Remove local version (e.g., "1.0+local" -> "1.0")
-    let version = if let Some(pos) = version.find('+') {
-        &version[..pos]
-    } else {
-        version
+    /// These rules, with the segments they leave open taken from `fallback`
+    pub fn or(&self, fallback: Option<&CastRules>) -> CastRules {
+        let Some(fallback) = fallback else {
+            return self.clone();
+        };
+        CastRules {
+            pre: self.pre.clone().or_else(|| fallback.pre.clone()),
+            post: self.post.clone().or_else(|| fallback.post.clone()),
+            dev: self.dev.clone().or_else(|| fallback.dev.clone()),
+            local: self.local.clone().or_else(|| fallback.local.clone()),
+        }
+    }
+
+    /// The version without the segments these rules write, i.e. what a cast with them
+    /// must keep to be lossless
+    pub fn strip(&self, version: &str) -> String {
+        let Ok(mut parsed) = version.parse::<Pep440Version>() else {
+            return version.to_string();
+        };
+        if self.pre.is_some() {
+            parsed.pre = None;
+        }
+        if self.post.is_some() {
+            parsed.post = None;
+        }
+        if self.dev.is_some() {
+            parsed.dev = None;
+        }
+        if self.local.is_some() {
+            parsed.local.clear();
+        }
+        parsed.to_string()
+    }
+}
+
+/// Cast a version to the target kind, writing its segments with `rules`
+/// (e.g., `post = "-post.{n}"` casts 1.2.3.post1 to 1.2.3-post.1 for semver)
+pub fn cast_with_rules(version: &str, target_kind: VersionKind, rules: &CastRules) -> Result<String, String> {
+    let parsed = match version.parse::<Pep440Version>() {
+        Ok(parsed) if !rules.is_empty() => parsed,
+        _ => return cast_version(version, target_kind),
+    };
+    let release = Pep440Version {
+        pre: None,
+        post: None,
+        dev: None,
+        local: Vec::new(),
+        ..parsed.clone()
     };
+    let base = cast_version(&release.to_string(), target_kind)?;
+    // How the kind's own cast writes a single segment, as what it adds to the release
+    let default = |segment: Pep440Version| -> Result<String, String> {
+        let casted = cast_version(&segment.to_string(), target_kind)?;
+        Ok(casted.strip_prefix(base.as_str()).unwrap_or_default().to_string())
+    };
+
+    let mut casted = base.clone();
+    if let Some((phase, n)) = parsed.pre {
+        casted += &match &rules.pre {
+            Some(template) => template
+                .replace("{label}", prerelease_label(phase))
+                .replace("{short}", &phase.to_string())
+                .replace("{n}", &n.to_string()),
+            None => default(Pep440Version { pre: parsed.pre, ..release.clone() })?,
+        };
+    }
+    if let Some(n) = parsed.post {
+        casted += &match &rules.post {
+            Some(template) => template.replace("{n}", &n.to_string()),
+            None => default(Pep440Version { post: parsed.post, ..release.clone() })?,
+        };
+    }
+    if let Some(n) = parsed.dev {
+        casted += &match &rules.dev {
+            Some(template) => template.replace("{n}", &n.to_string()),
+            None => default(Pep440Version { dev: parsed.dev, ..release.clone() })?,
+        };
+    }
+    if !parsed.local.is_empty() {
+        casted += &match &rules.local {
+            Some(template) => template.replace("{local}", &parsed.local.join(".")),
+            None => default(Pep440Version { local: parsed.local.clone(), ..release.clone() })?,
+        };
+    }
+    Ok(casted)
+}
 
-    // Find where the release version ends (before any pre/post/dev markers)
-    let release_end = find_release_end(version);
-    let release = &version[..release_end];
+fn prerelease_label(phase: PreRelease) -> &'static str {
+    match phase {
+        PreRelease::Alpha => "alpha",
+        PreRelease::Beta => "beta",
+        PreRelease::Rc => "rc",
+    }
+}
 
-    // Parse the release parts
-    let parts: Vec<&str> = release.split('.').collect();
+/// Describe the parts of `original` that are missing from its cast `casted`
+/// (e.g., 1.2.3.post1+abc -> 1.2.3 loses the post-release '.post1' and local version '+abc').
+/// Padding (1.2 -> 1.2.0) and respelling (1.2.3a1 -> 1.2.3-alpha.1) lose nothing.
+pub fn lost_information(original: &str, casted: &str) -> Vec<String> {
+    let mut lost = Vec::new();
+    match (original.parse::<Pep440Version>(), casted.parse::<Pep440Version>()) {
+        (Ok(original), Ok(casted)) => {
+            if original.epoch != casted.epoch {
+                lost.push(format!("epoch '{}!'", original.epoch));
+            }
+            let significant = |v: &Pep440Version| v.release.iter().rposition(|&n| n != 0).map_or(0, |i| i + 1);
+            if significant(&original) > casted.release.len() {
+                let dropped: Vec<String> = original.release[casted.release.len()..].iter().map(u64::to_string).collect();
+                lost.push(format!("release segment(s) '.{}'", dropped.join(".")));
+            }
+            if let Some((phase, n)) = original.pre
+                && casted.pre != original.pre
+            {
+                lost.push(format!("pre-release '{phase}{n}'"));
+            }
+            if let Some(n) = original.post
+                && casted.post != original.post
+            {
+                lost.push(format!("post-release '.post{n}'"));
+            }
+            if let Some(n) = original.dev
+                && casted.dev != original.dev
+                && casted.release.get(3) != Some(&r_dev_component(n))
+            {
+                lost.push(format!("dev release '.dev{n}'"));
+            }
+            if !original.local.is_empty() && casted.local != original.local {
+                lost.push(format!("local version '+{}'", original.local.join(".")));
+            }
+        }
+        _ => {
+            if let Ok(original) = semver::Version::parse(original) {
+                if !original.pre.is_empty() && !casted.contains(original.pre.as_str()) {
+                    lost.push(format!("pre-release '-{}'", original.pre));
+                }
+                if !original.build.is_empty() && !casted.contains(original.build.as_str()) {
+                    lost.push(format!("build metadata '+{}'", original.build));
+                }
+            }
+        }
+    }
+    lost
+}
 
-    if parts.is_empty() {
-        return Err(format!("Cannot cast '{version}' to simple version: no version parts found"));
+/// Re-render a version's prerelease with a style template, where `{label}` is `alpha`/`beta`/`rc`,
+/// `{short}` is `a`/`b`/`rc` and `{n}` is the prerelease number
+/// (e.g., `-{label}.{n}` renders 1.2.3a1 as 1.2.3-alpha.1, `.{label}{n}` as 1.2.3.alpha1).
+/// Versions without a prerelease, or with post/dev/local segments, are returned unchanged.
+pub fn apply_prerelease_style(version: &str, style: &str) -> String {
+    let Ok(parsed) = version.parse::<Pep440Version>() else {
+        return version.to_string();
+    };
+    let Some((phase, n)) = parsed.pre else {
+        return version.to_string();
+    };
+    if parsed.post.is_some() || parsed.dev.is_some() || !parsed.local.is_empty() {
+        return version.to_string();
     }
 
-    // Validate all parts are numeric
-    for part in &parts {
-        if part.parse::<u32>().is_err() {
-            return Err(format!("Cannot cast '{version}' to simple version: invalid part '{part}'"));
+    let label = prerelease_label(phase);
+    let release: Vec<String> = parsed.release.iter().map(u64::to_string).collect();
+    let epoch = if parsed.epoch == 0 { String::new() } else { format!("{}!", parsed.epoch) };
+    let pre = style
+        .replace("{label}", label)
+        .replace("{short}", &phase.to_string())
+        .replace("{n}", &n.to_string());
+    format!("{epoch}{}{pre}", release.join("."))
+}
+
+/// Cast any version to RubyGems format.
+/// Prereleases become a lettered segment (e.g., 1.2.3a1 -> 1.2.3.alpha1, 1.2.3-rc.1 -> 1.2.3.rc1)
+/// and dev releases become `.preN`. Post releases and local versions are stripped.
+pub(crate) fn cast_to_rubygems(version: &str) -> Result<String, String> {
+    let base = cast_to_simple(version)?;
+    let version = version.to_lowercase();
+    let version = version.split_once('!').map_or(version.as_str(), |(_, v)| v);
+    let version = version.split('+').next().unwrap_or(version);
+    let suffix = &version[find_release_end(version)..];
+
+    let labels = [
+        ("alpha", "alpha"),
+        ("beta", "beta"),
+        ("preview", "rc"),
+        ("rc", "rc"),
+        ("pre", "pre"),
+        ("a", "alpha"),
+        ("b", "beta"),
+        ("c", "rc"),
+    ];
+    let pre = suffix.trim_start_matches(['-', '.']);
+    for (marker, label) in labels {
+        if let Some(rest) = pre.strip_prefix(marker) {
+            let num: String = rest
+                .trim_start_matches(['.', '-'])
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            return Ok(format!("{base}.{label}{num}"));
         }
     }
 
-    // Pad or truncate to exactly 3 parts
-    let major = parts.first().unwrap_or(&"0");
-    let minor = parts.get(1).unwrap_or(&"0");
-    let patch = parts.get(2).unwrap_or(&"0");
+    if let Some(pos) = suffix.find("dev") {
+        let num: String = suffix[pos + 3..].chars().take_while(|c| c.is_ascii_digit()).collect();
+        return Ok(format!("{base}.pre{num}"));
+    }
 
-    Ok(format!("{major}.{minor}.{patch}"))
+    Ok(base)
 }
 
-/// Cast any version to PEP 440 format.
-/// Most versions are already valid or can be normalized.
-fn cast_to_python(version: &str) -> Result<String, String> {
-    // Simple semver is valid PEP 440
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.iter().all(|p| p.parse::<u32>().is_ok()) {
+/// The 4th release component R uses for a development version (`.devN` -> `.9000`, `.9001`, ...)
+fn r_dev_component(dev: u64) -> u64 {
+    9000 + dev.saturating_sub(1)
+}
+
+/// Cast any version to an R package version (major.minor.patch).
+/// R versions pass through; dev releases become R's development suffix
+/// (e.g., 1.2.3.dev1 -> 1.2.3.9000), other prerelease and post segments are stripped.
+pub(crate) fn cast_to_r(version: &str) -> Result<String, String> {
+    if validate_r(version).is_ok() {
         return Ok(version.to_string());
     }
+    let base = cast_to_simple(version)?;
+    match version.parse::<Pep440Version>().ok().and_then(|parsed| parsed.dev) {
+        Some(dev) => Ok(format!("{base}.{}", r_dev_component(dev))),
+        None => Ok(base),
+    }
+}
+
+/// Cast any version to a 4-component .NET version (major.minor.build.revision).
+/// Pads missing components with zeros (e.g., 1.2.3 -> 1.2.3.0) and strips everything
+/// after the numeric release, as .NET assembly versions can't express prereleases.
+pub(crate) fn cast_to_dotnet(version: &str) -> Result<String, String> {
+    let simple = cast_to_simple(version)?;
+    let version = version.to_lowercase();
+    let version = version.split_once('!').map_or(version.as_str(), |(_, v)| v);
+    let version = version.split('+').next().unwrap_or(version);
+    let release = &version[..find_release_end(version)];
 
-    // Already a valid Python version (assume it's fine)
-    // The validator will catch any issues
-    Ok(version.to_string())
+    // Keep a 4th numeric component if the source already had one
+    let revision = release.split('.').nth(3).filter(|p| p.parse::<u32>().is_ok()).unwrap_or("0");
+    Ok(format!("{simple}.{revision}"))
 }
 
-/// Cast any version to semver format (used by npm, Cargo, etc.).
-/// Converts Python-style prereleases to semver-style (e.g., 1.2.3a1 -> 1.2.3-alpha.1)
-/// Strips post and dev releases as they're not supported in semver.
-fn cast_to_semver(version: &str) -> Result<String, String> {
+/// Cast any version to a 4-component Haskell PVP version (A.B.C.D).
+/// Pads missing components with zeros (e.g., 1.2.3 -> 1.2.3.0), keeps a 4th numeric component,
+/// and strips everything after the numeric release, as PVP has no prereleases.
+pub(crate) fn cast_to_pvp(version: &str) -> Result<String, String> {
+    let simple = cast_to_simple(version)?;
     let version = version.to_lowercase();
+    let version = version.split_once('!').map_or(version.as_str(), |(_, v)| v);
+    let version = version.split('+').next().unwrap_or(version);
+    let release = &version[..find_release_end(version)];
 
-    // Remove epoch (e.g., "1!1.0" -> "1.0")
-    let version = if let Some(pos) = version.find('!') {
-        &version[pos + 1..]
-    } else {
-        version.as_str()
-    };
+    let fourth = release.split('.').nth(3).filter(|p| p.parse::<u64>().is_ok()).unwrap_or("0");
+    Ok(format!("{simple}.{fourth}"))
+}
 
-    // Remove local version (e.g., "1.0+local" -> "1.0")
-    let version = if let Some(pos) = version.find('+') {
-        &version[..pos]
-    } else {
-        version
-    };
+/// Cast any version to simple semver (major.minor.patch).
+/// Strips pre-release, post-release, dev, local, and epoch information.
+pub(crate) fn cast_to_simple(version: &str) -> Result<String, String> {
+    let [major, minor, patch] = release_triple(version)
+        .map_err(|e| format!("Cannot cast '{version}' to simple version: {e}"))?;
+    Ok(format!("{major}.{minor}.{patch}"))
+}
 
-    // Find where the release version ends
-    let release_end = find_release_end(version);
-    let release = &version[..release_end];
-    let suffix = &version[release_end..];
+/// Cast any version to canonical PEP 440 form
+/// (e.g., 1.2.3-beta.2 -> 1.2.3b2, 1.2.3.RC1 -> 1.2.3rc1, 1.2.3-1 -> 1.2.3.post1).
+pub(crate) fn cast_to_python(version: &str) -> Result<String, String> {
+    version
+        .parse::<Pep440Version>()
+        .map(|parsed| parsed.to_string())
+        .map_err(|e| format!("Cannot cast '{version}' to a Python version: {e}"))
+}
 
-    // Parse the release parts and ensure we have exactly 3
-    let parts: Vec<&str> = release.split('.').collect();
-    if parts.is_empty() {
-        return Err(format!("Cannot cast '{version}' to semver: no version parts found"));
+/// Cast any version to semver format (used by npm, Cargo, etc.).
+/// Converts Python-style prereleases to semver-style (e.g., 1.2.3a1 -> 1.2.3-alpha.1)
+/// Strips epochs, post and dev releases, and local versions as they're not supported in semver.
+pub(crate) fn cast_to_semver(version: &str) -> Result<String, String> {
+    if let Ok(parsed) = semver::Version::parse(version) {
+        return Ok(parsed.to_string());
     }
 
-    for part in &parts {
-        if part.parse::<u32>().is_err() {
-            return Err(format!("Cannot cast '{version}' to semver: invalid part '{part}'"));
-        }
+    let parsed: Pep440Version = version
+        .parse()
+        .map_err(|e| format!("Cannot cast '{version}' to semver: {e}"))?;
+    let mut release = parsed.release.iter().chain(std::iter::repeat(&0));
+    let mut cast = semver::Version::new(*release.next().unwrap(), *release.next().unwrap(), *release.next().unwrap());
+
+    if let Some((phase, n)) = parsed.pre {
+        let label = match phase {
+            PreRelease::Alpha => "alpha",
+            PreRelease::Beta => "beta",
+            PreRelease::Rc => "rc",
+        };
+        cast.pre = semver::Prerelease::new(&format!("{label}.{n}")).map_err(|e| e.to_string())?;
     }
+    Ok(cast.to_string())
+}
 
-    let major = parts.first().unwrap_or(&"0");
-    let minor = parts.get(1).unwrap_or(&"0");
-    let patch = parts.get(2).unwrap_or(&"0");
-    let base = format!("{major}.{minor}.{patch}");
-
-    // Convert Python prerelease to JS format
-    if suffix.is_empty() {
-        return Ok(base);
-    }
-
-    // Strip .post and .dev as they're not supported
-    let suffix = suffix
-        .split(".post")
-        .next()
-        .unwrap_or(suffix)
-        .split(".dev")
-        .next()
-        .unwrap_or(suffix);
-
-    if suffix.is_empty() {
-        return Ok(base);
-    }
-
-    // Convert a1 -> -alpha.1, b1 -> -beta.1, rc1 -> -rc.1
-    let js_prerelease = if let Some(rest) = suffix.strip_prefix("alpha") {
-        format!("-alpha.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix('a') {
-        format!("-alpha.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix("beta") {
-        format!("-beta.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix('b') {
-        format!("-beta.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix("rc") {
-        format!("-rc.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix('c') {
-        format!("-rc.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else if let Some(rest) = suffix.strip_prefix("preview") {
-        format!("-rc.{}", rest.trim_start_matches(|c: char| !c.is_ascii_digit()))
-    } else {
-        // Unknown suffix, strip it
-        return Ok(base);
+/// The first three numeric release components of a PEP 440 or semver version, zero-padded
+fn release_triple(version: &str) -> Result<[u64; 3], String> {
+    if let Ok(parsed) = version.parse::<Pep440Version>() {
+        let mut release = parsed.release.iter().copied().chain(std::iter::repeat(0));
+        return Ok([release.next().unwrap(), release.next().unwrap(), release.next().unwrap()]);
+    }
+    semver::Version::parse(version)
+        .map(|parsed| [parsed.major, parsed.minor, parsed.patch])
+        .map_err(|_| "no numeric release found".to_string())
+}
+
+/// Cast any version to one Cargo can publish: semver without build metadata,
+/// with prerelease numbers split into their own identifier (e.g., 1.2.3a1 -> 1.2.3-alpha.1,
+/// 1.2.3-beta2 -> 1.2.3-beta.2)
+pub(crate) fn cast_to_cargo(version: &str) -> Result<String, String> {
+    let version = version.split('+').next().unwrap_or(version);
+
+    // Keep an existing semver-style prerelease; cast everything else through semver
+    let semver = match version.split_once('-') {
+        Some((release, pre)) if !pre.is_empty() => format!("{}-{}", cast_to_semver(release)?, pre.to_lowercase()),
+        _ => cast_to_semver(version)?,
     };
+    let Some((release, prerelease)) = semver.split_once('-') else {
+        return Ok(semver);
+    };
+
+    let mut identifiers: Vec<&str> = Vec::new();
+    for identifier in prerelease.split('.') {
+        let label = identifier.trim_end_matches(|c: char| c.is_ascii_digit());
+        let number = &identifier[label.len()..];
+        identifiers.push(label);
+        // Numeric identifiers must not have leading zeros
+        let trimmed = number.trim_start_matches('0');
+        identifiers.push(if trimmed.is_empty() && !number.is_empty() { "0" } else { trimmed });
+    }
+    identifiers.retain(|identifier| !identifier.is_empty());
 
-    Ok(format!("{base}{js_prerelease}"))
+    if identifiers.is_empty() {
+        Ok(release.to_string())
+    } else {
+        Ok(format!("{release}-{}", identifiers.join(".")))
+    }
 }
 
 /// Find the end position of the release version (before pre/post/dev markers).
@@ -208,6 +433,92 @@ mod tests {
         assert_eq!(cast_to_simple("1!1.2.3a1.post1.dev1+local").unwrap(), "1.2.3");
     }
 
+    #[test]
+    fn test_cast_to_cargo() {
+        assert_eq!(cast_version("1.2.3a1", VersionKind::Cargo).unwrap(), "1.2.3-alpha.1");
+        assert_eq!(cast_version("1.2.3rc2.post1", VersionKind::Cargo).unwrap(), "1.2.3-rc.2");
+        assert_eq!(cast_version("1.2.3-beta2+build.5", VersionKind::Cargo).unwrap(), "1.2.3-beta.2");
+        assert_eq!(cast_version("1.2.3-alpha.01", VersionKind::Cargo).unwrap(), "1.2.3-alpha.1");
+        assert_eq!(cast_version("1.2", VersionKind::Cargo).unwrap(), "1.2.0");
+    }
+
+    #[test]
+    fn test_cast_with_rules() {
+        let rules = |pre: Option<&str>, post: Option<&str>| CastRules {
+            pre: pre.map(String::from),
+            post: post.map(String::from),
+            ..CastRules::default()
+        };
+        let post = rules(None, Some("-post.{n}"));
+        assert_eq!(cast_with_rules("1.2.3.post1", VersionKind::Semver, &post).unwrap(), "1.2.3-post.1");
+        assert_eq!(cast_with_rules("1.2.3a1+abc", VersionKind::Semver, &post).unwrap(), "1.2.3-alpha.1+abc");
+        let drop_pre = rules(Some(""), None);
+        assert_eq!(cast_with_rules("1.2.3rc2", VersionKind::Dotnet, &drop_pre).unwrap(), "1.2.3.0");
+        assert_eq!(cast_with_rules("1.2.3.dev1", VersionKind::R, &drop_pre).unwrap(), "1.2.3.9000");
+        assert_eq!(drop_pre.strip("1.2.3rc2.post1"), "1.2.3.post1");
+        assert_eq!(drop_pre.or(Some(&post)), rules(Some(""), Some("-post.{n}")));
+    }
+
+    #[test]
+    fn test_lost_information() {
+        assert!(lost_information("1.2", "1.2.0").is_empty());
+        assert!(lost_information("1.2.3a1", "1.2.3-alpha.1").is_empty());
+        assert_eq!(
+            lost_information("1.2.3.post1+abc", "1.2.3"),
+            vec!["post-release '.post1'", "local version '+abc'"]
+        );
+        assert_eq!(lost_information("1.2.3rc1", "1.2.3.0"), vec!["pre-release 'rc1'"]);
+        assert_eq!(lost_information("1.2.3.4", "1.2.3"), vec!["release segment(s) '.4'"]);
+        assert_eq!(lost_information("1.2.3-beta.x+b5", "1.2.3"), vec!["pre-release '-beta.x'", "build metadata '+b5'"]);
+    }
+
+    #[test]
+    fn test_apply_prerelease_style() {
+        assert_eq!(apply_prerelease_style("1.2.3a1", "-{label}.{n}"), "1.2.3-alpha.1");
+        assert_eq!(apply_prerelease_style("1.2.3-beta.2", "{short}{n}"), "1.2.3b2");
+        assert_eq!(apply_prerelease_style("1.2.3rc1", ".{label}{n}"), "1.2.3.rc1");
+        assert_eq!(apply_prerelease_style("1.2.3", "-{label}.{n}"), "1.2.3");
+        assert_eq!(apply_prerelease_style("1.2.3a1.dev1", "-{label}.{n}"), "1.2.3a1.dev1");
+    }
+
+    #[test]
+    fn test_cast_to_dotnet() {
+        assert_eq!(cast_to_dotnet("1.2.3").unwrap(), "1.2.3.0");
+        assert_eq!(cast_to_dotnet("1.2").unwrap(), "1.2.0.0");
+        assert_eq!(cast_to_dotnet("1.2.3.4").unwrap(), "1.2.3.4");
+        assert_eq!(cast_to_dotnet("1.2.3rc1").unwrap(), "1.2.3.0");
+        assert_eq!(cast_to_dotnet("1.2.3-beta.2").unwrap(), "1.2.3.0");
+    }
+
+    #[test]
+    fn test_cast_to_pvp() {
+        assert_eq!(cast_to_pvp("1.2.3").unwrap(), "1.2.3.0");
+        assert_eq!(cast_to_pvp("0.1").unwrap(), "0.1.0.0");
+        assert_eq!(cast_to_pvp("1.2.3.70000").unwrap(), "1.2.3.70000");
+        assert_eq!(cast_to_pvp("1.2.3-beta.2").unwrap(), "1.2.3.0");
+    }
+
+    #[test]
+    fn test_cast_to_rubygems() {
+        assert_eq!(cast_to_rubygems("1.2.3").unwrap(), "1.2.3");
+        assert_eq!(cast_to_rubygems("1.2.3a1").unwrap(), "1.2.3.alpha1");
+        assert_eq!(cast_to_rubygems("1.2.3rc2").unwrap(), "1.2.3.rc2");
+        assert_eq!(cast_to_rubygems("1.2.3-beta.1").unwrap(), "1.2.3.beta1");
+        assert_eq!(cast_to_rubygems("1.2.3.dev4").unwrap(), "1.2.3.pre4");
+        assert_eq!(cast_to_rubygems("1.2.3.post1").unwrap(), "1.2.3");
+    }
+
+    #[test]
+    fn test_cast_to_r() {
+        assert_eq!(cast_to_r("1.2.3").unwrap(), "1.2.3");
+        assert_eq!(cast_to_r("1.2-3").unwrap(), "1.2-3");
+        assert_eq!(cast_to_r("1.2.3.dev1").unwrap(), "1.2.3.9000");
+        assert_eq!(cast_to_r("1.2.3.dev2").unwrap(), "1.2.3.9001");
+        assert_eq!(cast_to_r("1.2.3rc1").unwrap(), "1.2.3");
+        assert!(lost_information("1.2.3.dev1", "1.2.3.9000").is_empty());
+        assert_eq!(lost_information("1.2.3rc1", "1.2.3"), vec!["pre-release 'rc1'"]);
+    }
+
     #[test]
     fn test_cast_to_python() {
         // Simple versions pass through
@@ -215,5 +526,18 @@ mod tests {
 
         // Python versions pass through
         assert_eq!(cast_to_python("1.2.3a1").unwrap(), "1.2.3a1");
+
+        // Other spellings are normalized
+        assert_eq!(cast_to_python("1.2.3-beta.2").unwrap(), "1.2.3b2");
+        assert!(cast_to_python("1.2.3-beta.x").is_err());
+    }
+
+    #[test]
+    fn test_cast_to_semver() {
+        assert_eq!(cast_to_semver("1.2.3-beta.x").unwrap(), "1.2.3-beta.x");
+        assert_eq!(cast_to_semver("1.2.3a1").unwrap(), "1.2.3-alpha.1");
+        assert_eq!(cast_to_semver("1.2RC1.dev3").unwrap(), "1.2.0-rc.1");
+        assert_eq!(cast_to_semver("1!1.2.3.post1+local").unwrap(), "1.2.3");
+        assert!(cast_to_semver("1.2.3abc").is_err());
     }
 }
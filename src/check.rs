@@ -0,0 +1,136 @@
+use regex::Regex;
+
+use crate::bump::list_files;
+use crate::schema::Config;
+use crate::template::render;
+use crate::vfs::Vfs;
+
+/// Placeholders only the git templates support
+const GIT_PLACEHOLDERS: &[&str] = &["{git-sha}", "{branch}", "{author}", "{date}"];
+
+/// A problem found by `bver check-config`
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The (1-based) line of the config file it's about, when known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Every problem with a config, instead of just the first: unknown keys, a current version that's
+/// invalid for its kind, contradictory git actions, templates with unknown placeholders, and files
+/// that can't be found, don't hold the version, or can't take it in their kind
+pub fn check_config(fs: &dyn Vfs, config: &Config) -> Vec<Problem> {
+    let content = config
+        .source
+        .as_ref()
+        .and_then(|source| fs.read_to_string(source.path()).ok())
+        .unwrap_or_default();
+    let line_of = |needle: &str| content.lines().position(|line| line.contains(needle)).map(|idx| idx + 1);
+    let key_line = |key: &str| {
+        let re = Regex::new(&format!(r#"^\s*"?{}"?\s*[=:]"#, regex::escape(key))).unwrap();
+        content.lines().position(|line| re.is_match(line)).map(|idx| idx + 1)
+    };
+
+    let mut problems = Vec::new();
+    let mut report = |line: Option<usize>, message: String| problems.push(Problem { line, message });
+
+    for key in config.unknown.keys() {
+        report(key_line(key), format!("Unknown key '{key}'"));
+    }
+    for key in config.git.unknown.keys() {
+        report(key_line(key), format!("Unknown key '{key}' in the git settings"));
+    }
+    for (idx, file) in config.files.iter().enumerate() {
+        for key in file.unknown.keys() {
+            report(key_line(key), format!("Unknown key '{key}' in [[file]] entry {}", idx + 1));
+        }
+    }
+
+    if let Err(e) = config.validate_current_version() {
+        report(key_line("current-version"), e);
+    }
+    if let Err(e) = config.git.validate() {
+        report(key_line("actions"), format!("Contradictory git actions: {e}"));
+    }
+
+    let git = &config.git;
+    let mut templates: Vec<(&str, &str, &[&str])> = vec![
+        ("tag-template", &git.tag_template, GIT_PLACEHOLDERS),
+        ("commit-template", &git.commit_template, GIT_PLACEHOLDERS),
+        ("branch-template", &git.branch_template, GIT_PLACEHOLDERS),
+    ];
+    for policy in &config.branches {
+        templates.extend(policy.tag_template.as_deref().map(|t| ("tag-template", t, GIT_PLACEHOLDERS)));
+    }
+    for file in &config.files {
+        templates.extend(file.search.as_deref().map(|t| ("search", t, &[] as &[&str])));
+        templates.extend(file.replace.as_deref().map(|t| ("replace", t, &[] as &[&str])));
+        templates.extend(file.post_hook.as_deref().map(|t| ("post-hook", t, &["{file}"] as &[&str])));
+    }
+    for pin in &config.pins {
+        templates.push(("range", &pin.range, &[]));
+    }
+    for (name, template, extra) in templates {
+        for placeholder in unknown_placeholders(template, extra) {
+            report(line_of(template), format!("Unknown placeholder {placeholder} in {name} '{template}'"));
+        }
+    }
+
+    match list_files(fs, config) {
+        Ok(reports) => {
+            for file in reports {
+                match (&file.matches, &file.version) {
+                    (Err(e), _) => report(line_of(&file.path), format!("{}: {e}", file.path)),
+                    (Ok(0), Some(version)) => report(
+                        line_of(&file.path),
+                        format!("{}: version {version} not found (kind: {})", file.path, file.kind.name()),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Err(e) => report(None, e),
+    }
+    problems
+}
+
+/// The `{...}` placeholders of `template` that rendering leaves in place
+fn unknown_placeholders(template: &str, extra: &[&str]) -> Vec<String> {
+    let mut rendered = render(template, "1.2.3", "1.2.4");
+    for placeholder in extra {
+        rendered = rendered.replace(placeholder, "");
+    }
+    let re = Regex::new(r"\{[a-z][a-z-]*\}").unwrap();
+    re.find_iter(&rendered).map(|m| m.as_str().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::load_config;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_check_config() {
+        let fs = MemoryFs::new("/project")
+            .with_file(
+                "/project/bver.toml",
+                "current-version = \"1.2.3\"\ncontext-line = 3\n\n[git]\nactions = [\"tag\"]\ntag-template = \"v{new-verison}\"\n\n[[file]]\nsrc = \"a.txt\"\n\n[[file]]\nsrc = \"b.txt\"\nkinds = \"semver\"\n",
+            )
+            .with_file("/project/a.txt", "1.2.3\n")
+            .with_file("/project/b.txt", "nothing\n");
+        let config = load_config(&fs).unwrap().unwrap();
+        let problems = check_config(&fs, &config);
+        let lines: Vec<(Option<usize>, &str)> = problems.iter().map(|p| (p.line, p.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (Some(2), "Unknown key 'context-line'"),
+                (Some(13), "Unknown key 'kinds' in [[file]] entry 2"),
+                (Some(5), "Contradictory git actions: git action 'tag' requires 'commit'"),
+                (Some(6), "Unknown placeholder {new-verison} in tag-template 'v{new-verison}'"),
+                (Some(12), "b.txt: version 1.2.3 not found (kind: any)"),
+            ]
+        );
+    }
+}
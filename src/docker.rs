@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pep440::Pep440Version;
+use crate::template::render;
+
+/// Which image tags to publish for a version (`[docker]`).
+/// Each entry is a template rendered for the version.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct DockerConfig {
+    #[serde(default = "default_stable_tags")]
+    pub tags: Vec<String>,
+    #[serde(default = "default_prerelease_tags")]
+    pub prerelease_tags: Vec<String>,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self {
+            tags: default_stable_tags(),
+            prerelease_tags: default_prerelease_tags(),
+        }
+    }
+}
+
+fn default_stable_tags() -> Vec<String> {
+    ["{major}", "{major}.{minor}", "{new-version}", "latest"].map(String::from).to_vec()
+}
+
+fn default_prerelease_tags() -> Vec<String> {
+    vec!["{new-version}".to_string()]
+}
+
+/// Whether a version is a prerelease (PEP 440 `a`/`b`/`rc`/`dev`, or a semver `-` suffix)
+fn is_prerelease(version: &str) -> bool {
+    let bare = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    if let Ok(parsed) = semver::Version::parse(bare) {
+        return !parsed.pre.is_empty();
+    }
+    version.parse::<Pep440Version>().is_ok_and(|parsed| parsed.is_prerelease())
+}
+
+/// The image tags for a version, in policy order without duplicates.
+/// Characters docker doesn't allow in tags (e.g. `+`) are replaced with `-`.
+pub fn docker_tags(config: &DockerConfig, version: &str) -> Vec<String> {
+    let templates = if is_prerelease(version) {
+        &config.prerelease_tags
+    } else {
+        &config.tags
+    };
+    let mut tags: Vec<String> = Vec::new();
+    for template in templates {
+        let tag: String = render(template, version, version)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') { c } else { '-' })
+            .collect();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docker_tags() {
+        let config = DockerConfig::default();
+        assert_eq!(docker_tags(&config, "1.2.4"), ["1", "1.2", "1.2.4", "latest"]);
+        assert_eq!(docker_tags(&config, "1.3.0rc1"), ["1.3.0rc1"]);
+        assert_eq!(docker_tags(&config, "1.3.0-beta.2"), ["1.3.0-beta.2"]);
+        assert_eq!(docker_tags(&config, "1.2.4+build5"), ["1", "1.2", "1.2.4-build5", "latest"]);
+
+        let config = DockerConfig {
+            tags: vec!["v{new-version}".to_string(), "stable".to_string()],
+            prerelease_tags: vec!["v{new-version}".to_string(), "next".to_string()],
+        };
+        assert_eq!(docker_tags(&config, "2.0.0a1"), ["v2.0.0a1", "next"]);
+    }
+}
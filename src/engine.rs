@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::buffer::FileBuffer;
+use crate::template::render;
+
+/// How to find a version in text and what to write instead.
+/// `search` and `replace` are templates, like those of a `[[file]]` entry.
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceOptions {
+    /// Text to find (default: `{current-version}`)
+    pub search: Option<String>,
+    /// Replacement text (default: `{new-version}`)
+    pub replace: Option<String>,
+    /// Literal text that must come right before the search text, and is kept in front of the replacement
+    pub prefix: Option<String>,
+    /// Literal text that must come right after the search text, and is kept after the replacement
+    pub suffix: Option<String>,
+    /// Only replace between lines containing `bver:start` and `bver:end`
+    /// (e.g. `<!-- bver:start -->` ... `<!-- bver:end -->`)
+    pub markers: bool,
+    /// Only replace within these lines, e.g. `10..50` (1-based and inclusive; either end may be left out)
+    pub lines: Option<String>,
+    /// Only replace after the first line matching this regex
+    pub after: Option<String>,
+    /// Only replace before the first line matching this regex (after the `after` line, if given)
+    pub before: Option<String>,
+}
+
+impl ReplaceOptions {
+    /// The rendered search and replacement text for a version change
+    pub fn render(&self, old_version: &str, new_version: &str) -> (String, String) {
+        let search = render(self.search.as_deref().unwrap_or("{current-version}"), old_version, new_version);
+        let replace = render(self.replace.as_deref().unwrap_or("{new-version}"), old_version, new_version);
+        let (prefix, suffix) = (self.prefix.as_deref().unwrap_or_default(), self.suffix.as_deref().unwrap_or_default());
+        (format!("{prefix}{search}{suffix}"), format!("{prefix}{replace}{suffix}"))
+    }
+
+    /// Whether only part of a file may be replaced, which takes reading all of it
+    pub fn is_restricted(&self) -> bool {
+        self.markers || self.lines.is_some() || self.after.is_some() || self.before.is_some()
+    }
+
+    /// Which of `lines` may be replaced, given the marker regions, line range and anchors;
+    /// `None` if all of them may
+    pub(crate) fn allowed_lines<S: AsRef<str>>(&self, lines: &[S]) -> Result<Option<Vec<bool>>, String> {
+        if !self.is_restricted() {
+            return Ok(None);
+        }
+        let mut allowed = match self.markers {
+            true => marker_regions(lines),
+            false => vec![true; lines.len()],
+        };
+        if let Some(range) = &self.lines {
+            let (first, last) = parse_line_range(range)?;
+            for (idx, allowed) in allowed.iter_mut().enumerate() {
+                *allowed &= (first..=last).contains(&(idx + 1));
+            }
+        }
+
+        let anchor = |pattern: &str, from: usize| -> Result<Option<usize>, String> {
+            let re = Regex::new(pattern).map_err(|e| format!("Invalid anchor regex '{pattern}': {e}"))?;
+            Ok((from..lines.len()).find(|&idx| re.is_match(lines[idx].as_ref())))
+        };
+        // Without its anchor line, nothing is after it, and everything is before it
+        let start = match &self.after {
+            Some(pattern) => anchor(pattern, 0)?.map_or(lines.len(), |idx| idx + 1),
+            None => 0,
+        };
+        let end = match &self.before {
+            Some(pattern) => anchor(pattern, start)?.unwrap_or(lines.len()),
+            None => lines.len(),
+        };
+        for (idx, allowed) in allowed.iter_mut().enumerate() {
+            *allowed &= (start..end).contains(&idx);
+        }
+        Ok(Some(allowed))
+    }
+}
+
+/// Parse a 1-based, inclusive `first..last` line range, where either end may be left out
+fn parse_line_range(range: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("Invalid line range '{range}', expected e.g. '10..50', '10..' or '..50'");
+    let (first, last) = range.split_once("..").ok_or_else(invalid)?;
+    let bound = |text: &str, default: usize| match text.trim() {
+        "" => Ok(default),
+        text => text.parse::<usize>().map_err(|_| invalid()),
+    };
+    let (first, last) = (bound(first, 1)?, bound(last, usize::MAX)?);
+    if first == 0 || first > last {
+        return Err(invalid());
+    }
+    Ok((first, last))
+}
+
+/// A replacement of one line (0-based `line_idx`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub line_idx: usize,
+    pub old_line: String,
+    pub new_line: String,
+}
+
+/// The rewritten line, if it contains `search`
+pub(crate) fn replace_line(line: &str, search: &str, replace: &str) -> Option<String> {
+    line.contains(search).then(|| line.replace(search, replace))
+}
+
+/// The rewritten line, if it contains `search` as a whole version (see `match_offsets`)
+pub(crate) fn replace_bounded(line: &str, search: &str, replace: &str) -> Option<String> {
+    let offsets = match_offsets(line, search, true);
+    (!offsets.is_empty()).then(|| replace_at(line, search, replace, &offsets))
+}
+
+/// Where `search` occurs in `text`. With `bounded`, only where it isn't part of a longer version,
+/// i.e. not next to a letter, digit or a dot that continues it: `1.2.3` doesn't match in `11.2.30`
+/// or `1.2.3.4`, but does in `1.2.3.tar.gz`, at the end of a sentence and after a lone `v` (as in
+/// tag `v1.2.3`).
+/// An edge of `search` that can't be part of a version (e.g. the quote of `"{current-version}"`)
+/// bounds the match by itself.
+fn match_offsets(text: &str, search: &str, bounded: bool) -> Vec<usize> {
+    let version_char = |c: char| c.is_ascii_alphanumeric() || c == '.';
+    let check_start = bounded && search.chars().next().is_some_and(version_char);
+    let check_end = bounded && search.chars().next_back().is_some_and(version_char);
+    text.match_indices(search)
+        .map(|(offset, _)| offset)
+        .filter(|&offset| {
+            let before = text[..offset].trim_end_matches(['v', 'V']);
+            let tag_prefix = offset - before.len() == 1 && !continues_version(before.chars().rev());
+            let before = if tag_prefix { before } else { &text[..offset] };
+            (!check_start || !continues_version(before.chars().rev()))
+                && (!check_end || !continues_version(text[offset + search.len()..].chars()))
+        })
+        .collect()
+}
+
+/// Whether the characters next to a match (walking away from it) make it part of a longer version.
+/// A dot only does if a digit follows it (`1.2.3.4`, not `1.2.3.` or `1.2.3.tar.gz`).
+fn continues_version(mut chars: impl Iterator<Item = char>) -> bool {
+    match chars.next() {
+        Some('.') => chars.next().is_some_and(|c| c.is_ascii_digit()),
+        Some(c) => c.is_ascii_alphanumeric(),
+        None => false,
+    }
+}
+
+/// `text` with the `search` at each of `offsets` replaced
+fn replace_at(text: &str, search: &str, replace: &str, offsets: &[usize]) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for &offset in offsets {
+        replaced.push_str(&text[last..offset]);
+        replaced.push_str(replace);
+        last = offset + search.len();
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+/// The rewritten lines of `lines` as `(line index, new line)`, for every line containing `search`
+/// (as a whole version with `bounded`, see `match_offsets`). A `search` spanning several lines is
+/// matched across line breaks, within runs of lines that are `inside` (all lines without it), and
+/// yields the lines of each match that change (or its first line, if it doesn't change). Such a
+/// search must have as many lines as its `replace`.
+pub(crate) fn replace_lines<S: AsRef<str>>(
+    lines: &[S],
+    search: &str,
+    replace: &str,
+    inside: Option<&[bool]>,
+    bounded: bool,
+) -> Vec<(usize, String)> {
+    let is_inside = |idx: usize| inside.is_none_or(|inside| inside[idx]);
+    let replace_one = if bounded { replace_bounded } else { replace_line };
+    if !search.contains('\n') {
+        return lines
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| is_inside(*idx))
+            .filter_map(|(idx, line)| Some((idx, replace_one(line.as_ref(), search, replace)?)))
+            .collect();
+    }
+    if check_line_spans(search, replace).is_err() {
+        return Vec::new();
+    }
+
+    let mut edits = BTreeMap::new();
+    let mut start = 0;
+    while start < lines.len() {
+        if !is_inside(start) {
+            start += 1;
+            continue;
+        }
+        let end = (start..lines.len()).find(|&idx| !is_inside(idx)).unwrap_or(lines.len());
+        let block: Vec<&str> = lines[start..end].iter().map(AsRef::as_ref).collect();
+        let text = block.join("\n");
+        let offsets = match_offsets(&text, search, bounded);
+        let replaced = replace_at(&text, search, replace, &offsets);
+        let new_lines: Vec<&str> = replaced.split('\n').collect();
+        for offset in offsets {
+            let first = text[..offset].matches('\n').count();
+            let last = first + search.matches('\n').count();
+            let changed: Vec<usize> = (first..=last).filter(|&idx| block[idx] != new_lines[idx]).collect();
+            for idx in if changed.is_empty() { vec![first] } else { changed } {
+                edits.insert(start + idx, new_lines[idx].to_string());
+            }
+        }
+        start = end;
+    }
+    edits.into_iter().collect()
+}
+
+/// Multi-line search and replace templates must span the same number of lines,
+/// so that every change stays a change of whole lines
+pub fn check_line_spans(search: &str, replace: &str) -> Result<(), String> {
+    let (search_lines, replace_lines) = (search.split('\n').count(), replace.split('\n').count());
+    if search_lines != replace_lines {
+        return Err(format!(
+            "The search text spans {search_lines} line(s) but its replacement {replace_lines}; they must span the same number"
+        ));
+    }
+    Ok(())
+}
+
+/// Which lines lie inside a `bver:start` ... `bver:end` region (the marker lines themselves don't).
+/// A region that is never closed is ignored.
+pub(crate) fn marker_regions<S: AsRef<str>>(lines: &[S]) -> Vec<bool> {
+    let mut inside = vec![false; lines.len()];
+    let mut start = None;
+    for (idx, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        if line.contains("bver:start") {
+            start = Some(idx);
+        } else if line.contains("bver:end")
+            && let Some(start) = start.take()
+        {
+            inside[start + 1..idx].fill(true);
+        }
+    }
+    inside
+}
+
+/// The first version found where the search template expects `{current-version}`
+/// (other placeholders are filled in from `version`), e.g. to check what a file actually holds.
+/// Fails if the template has no `{current-version}`.
+pub fn find_version(content: &str, version: &str, options: &ReplaceOptions) -> Result<Option<String>, String> {
+    let template = options.search.as_deref().unwrap_or("{current-version}");
+    let pieces: Vec<&str> = template.split("{current-version}").collect();
+    if pieces.len() < 2 {
+        return Err(format!("The search template '{template}' doesn't contain {{current-version}}"));
+    }
+    let escaped: Vec<String> = pieces.iter().map(|piece| regex::escape(&render(piece, version, version))).collect();
+    // Only the first placeholder captures, a regex can't have duplicate group names
+    let mut pattern = regex::escape(options.prefix.as_deref().unwrap_or_default());
+    pattern.push_str(&escaped[0]);
+    for (idx, piece) in escaped[1..].iter().enumerate() {
+        let group = if idx == 0 { "(?P<version>" } else { "(?:" };
+        pattern.push_str(&format!(r"{group}[0-9](?:[0-9A-Za-z.+\-]*[0-9A-Za-z])?){piece}"));
+    }
+    pattern.push_str(&regex::escape(options.suffix.as_deref().unwrap_or_default()));
+    let re = Regex::new(&pattern).map_err(|e| format!("Invalid search template '{template}': {e}"))?;
+
+    // Search the lines as one text (blanking those outside marker regions), so templates
+    // spanning several lines match too
+    let lines: Vec<&str> = content.lines().collect();
+    let inside = options.allowed_lines(&lines)?;
+    let text: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(line_idx, line)| if inside.as_ref().is_none_or(|inside| inside[line_idx]) { *line } else { "" })
+        .collect();
+    Ok(re.captures(&text.join("\n")).map(|caps| caps["version"].to_string()))
+}
+
+/// Find every line of `content` that needs rewriting to change `old_version` into `new_version`
+pub fn propose_changes(
+    content: &str,
+    old_version: &str,
+    new_version: &str,
+    options: &ReplaceOptions,
+) -> Result<Vec<Change>, String> {
+    let (search, replace) = options.render(old_version, new_version);
+    let lines: Vec<&str> = content.lines().collect();
+    let inside = options.allowed_lines(&lines)?;
+    Ok(replace_lines(&lines, &search, &replace, inside.as_deref(), true)
+        .into_iter()
+        .map(|(line_idx, new_line)| Change {
+            line_idx,
+            old_line: lines[line_idx].to_string(),
+            new_line,
+        })
+        .collect())
+}
+
+/// Apply changes to `content`, keeping its line endings and trailing newline.
+/// Fails if a change doesn't match the line it targets.
+pub fn apply(content: &str, changes: &[Change]) -> Result<String, String> {
+    let mut buffer = FileBuffer::from_content(Path::new("-"), content);
+    for change in changes {
+        if buffer.lines().get(change.line_idx) != Some(&change.old_line) {
+            return Err(format!("Change for line {} doesn't match the content", change.line_idx + 1));
+        }
+        buffer.set_line(change.line_idx, &change.new_line)?;
+    }
+    Ok(buffer.content())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propose_and_apply() {
+        let content = "version = \"1.2.3\"\r\nother = 1.2.3\r\n";
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &ReplaceOptions::default()).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(apply(content, &changes).unwrap(), "version = \"1.2.4\"\r\nother = 1.2.4\r\n");
+
+        let options = ReplaceOptions {
+            search: Some("version = \"{current-version}\"".to_string()),
+            replace: Some("version = \"{new-version}\"".to_string()),
+            ..ReplaceOptions::default()
+        };
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &options).unwrap();
+        assert_eq!(
+            changes,
+            vec![Change {
+                line_idx: 0,
+                old_line: "version = \"1.2.3\"".to_string(),
+                new_line: "version = \"1.2.4\"".to_string(),
+            }]
+        );
+        assert_eq!(apply(content, &changes).unwrap(), "version = \"1.2.4\"\r\nother = 1.2.3\r\n");
+    }
+
+    #[test]
+    fn test_whole_versions_only() {
+        let content = "dep 11.2.30\nx 1.2.3.4\nv1.2.3 and dev1.2.3\nReleased 1.2.3.\n(1.2.3.tar.gz)\n";
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &ReplaceOptions::default()).unwrap();
+        let lines: Vec<(usize, &str)> = changes.iter().map(|c| (c.line_idx, c.new_line.as_str())).collect();
+        assert_eq!(lines, vec![(2, "v1.2.4 and dev1.2.3"), (3, "Released 1.2.4."), (4, "(1.2.4.tar.gz)")]);
+
+        // Template text that can't be part of a version needs no boundary
+        let options = ReplaceOptions {
+            search: Some("=\"{current-version}\"".to_string()),
+            replace: Some("=\"{new-version}\"".to_string()),
+            ..ReplaceOptions::default()
+        };
+        let changes = propose_changes("version=\"1.2.3\"\n", "1.2.3", "1.2.4", &options).unwrap();
+        assert_eq!(changes[0].new_line, "version=\"1.2.4\"");
+    }
+
+    #[test]
+    fn test_multiline_search() {
+        let content = "image:\n  tag: 1.2.3\nsidecar:\n  tag: 1.2.3\n<!-- bver:start -->\nimage:\n  tag: 1.2.3\n<!-- bver:end -->\n";
+        let mut options = ReplaceOptions {
+            search: Some("image:\n  tag: {current-version}".to_string()),
+            replace: Some("image:\n  tag: {new-version}".to_string()),
+            ..ReplaceOptions::default()
+        };
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &options).unwrap();
+        let lines: Vec<(usize, &str)> = changes.iter().map(|c| (c.line_idx, c.new_line.as_str())).collect();
+        assert_eq!(lines, vec![(1, "  tag: 1.2.4"), (6, "  tag: 1.2.4")]);
+
+        // Matches don't cross the edges of marker regions
+        options.markers = true;
+        let content = "image:\n<!-- bver:start -->\n  tag: 1.2.3\n<!-- bver:end -->\n";
+        assert!(propose_changes(content, "1.2.3", "1.2.4", &options).unwrap().is_empty());
+
+        // Replacing a version with itself still reports where it is
+        let lines = ["a", "image:", "  tag: 1.2.3"];
+        assert_eq!(replace_lines(&lines, "image:\n  tag: 1.2.3", "image:\n  tag: 1.2.3", None, true), vec![(1, "image:".to_string())]);
+        assert!(check_line_spans("a\nb", "a b").is_err());
+        options.markers = false;
+        let found = find_version("image:\n  tag: 2.0.0\n", "0.0.0", &options).unwrap();
+        assert_eq!(found.as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn test_line_restrictions() {
+        let content = "deps = 1.2.3\n[tool]\nversion = 1.2.3\n[other]\nversion = 1.2.3\n";
+        let changed = |options: ReplaceOptions| -> Vec<usize> {
+            let changes = propose_changes(content, "1.2.3", "1.2.4", &options).unwrap();
+            changes.iter().map(|c| c.line_idx).collect()
+        };
+        let range = |lines: &str| ReplaceOptions {
+            lines: Some(lines.to_string()),
+            ..ReplaceOptions::default()
+        };
+        assert_eq!(changed(range("2..3")), vec![2]);
+        assert_eq!(changed(range("3..")), vec![2, 4]);
+        assert_eq!(changed(range("..1")), vec![0]);
+        let anchors = ReplaceOptions {
+            after: Some(r"^\[tool\]".to_string()),
+            before: Some(r"^\[".to_string()),
+            ..ReplaceOptions::default()
+        };
+        assert_eq!(changed(anchors), vec![2]);
+        let missing = ReplaceOptions {
+            after: Some("nowhere".to_string()),
+            ..ReplaceOptions::default()
+        };
+        assert!(changed(missing).is_empty());
+        assert!(propose_changes(content, "1.2.3", "1.2.4", &range("5..2")).is_err());
+    }
+
+    #[test]
+    fn test_prefix_suffix() {
+        let content = "version = \"1.2.3\"\nrequires = \">=1.2.3\"\n";
+        let options = ReplaceOptions {
+            prefix: Some("version = \"".to_string()),
+            suffix: Some("\"".to_string()),
+            ..ReplaceOptions::default()
+        };
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &options).unwrap();
+        assert_eq!(apply(content, &changes).unwrap(), "version = \"1.2.4\"\nrequires = \">=1.2.3\"\n");
+        let found = find_version("requires = \"2.0\"\nversion = \"1.3.0\"\n", "0.0.0", &options).unwrap();
+        assert_eq!(found.as_deref(), Some("1.3.0"));
+    }
+
+    #[test]
+    fn test_marker_regions() {
+        let content = "pip install pkg==1.2.3\n<!-- bver:start -->\npip install pkg==1.2.3\n<!-- bver:end -->\n1.2.3\n# bver:start\n1.2.3\n";
+        let options = ReplaceOptions {
+            markers: true,
+            ..ReplaceOptions::default()
+        };
+        let changes = propose_changes(content, "1.2.3", "1.2.4", &options).unwrap();
+        let lines: Vec<usize> = changes.iter().map(|c| c.line_idx).collect();
+        assert_eq!(lines, vec![2]);
+    }
+
+    #[test]
+    fn test_find_version() {
+        let content = "name = \"app\"\nversion = \"1.3.0-rc.1\"\nother = 2.0\n";
+        let options = ReplaceOptions {
+            search: Some("version = \"{current-version}\"".to_string()),
+            ..ReplaceOptions::default()
+        };
+        assert_eq!(find_version(content, "1.2.3", &options).unwrap().as_deref(), Some("1.3.0-rc.1"));
+        let any = ReplaceOptions::default();
+        assert_eq!(find_version("see v2.0.1 here", "1.2.3", &any).unwrap().as_deref(), Some("2.0.1"));
+        let fixed = ReplaceOptions {
+            search: Some("version".to_string()),
+            ..ReplaceOptions::default()
+        };
+        assert!(find_version(content, "1.2.3", &fixed).is_err());
+    }
+
+    #[test]
+    fn test_apply_rejects_stale_changes() {
+        let changes = propose_changes("a 1.0\n", "1.0", "1.1", &ReplaceOptions::default()).unwrap();
+        assert!(apply("b 1.0\n", &changes).is_err());
+    }
+}
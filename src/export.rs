@@ -0,0 +1,83 @@
+use clap::ValueEnum;
+
+use crate::template::Components;
+
+/// How `bver export` prints variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// `KEY=VALUE`, for `eval` in POSIX shells or appending to `$GITHUB_ENV`
+    Env,
+    /// `KEY="VALUE"`
+    Dotenv,
+    /// `set -gx KEY VALUE`
+    Fish,
+    /// `$env:KEY = "VALUE"`
+    Powershell,
+}
+
+/// `BVER_VERSION`, `BVER_MAJOR`, `BVER_MINOR`, `BVER_PATCH` and `BVER_PRE` for the current
+/// version, and the same with `BVER_NEW_` for the new version if there is one
+pub fn version_vars(current_version: &str, new_version: Option<&str>) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    let mut push = |prefix: &str, version: &str| {
+        let components = Components::parse(version);
+        vars.push((format!("{prefix}VERSION"), version.to_string()));
+        vars.push((format!("{prefix}MAJOR"), components.major));
+        vars.push((format!("{prefix}MINOR"), components.minor));
+        vars.push((format!("{prefix}PATCH"), components.patch));
+        vars.push((format!("{prefix}PRE"), components.pre));
+    };
+    push("BVER_", current_version);
+    if let Some(new_version) = new_version {
+        push("BVER_NEW_", new_version);
+    }
+    vars
+}
+
+/// One line per variable, quoted for the target shell where needed
+pub fn format_vars(vars: &[(String, String)], format: ExportFormat) -> String {
+    vars.iter()
+        .map(|(key, value)| match format {
+            ExportFormat::Env => format!("{key}={}\n", sh_quote(value)),
+            ExportFormat::Dotenv => format!("{key}=\"{}\"\n", value.replace(['\\', '"'], "")),
+            ExportFormat::Fish => format!("set -gx {key} {}\n", sh_quote(value)),
+            ExportFormat::Powershell => format!("$env:{key} = \"{}\"\n", value.replace(['`', '"', '$'], "")),
+        })
+        .collect()
+}
+
+/// Single-quote a value unless it's made of characters that are safe in any shell
+fn sh_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+' | '!'));
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_formats() {
+        let vars = version_vars("1.2.3", Some("1.3.0a1"));
+        let keys: Vec<&str> = vars.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys[..5], ["BVER_VERSION", "BVER_MAJOR", "BVER_MINOR", "BVER_PATCH", "BVER_PRE"]);
+        assert_eq!(vars[9], ("BVER_NEW_PRE".to_string(), "a1".to_string()));
+
+        let vars = &vars[..2];
+        assert_eq!(format_vars(vars, ExportFormat::Env), "BVER_VERSION=1.2.3\nBVER_MAJOR=1\n");
+        assert_eq!(format_vars(vars, ExportFormat::Dotenv), "BVER_VERSION=\"1.2.3\"\nBVER_MAJOR=\"1\"\n");
+        assert_eq!(format_vars(vars, ExportFormat::Fish), "set -gx BVER_VERSION 1.2.3\nset -gx BVER_MAJOR 1\n");
+        assert_eq!(
+            format_vars(vars, ExportFormat::Powershell),
+            "$env:BVER_VERSION = \"1.2.3\"\n$env:BVER_MAJOR = \"1\"\n"
+        );
+        assert_eq!(format_vars(&version_vars("1.2.3", None)[4..], ExportFormat::Env), "BVER_PRE=''\n");
+    }
+}
@@ -1,10 +1,16 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn find_repo_root() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
+use ignore::overrides::{Override, OverrideBuilder};
+
+use crate::vfs::Vfs;
+
+/// Walk up from the current directory to the first directory containing `name`
+fn find_upwards(fs: &dyn Vfs, name: &str) -> Option<PathBuf> {
+    let mut current = fs.current_dir()?;
     loop {
-        if current.join(".git").exists() {
-            return Some(current);
+        let candidate = current.join(name);
+        if fs.exists(&candidate) {
+            return Some(candidate);
         }
         if !current.pop() {
             return None;
@@ -12,62 +18,170 @@ pub fn find_repo_root() -> Option<PathBuf> {
     }
 }
 
-pub fn find_pyproject_toml() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
-    loop {
-        let pyproject = current.join("pyproject.toml");
-        if pyproject.exists() {
-            return Some(pyproject);
-        }
-        if !current.pop() {
-            return None;
-        }
+/// A configured relative path (written with `/` in config files) with the platform's separators
+pub fn native_path(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+/// The current directory and each directory above it, nearest first
+pub fn search_dirs(fs: &dyn Vfs) -> Vec<PathBuf> {
+    fs.current_dir()
+        .map(|dir| dir.ancestors().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+/// The root of the repository (or worktree, or submodule) containing the current directory
+pub fn find_repo_root(fs: &dyn Vfs) -> Option<PathBuf> {
+    let dot_git = find_upwards(fs, ".git")?;
+    git_dir_of(fs, &dot_git)?;
+    dot_git.parent().map(PathBuf::from)
+}
+
+/// The git directory of the repository containing the current directory
+pub fn find_git_dir(fs: &dyn Vfs) -> Option<PathBuf> {
+    git_dir_of(fs, &find_upwards(fs, ".git")?)
+}
+
+/// The git directory shared by every worktree of the repository, which holds `hooks/`
+pub fn find_common_git_dir(fs: &dyn Vfs) -> Option<PathBuf> {
+    let git_dir = find_git_dir(fs)?;
+    match fs.read_to_string(&git_dir.join("commondir")) {
+        Ok(common) => Some(git_dir.join(common.trim())),
+        Err(_) => Some(git_dir),
     }
 }
 
-pub fn find_package_json() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
-    loop {
-        let package_json = current.join("package.json");
-        if package_json.exists() {
-            return Some(package_json);
-        }
-        if !current.pop() {
-            return None;
-        }
+/// The git directory a `.git` entry stands for: the directory itself, or where a `.git` file
+/// (in a worktree or submodule) points with `gitdir: <path>`
+fn git_dir_of(fs: &dyn Vfs, dot_git: &Path) -> Option<PathBuf> {
+    let Ok(content) = fs.read_to_string(dot_git) else {
+        return Some(dot_git.to_path_buf());
+    };
+    let target = content.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim();
+    Some(dot_git.parent()?.join(target))
+}
+
+pub fn find_pyproject_toml(fs: &dyn Vfs) -> Option<PathBuf> {
+    find_upwards(fs, "pyproject.toml")
+}
+
+pub fn find_package_json(fs: &dyn Vfs) -> Option<PathBuf> {
+    find_upwards(fs, "package.json")
+}
+
+pub fn find_cargo_toml(fs: &dyn Vfs) -> Option<PathBuf> {
+    find_upwards(fs, "Cargo.toml")
+}
+
+pub fn find_bver_toml(fs: &dyn Vfs) -> Option<PathBuf> {
+    find_upwards(fs, "bver.toml")
+}
+
+pub fn find_project_root(fs: &dyn Vfs) -> Option<PathBuf> {
+    find_repo_root(fs)
+        .or_else(|| find_bver_toml(fs).and_then(|p| p.parent().map(PathBuf::from)))
+        .or_else(|| find_pyproject_toml(fs).and_then(|p| p.parent().map(PathBuf::from)))
+        .or_else(|| find_package_json(fs).and_then(|p| p.parent().map(PathBuf::from)))
+        .or_else(|| find_cargo_toml(fs).and_then(|p| p.parent().map(PathBuf::from)))
+}
+
+/// Whether a configured `src` entry is a glob pattern rather than a plain path
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{'])
+}
+
+/// Anchor a path or glob pattern at the project root. Unlike in `.gitignore`, `setup.py` is only
+/// the file at the root; `**/setup.py` matches at any depth.
+fn anchored(pattern: &str) -> String {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.starts_with('/') || pattern.starts_with("**") {
+        pattern.to_string()
+    } else {
+        format!("/{pattern}")
     }
 }
 
-pub fn find_cargo_toml() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
-    loop {
-        let cargo_toml = current.join("Cargo.toml");
-        if cargo_toml.exists() {
-            return Some(cargo_toml);
-        }
-        if !current.pop() {
-            return None;
-        }
+/// Build a matcher for files under `root` from paths or glob patterns (e.g. given on the
+/// command line), anchored at `root` like `src` patterns
+pub fn path_matcher(root: &Path, patterns: &[String]) -> Result<Override, String> {
+    let mut builder = OverrideBuilder::new(root);
+    for pattern in patterns {
+        builder
+            .add(&anchored(pattern))
+            .map_err(|e| format!("Invalid path pattern '{pattern}': {e}"))?;
     }
+    builder.build().map_err(|e| format!("Invalid path pattern: {e}"))
 }
 
-pub fn find_bver_toml() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
-    loop {
-        let bver_toml = current.join("bver.toml");
-        if bver_toml.exists() {
-            return Some(bver_toml);
-        }
-        if !current.pop() {
-            return None;
-        }
+/// Expand a glob pattern relative to `root` into a sorted list of files.
+/// Respects `.gitignore` (on the real filesystem), skips `.git/`, and drops anything matching `excludes`.
+/// Both are anchored at `root`.
+pub fn expand_glob(fs: &dyn Vfs, root: &Path, pattern: &str, excludes: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut include = OverrideBuilder::new(root);
+    include
+        .add(&anchored(pattern))
+        .map_err(|e| format!("Invalid glob pattern '{pattern}': {e}"))?;
+    let include = include
+        .build()
+        .map_err(|e| format!("Invalid glob pattern '{pattern}': {e}"))?;
+
+    let mut exclude = OverrideBuilder::new(root);
+    for pattern in excludes {
+        exclude
+            .add(&format!("!{}", anchored(pattern)))
+            .map_err(|e| format!("Invalid exclude pattern '{pattern}': {e}"))?;
     }
+    let exclude = exclude
+        .build()
+        .map_err(|e| format!("Invalid exclude pattern: {e}"))?;
+
+    let mut paths = fs.list_files(root, &exclude)?;
+    paths.retain(|path| include.matched(path, false).is_whitelist());
+    Ok(paths)
 }
 
-pub fn find_project_root() -> Option<PathBuf> {
-    find_repo_root()
-        .or_else(|| find_bver_toml().and_then(|p| p.parent().map(PathBuf::from)))
-        .or_else(|| find_pyproject_toml().and_then(|p| p.parent().map(PathBuf::from)))
-        .or_else(|| find_package_json().and_then(|p| p.parent().map(PathBuf::from)))
-        .or_else(|| find_cargo_toml().and_then(|p| p.parent().map(PathBuf::from)))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_find_in_memory() {
+        let fs = MemoryFs::new("/repo/pkg/src")
+            .with_file("/repo/.git/HEAD", "ref: refs/heads/main\n")
+            .with_file("/repo/pkg/pyproject.toml", "")
+            .with_file("/repo/pkg/src/a.py", "")
+            .with_file("/repo/pkg/src/gen/b.py", "")
+            .with_file("/repo/pkg/src/c.txt", "");
+        assert_eq!(find_pyproject_toml(&fs), Some(PathBuf::from("/repo/pkg/pyproject.toml")));
+        assert_eq!(find_project_root(&fs), Some(PathBuf::from("/repo")));
+        assert_eq!(find_bver_toml(&fs), None);
+        assert_eq!(find_common_git_dir(&fs), Some(PathBuf::from("/repo/.git")));
+
+        let paths = expand_glob(&fs, Path::new("/repo"), "**/*.py", &["**/gen/**".to_string()]).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("/repo/pkg/src/a.py")]);
+        // Patterns without a `/` only match at the root
+        let paths = expand_glob(&fs, Path::new("/repo"), "*.py", &[]).unwrap();
+        assert!(paths.is_empty());
+        let paths = expand_glob(&fs, Path::new("/repo"), "pkg/**/*.py", &["b.py".to_string()]).unwrap();
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn test_worktree_and_submodule() {
+        let fs = MemoryFs::new("/wt/src")
+            .with_file("/wt/.git", "gitdir: /main/.git/worktrees/wt\n")
+            .with_file("/main/.git/worktrees/wt/commondir", "../..\n");
+        assert_eq!(find_repo_root(&fs), Some(PathBuf::from("/wt")));
+        assert_eq!(find_git_dir(&fs), Some(PathBuf::from("/main/.git/worktrees/wt")));
+        assert_eq!(find_common_git_dir(&fs), Some(PathBuf::from("/main/.git/worktrees/wt/../..")));
+
+        let fs = MemoryFs::new("/super/sub").with_file("/super/sub/.git", "gitdir: ../.git/modules/sub\n");
+        assert_eq!(find_repo_root(&fs), Some(PathBuf::from("/super/sub")));
+        assert_eq!(find_git_dir(&fs), Some(PathBuf::from("/super/sub/../.git/modules/sub")));
+
+        // A `.git` file that doesn't point anywhere isn't a repository
+        let fs = MemoryFs::new("/proj").with_file("/proj/.git", "not a gitdir\n");
+        assert_eq!(find_repo_root(&fs), None);
+    }
 }
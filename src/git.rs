@@ -1,9 +1,17 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::finders::find_repo_root;
-use crate::schema::{Action, GitConfig, RunPreCommit};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::finders::{find_common_git_dir, find_repo_root};
+use crate::pep440::Pep440Version;
+use crate::process::{program, run_captured};
+use crate::schema::{Action, GitConfig, PreCommitScope, RunPreCommit};
+use crate::template::render;
+use crate::vfs::{OsFs, Vfs};
 
 /// Detected pre-commit tool type
 enum PreCommitTool {
@@ -13,7 +21,7 @@ enum PreCommitTool {
 
 /// Check if a command is available
 fn command_available(cmd: &str) -> bool {
-    Command::new(cmd)
+    Command::new(program(cmd))
         .arg("--version")
         .output()
         .map(|o| o.status.success())
@@ -22,12 +30,11 @@ fn command_available(cmd: &str) -> bool {
 
 /// Detect which pre-commit tool is available and configured
 fn detect_pre_commit_tool() -> Option<PreCommitTool> {
-    let repo_root = find_repo_root()?;
-    let hook_path = repo_root.join(".git/hooks/pre-commit");
-
-    if !hook_path.exists() {
-        return None;
-    }
+    let git_dir = match std::env::var_os("GIT_DIR") {
+        Some(_) => PathBuf::from(git_output(&["rev-parse", "--path-format=absolute", "--git-common-dir"]).ok()?),
+        None => find_common_git_dir(&OsFs)?,
+    };
+    let hook_path = installed_hook(&git_dir.join("hooks"))?;
 
     // Check if the hook is a prek hook
     if let Ok(content) = fs::read_to_string(&hook_path)
@@ -45,16 +52,30 @@ fn detect_pre_commit_tool() -> Option<PreCommitTool> {
     None
 }
 
-/// Run pre-commit hooks based on config setting
-pub fn maybe_run_pre_commit(setting: RunPreCommit) -> Result<(), String> {
-    match setting {
-        RunPreCommit::Disabled => Ok(()),
-        RunPreCommit::Enabled => run_pre_commit(true),
-        RunPreCommit::WhenPresent => run_pre_commit(false),
-    }
+/// The installed `pre-commit` hook in a hooks directory: the script, or an `.exe`/`.cmd`/`.bat`
+/// variant as installed on Windows
+fn installed_hook(hooks_dir: &Path) -> Option<PathBuf> {
+    ["pre-commit", "pre-commit.exe", "pre-commit.cmd", "pre-commit.bat"]
+        .into_iter()
+        .map(|name| hooks_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Run pre-commit hooks based on config setting. When the bump gets committed, the changed files
+/// are staged first and files the hooks fix are re-staged before each retry.
+pub fn maybe_run_pre_commit(git_config: &GitConfig, changed_files: &[&Path], timeout: Duration) -> Result<(), String> {
+    let required = match git_config.run_pre_commit {
+        RunPreCommit::Disabled => return Ok(()),
+        RunPreCommit::Enabled => true,
+        RunPreCommit::WhenPresent => false,
+    };
+    run_pre_commit(git_config, required, changed_files, timeout)
 }
 
-fn run_pre_commit(required: bool) -> Result<(), String> {
+fn run_pre_commit(git_config: &GitConfig, required: bool, changed_files: &[&Path], timeout: Duration) -> Result<(), String> {
+    let retries = git_config.pre_commit_retries;
+    let stage = git_config.has(Action::Commit).then_some(changed_files);
+    let runs = pre_commit_args(git_config, changed_files)?;
     let tool = match detect_pre_commit_tool() {
         Some(t) => t,
         None => {
@@ -72,39 +93,136 @@ fn run_pre_commit(required: bool) -> Result<(), String> {
         PreCommitTool::PreCommit => ("pre-commit", "pre-commit"),
     };
 
+    // Hooks check what is staged, so stage the bump before running them
+    if let Some(files) = stage {
+        git_add_files(files)?;
+    }
+
     println!("Running {} hooks...", name);
 
-    let status = Command::new(cmd)
-        .args(["run", "--all-files"])
-        .status()
-        .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+    for attempt in 0..=retries {
+        let unstaged = if stage.is_some() { unstaged_files()? } else { Vec::new() };
+        let mut failures = Vec::new();
+        for args in &runs {
+            let run = run_captured(Command::new(program(cmd)).args(args), timeout)
+                .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+            if !run.success() {
+                failures.push(format!("{} {} {}", cmd, args.join(" "), run.failure(timeout)));
+            }
+        }
 
-    if status.success() {
-        println!("{} hooks passed.", name);
-        return Ok(());
+        if failures.is_empty() {
+            if attempt == 0 {
+                println!("{} hooks passed.", name);
+            } else {
+                println!("{} hooks passed on retry {}.", name, attempt);
+            }
+            return Ok(());
+        }
+        if attempt == retries {
+            return Err(format!(
+                "{} hooks failed {} times, aborting bump\n{}",
+                name,
+                retries + 1,
+                failures.join("\n")
+            ));
+        }
+
+        // Failed, run it again (it may have auto-fixed files)
+        if stage.is_some() {
+            let fixed = hook_fixed_files(&unstaged, &unstaged_files()?);
+            if !fixed.is_empty() {
+                println!("Re-staging files fixed by {}: {}", name, fixed.join(", "));
+                let pathspecs: Vec<String> = fixed.iter().map(|file| format!(":(top){file}")).collect();
+                let mut args = vec!["add", "--"];
+                args.extend(pathspecs.iter().map(String::as_str));
+                git(&args)?;
+            }
+        }
+        println!("{} hooks failed, running again ({}/{})...", name, attempt + 1, retries);
     }
+    unreachable!("the last attempt returns")
+}
 
-    // Failed, run it a second time (it may have auto-fixed files)
-    println!("{} hooks failed, running again...", name);
+/// The arguments of each pre-commit invocation: one per selected hook (or one for every hook),
+/// on all files or only on the changed ones depending on `pre-commit-scope`
+fn pre_commit_args(git_config: &GitConfig, changed_files: &[&Path]) -> Result<Vec<Vec<String>>, String> {
+    let mut scope = Vec::new();
+    match git_config.pre_commit_scope {
+        PreCommitScope::AllFiles => scope.push("--all-files".to_string()),
+        PreCommitScope::ChangedFiles => {
+            scope.push("--files".to_string());
+            for path in changed_files {
+                let path = path.to_str().ok_or_else(|| format!("Invalid path: {:?}", path))?;
+                scope.push(path.to_string());
+            }
+        }
+    }
+    let hooks: Vec<Option<&String>> = if git_config.pre_commit_hooks.is_empty() {
+        vec![None]
+    } else {
+        git_config.pre_commit_hooks.iter().map(Some).collect()
+    };
+    Ok(hooks
+        .into_iter()
+        .map(|hook| {
+            let mut args = vec!["run".to_string()];
+            args.extend(hook.cloned());
+            args.extend(scope.iter().cloned());
+            args
+        })
+        .collect())
+}
 
-    let status = Command::new(cmd)
-        .args(["run", "--all-files"])
-        .status()
-        .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+/// Files with unstaged changes, relative to the repository root
+fn unstaged_files() -> Result<Vec<String>, String> {
+    let output = git_output(&["diff", "--name-only"])?;
+    Ok(output.lines().map(str::to_string).collect())
+}
 
-    if status.success() {
-        println!("{} hooks passed on second run.", name);
-        Ok(())
-    } else {
-        Err(format!("{} hooks failed twice, aborting bump", name))
+/// The files a hook run left modified that weren't modified before it
+fn hook_fixed_files(before: &[String], after: &[String]) -> Vec<String> {
+    after.iter().filter(|file| !before.contains(file)).cloned().collect()
+}
+
+/// A git command run from the root of the repository (or worktree) bver works in.
+/// `GIT_DIR` / `GIT_WORK_TREE` are passed on as absolute paths, since git runs elsewhere.
+fn git_command() -> Command {
+    let mut command = Command::new(program("git"));
+    match find_repo_root(&OsFs).or_else(|| OsFs.current_dir()) {
+        Some(root) => command.current_dir(root),
+        None => &mut command,
+    };
+    for var in ["GIT_DIR", "GIT_WORK_TREE"] {
+        if let Some(value) = std::env::var_os(var)
+            && let Ok(path) = std::path::absolute(value)
+        {
+            command.env(var, path);
+        }
     }
+    command
 }
 
 /// Run a git command and return the result
 fn git(args: &[&str]) -> Result<(), String> {
     println!("Running: git {}", args.join(" "));
 
-    let output = Command::new("git")
+    let output = git_command()
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+
+    Ok(())
+}
+
+/// Run a git command and return its trimmed stdout
+fn git_output(args: &[&str]) -> Result<String, String> {
+    let output = git_command()
         .args(args)
         .output()
         .map_err(|e| format!("Failed to run git: {e}"))?;
@@ -113,15 +231,185 @@ fn git(args: &[&str]) -> Result<(), String> {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(format!("git {} failed: {}", args[0], stderr.trim()));
     }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The checked out branch, if any (not in a detached HEAD or outside a repository)
+pub fn current_branch() -> Option<String> {
+    git_output(&["rev-parse", "--abbrev-ref", "HEAD"]).ok().filter(|branch| branch != "HEAD")
+}
+
+/// Render a git template: the placeholders of `render`, plus `{git-sha}` (short SHA of the commit
+/// the bump starts from), `{branch}` (the branch it starts from), `{author}` (git `user.name`)
+/// and `{date}` (today in UTC, `YYYY-MM-DD`)
+fn render_git(template: &str, current_version: &str, new_version: &str) -> String {
+    render_git_at(template, current_version, new_version, SystemTime::now())
+}
+
+/// `render_git` with `{date}` taken from `now`
+fn render_git_at(template: &str, current_version: &str, new_version: &str, now: SystemTime) -> String {
+    let value = |placeholder: &str| match placeholder {
+        "{git-sha}" => git_output(&["rev-parse", "--short", "HEAD"]).ok(),
+        "{branch}" => git_output(&["rev-parse", "--abbrev-ref", "HEAD"]).ok(),
+        "{author}" => git_output(&["config", "user.name"]).ok(),
+        _ => Some(utc_date(now)),
+    };
+    ["{git-sha}", "{branch}", "{author}", "{date}"]
+        .into_iter()
+        .fold(render(template, current_version, new_version), |rendered, placeholder| {
+            // Only ask git for what the template uses
+            match rendered.contains(placeholder) {
+                true => rendered.replace(placeholder, &value(placeholder).unwrap_or_default()),
+                false => rendered,
+            }
+        })
+}
+
+/// A UTC date as `YYYY-MM-DD`
+fn utc_date(time: SystemTime) -> String {
+    let days = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Civil date from days since 1970-01-01 (http://howardhinnant.github.io/date_algorithms.html)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// The refs created by the last bump that haven't been pushed yet, for `bver push`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BumpState {
+    pub version: String,
+    pub branch: String,
+    pub commit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+/// Where the bump state is kept: inside the git directory, so it's never committed
+fn bump_state_path() -> Result<PathBuf, String> {
+    Ok(PathBuf::from(git_output(&["rev-parse", "--absolute-git-dir"])?).join("bver-bump.json"))
+}
+
+fn record_bump_state(state: &BumpState) -> Result<(), String> {
+    let path = bump_state_path()?;
+    let json = serde_json::to_string_pretty(state).map_err(|e| format!("Failed to record bump state: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
 
+fn clear_bump_state() -> Result<(), String> {
+    let path = bump_state_path()?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    }
     Ok(())
 }
 
-/// Apply template substitutions for version strings
-fn apply_template(template: &str, current_version: &str, new_version: &str) -> String {
-    template
-        .replace("{current-version}", current_version)
-        .replace("{new-version}", new_version)
+/// Push the commit and tag recorded by the last bump
+pub fn push_recorded_bump(force: bool) -> Result<(), String> {
+    let path = bump_state_path()?;
+    let content = fs::read_to_string(&path)
+        .map_err(|_| "No unpushed bump recorded; run `bver bump` with a commit action first".to_string())?;
+    let state: BumpState =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid bump state in {}: {e}", path.display()))?;
+
+    // The branch may have moved on since, but it must still contain the bump commit
+    if git_output(&["merge-base", "--is-ancestor", &state.commit, &state.branch]).is_err() {
+        return Err(format!(
+            "Branch '{}' no longer contains the bump commit {} for {}",
+            state.branch, state.commit, state.version
+        ));
+    }
+
+    println!("Pushing bump to {} ({})", state.version, &state.commit[..state.commit.len().min(7)]);
+    git_push(force, true, &state.branch)?;
+    if let Some(tag) = &state.tag {
+        git_push_tag(tag, force)?;
+    }
+    clear_bump_state()
+}
+
+/// One configured git action as it would run for a bump, which can be turned off for this run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitStep {
+    pub action: Action,
+    /// What the action does, with its templates rendered (e.g. `tag 1.2.4`)
+    pub description: String,
+    pub enabled: bool,
+}
+
+/// The actions `run_git_actions` needs alongside `action`
+fn required_actions(action: Action) -> &'static [Action] {
+    match action {
+        Action::Tag | Action::Push => &[Action::Commit],
+        Action::Pr => &[Action::Push, Action::Branch],
+        Action::AddAll | Action::Branch | Action::Commit => &[],
+    }
+}
+
+/// The configured git actions for a bump, in the order they run
+pub fn git_plan(git_config: &GitConfig, current_version: &str, new_version: &str) -> Vec<GitStep> {
+    let tag_name = render_git(&git_config.tag_template, current_version, new_version);
+    let commit_msg = render_git(&git_config.commit_template, current_version, new_version);
+    let branch_name = render_git(&git_config.branch_template, current_version, new_version);
+
+    let order = [Action::Branch, Action::AddAll, Action::Commit, Action::Tag, Action::Push, Action::Pr];
+    order
+        .into_iter()
+        .filter(|action| git_config.has(*action))
+        .map(|action| {
+            let description = match action {
+                Action::Branch => format!("branch {branch_name}"),
+                Action::AddAll => "add --all".to_string(),
+                Action::Commit => format!("commit \"{commit_msg}\""),
+                Action::Tag => format!("tag {tag_name}"),
+                Action::Push => {
+                    let target = if git_config.has(Action::Branch) {
+                        format!("origin/{branch_name}")
+                    } else {
+                        git_output(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
+                            .unwrap_or_else(|_| "the current branch's upstream".to_string())
+                    };
+                    match git_config.has(Action::Tag) {
+                        true => format!("push to {target} (and tag {tag_name})"),
+                        false => format!("push to {target}"),
+                    }
+                }
+                Action::Pr => format!("pr \"{commit_msg}\""),
+            };
+            GitStep {
+                action,
+                description,
+                enabled: true,
+            }
+        })
+        .collect()
+}
+
+/// Toggle a step, keeping the plan runnable: turning a step off also turns off the steps
+/// that need it, turning it on also turns on the steps it needs
+pub fn toggle_git_step(steps: &mut [GitStep], idx: usize) {
+    let enabled = !steps[idx].enabled;
+    let mut changed = vec![steps[idx].action];
+    while let Some(action) = changed.pop() {
+        for step in steps.iter_mut() {
+            let affected = if enabled {
+                required_actions(action).contains(&step.action)
+            } else {
+                required_actions(step.action).contains(&action)
+            };
+            if (affected || step.action == action) && step.enabled != enabled {
+                step.enabled = enabled;
+                changed.push(step.action);
+            }
+        }
+    }
 }
 
 /// Run git operations based on config setting
@@ -132,9 +420,9 @@ pub fn run_git_actions(
     force: bool,
     changed_files: &[&Path],
 ) -> Result<(), String> {
-    let tag_name = apply_template(&git_config.tag_template, current_version, new_version);
-    let commit_msg = apply_template(&git_config.commit_template, current_version, new_version);
-    let branch_name = apply_template(&git_config.branch_template, current_version, new_version);
+    let tag_name = render_git(&git_config.tag_template, current_version, new_version);
+    let commit_msg = render_git(&git_config.commit_template, current_version, new_version);
+    let branch_name = render_git(&git_config.branch_template, current_version, new_version);
 
     if git_config.has(Action::Branch) {
         git_checkout_new_branch(&branch_name)?;
@@ -150,12 +438,22 @@ pub fn run_git_actions(
     if git_config.has(Action::Tag) {
         git_tag(&tag_name, new_version, force)?;
     }
+    if git_config.has(Action::Commit) {
+        // Kept until pushed, so a failed or deferred push can be finished with `bver push`
+        record_bump_state(&BumpState {
+            version: new_version.to_string(),
+            branch: git_output(&["rev-parse", "--abbrev-ref", "HEAD"])?,
+            commit: git_output(&["rev-parse", "HEAD"])?,
+            tag: git_config.has(Action::Tag).then(|| tag_name.clone()),
+        })?;
+    }
     if git_config.has(Action::Push) {
         let set_upstream = git_config.has(Action::Branch);
         git_push(force, set_upstream, &branch_name)?;
         if git_config.has(Action::Tag) {
             git_push_tag(&tag_name, force)?;
         }
+        clear_bump_state()?;
     }
     if git_config.has(Action::Pr) {
         gh_pr_create(&commit_msg)?;
@@ -164,6 +462,90 @@ pub fn run_git_actions(
     Ok(())
 }
 
+/// Tag the current version, e.g. on merge when the files were bumped earlier in a PR.
+/// The tag template is rendered with the current version as both current and new version.
+pub fn tag_current_version(git_config: &GitConfig, version: &str, force: bool, push: bool) -> Result<(), String> {
+    let tag_name = render_git(&git_config.tag_template, version, version);
+    git_tag(&tag_name, version, force)?;
+    if push {
+        git_push_tag(&tag_name, force)?;
+    }
+    Ok(())
+}
+
+/// A setuptools_scm-style development version for the commits since the last release tag,
+/// e.g. `1.2.4.dev3+g1a2b3c4` three commits after `v1.2.3` (or the version itself at the tag).
+/// Without a release tag, the commits are counted from the start on top of `current_version`.
+pub fn dev_version(git_config: &GitConfig, current_version: &str) -> Result<String, String> {
+    let sha = git_output(&["rev-parse", "--short", "HEAD"])?;
+    let pattern = tag_glob(&git_config.tag_template);
+    let (version, distance) = match git_output(&["describe", "--tags", "--long", "--match", &pattern]) {
+        Ok(described) => {
+            let (tag, distance) = parse_describe(&described)?;
+            (tag_version(&git_config.tag_template, &tag), distance)
+        }
+        Err(_) => {
+            let count = git_output(&["rev-list", "--count", "HEAD"])?;
+            let count = count.parse().map_err(|_| format!("Unexpected commit count '{count}'"))?;
+            (current_version.to_string(), count)
+        }
+    };
+    format_dev_version(&version, distance, &sha)
+}
+
+/// A glob matching the tags a tag template produces, e.g. `v*` for `v{new-version}`
+fn tag_glob(template: &str) -> String {
+    Regex::new(r"\{[a-z-]+\}").unwrap().replace_all(template, "*").into_owned()
+}
+
+/// The tag and commit distance of `git describe --long` output, e.g. `v1.2.3-4-g1a2b3c4`
+fn parse_describe(described: &str) -> Result<(String, u64), String> {
+    let mut parts = described.rsplitn(3, '-');
+    let (_sha, distance, tag) = (parts.next(), parts.next(), parts.next());
+    let distance = distance.and_then(|distance| distance.parse().ok());
+    match (tag, distance) {
+        (Some(tag), Some(distance)) => Ok((tag.to_string(), distance)),
+        _ => Err(format!("Unexpected `git describe` output '{described}'")),
+    }
+}
+
+/// The version in a tag made from `template`, e.g. `1.2.3` from `v1.2.3` with `v{new-version}`
+fn tag_version(template: &str, tag: &str) -> String {
+    let (prefix, suffix) = template.split_once("{new-version}").unwrap_or(("v", ""));
+    let version = tag.strip_prefix(prefix).unwrap_or(tag);
+    version.strip_suffix(suffix).unwrap_or(version).to_string()
+}
+
+/// The dev version `distance` commits after `version`: the next patch (or prerelease) with a
+/// `.devN` segment and the commit as local version
+fn format_dev_version(version: &str, distance: u64, sha: &str) -> Result<String, String> {
+    if distance == 0 {
+        return Ok(version.to_string());
+    }
+    let parsed: Pep440Version = version
+        .parse()
+        .map_err(|e| format!("Can't derive a dev version from '{version}': {e}"))?;
+    let mut release = parsed.release.clone();
+    let pre = match parsed.pre {
+        Some((phase, n)) => Some((phase, n + 1)),
+        None => {
+            release.resize(release.len().max(3), 0);
+            release[2] += 1;
+            release.truncate(3);
+            None
+        }
+    };
+    let next = Pep440Version {
+        epoch: parsed.epoch,
+        release,
+        pre,
+        post: None,
+        dev: Some(distance),
+        local: vec![format!("g{sha}")],
+    };
+    Ok(next.to_string())
+}
+
 fn git_add_all() -> Result<(), String> {
     git(&["add", "--all"])
 }
@@ -213,7 +595,7 @@ fn git_checkout_new_branch(name: &str) -> Result<(), String> {
 fn gh_pr_create(title: &str) -> Result<(), String> {
     println!("Running: gh pr create --title {:?} --body \"\"", title);
 
-    let output = Command::new("gh")
+    let output = Command::new(program("gh"))
         .args(["pr", "create", "--title", title, "--body", ""])
         .output()
         .map_err(|e| format!("Failed to run gh: {e}"))?;
@@ -225,3 +607,88 @@ fn gh_pr_create(title: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_date() {
+        let at = |secs: u64| UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        assert_eq!(utc_date(at(0)), "1970-01-01");
+        assert_eq!(utc_date(at(86_399)), "1970-01-01");
+        // 2000 is a leap year despite being a century
+        assert_eq!(utc_date(at(951_782_400)), "2000-02-29");
+        assert_eq!(utc_date(at(951_868_800)), "2000-03-01");
+        assert_eq!(utc_date(at(1_709_164_800)), "2024-02-29");
+        assert_eq!(utc_date(at(1_735_689_599)), "2024-12-31");
+        assert_eq!(render_git_at("v{new-version} ({date})", "1.0", "1.1", at(951_782_400)), "v1.1 (2000-02-29)");
+    }
+
+    #[test]
+    fn test_dev_version() {
+        assert_eq!(tag_glob("v{new-version}"), "v*");
+        assert_eq!(parse_describe("release-1.2.3-14-g1a2b3c4").unwrap(), ("release-1.2.3".to_string(), 14));
+        assert!(parse_describe("v1.2.3").is_err());
+        assert_eq!(tag_version("v{new-version}", "v1.2.3"), "1.2.3");
+        assert_eq!(tag_version("{new-version}-lts", "2.0-lts"), "2.0");
+        assert_eq!(format_dev_version("1.2.3", 3, "1a2b3c4").unwrap(), "1.2.4.dev3+g1a2b3c4");
+        assert_eq!(format_dev_version("1.2", 1, "abc").unwrap(), "1.2.1.dev1+gabc");
+        assert_eq!(format_dev_version("1.3.0rc1", 2, "abc").unwrap(), "1.3.0rc2.dev2+gabc");
+        assert_eq!(format_dev_version("1.2.3", 0, "abc").unwrap(), "1.2.3");
+    }
+
+    #[test]
+    fn test_pre_commit_args() {
+        let changed = [Path::new("pyproject.toml"), Path::new("src/version.py")];
+        let all: GitConfig = toml::from_str("").unwrap();
+        assert_eq!(pre_commit_args(&all, &changed).unwrap(), vec![vec!["run", "--all-files"]]);
+
+        let scoped: GitConfig =
+            toml::from_str("pre-commit-scope = \"changed-files\"\npre-commit-hooks = [\"ruff\", \"prettier\"]").unwrap();
+        assert_eq!(
+            pre_commit_args(&scoped, &changed).unwrap(),
+            vec![
+                vec!["run", "ruff", "--files", "pyproject.toml", "src/version.py"],
+                vec!["run", "prettier", "--files", "pyproject.toml", "src/version.py"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hook_fixed_files() {
+        let before = vec!["notes.txt".to_string()];
+        let after = vec!["notes.txt".to_string(), "src/version.py".to_string()];
+        assert_eq!(hook_fixed_files(&before, &after), vec!["src/version.py"]);
+        assert!(hook_fixed_files(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_bump_state_format() {
+        let state = BumpState {
+            version: "1.2.4".to_string(),
+            branch: "main".to_string(),
+            commit: "0123456789abcdef".to_string(),
+            tag: Some("v1.2.4".to_string()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"version":"1.2.4","branch":"main","commit":"0123456789abcdef","tag":"v1.2.4"}"#);
+        assert_eq!(serde_json::from_str::<BumpState>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_toggle_git_step() {
+        let git_config: GitConfig = toml::from_str(r#"actions = ["add-all", "commit", "tag", "push"]"#).unwrap();
+        let mut steps = git_plan(&git_config, "1.2.3", "1.2.4");
+        let descriptions: Vec<&str> = steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(descriptions[..3], ["add --all", "commit \"Bump version from 1.2.3 to 1.2.4\"", "tag 1.2.4"]);
+
+        let enabled = |steps: &[GitStep]| steps.iter().map(|s| s.enabled).collect::<Vec<_>>();
+        // Without a commit there's nothing to tag or push
+        toggle_git_step(&mut steps, 1);
+        assert_eq!(enabled(&steps), vec![true, false, false, false]);
+        // Pushing needs the commit again, but not the tag
+        toggle_git_step(&mut steps, 3);
+        assert_eq!(enabled(&steps), vec![true, true, false, true]);
+    }
+}
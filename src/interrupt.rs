@@ -0,0 +1,120 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of an interrupted bump (128 + SIGINT, as shells report it)
+pub const EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Catches Ctrl-C while alive, so a bump can roll back instead of dying halfway. Dropping it puts
+/// back whatever handled Ctrl-C before (the default, or e.g. the Python host's handler).
+pub struct Catch(platform::Previous);
+
+/// Catch Ctrl-C until the returned guard is dropped
+pub fn catch() -> Catch {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    Catch(platform::install())
+}
+
+impl Drop for Catch {
+    fn drop(&mut self) {
+        platform::restore(&self.0);
+    }
+}
+
+/// Record an interruption, e.g. Ctrl-C pressed in the TUI (where it arrives as a key)
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// An error once the run was interrupted
+pub fn check() -> Result<(), String> {
+    if interrupted() { Err("Interrupted".to_string()) } else { Ok(()) }
+}
+
+#[cfg(unix)]
+mod platform {
+    pub type Previous = Option<libc::sigaction>;
+
+    extern "C" fn handle(_: libc::c_int) {
+        super::interrupt();
+    }
+
+    pub fn install() -> Previous {
+        // SAFETY: both structs are fully initialized, and the handler only stores an atomic
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handle as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            let mut previous: libc::sigaction = std::mem::zeroed();
+            (libc::sigaction(libc::SIGINT, &action, &mut previous) == 0).then_some(previous)
+        }
+    }
+
+    pub fn restore(previous: &Previous) {
+        if let Some(previous) = previous {
+            // SAFETY: `previous` is what `sigaction` reported as installed before
+            unsafe { libc::sigaction(libc::SIGINT, previous, std::ptr::null_mut()) };
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows_sys::Win32::System::Console::{CTRL_C_EVENT, SetConsoleCtrlHandler};
+    use windows_sys::core::BOOL;
+
+    pub type Previous = bool;
+
+    unsafe extern "system" fn handle(ctrl_type: u32) -> BOOL {
+        if ctrl_type != CTRL_C_EVENT {
+            return 0;
+        }
+        super::interrupt();
+        1
+    }
+
+    /// Console handlers stack, so only ours is removed again and earlier ones are left alone
+    pub fn install() -> Previous {
+        // SAFETY: `handle` only stores an atomic
+        unsafe { SetConsoleCtrlHandler(Some(handle), 1) != 0 }
+    }
+
+    pub fn restore(installed: &Previous) {
+        if *installed {
+            // SAFETY: removes the handler added by `install`
+            unsafe { SetConsoleCtrlHandler(Some(handle), 0) };
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn current_handler() -> libc::sighandler_t {
+        // SAFETY: only queries the installed action
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGINT, std::ptr::null(), &mut action);
+            action.sa_sigaction
+        }
+    }
+
+    #[test]
+    fn test_catch_restores_previous_handler() {
+        let before = current_handler();
+        let catching = catch();
+        assert_ne!(current_handler(), before);
+        // SAFETY: SIGINT is caught by the handler installed above
+        unsafe { libc::raise(libc::SIGINT) };
+        assert!(interrupted());
+        assert!(check().is_err());
+        drop(catching);
+        assert_eq!(current_handler(), before);
+    }
+}
@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cast::{
+    cast_to_cargo, cast_to_dotnet, cast_to_pvp, cast_to_python, cast_to_r, cast_to_rubygems, cast_to_semver, cast_to_simple,
+};
+use crate::version::{
+    validate_cargo, validate_dotnet, validate_python, validate_pvp, validate_r, validate_rubygems, validate_semver, validate_simple,
+};
+
+/// A version format a file can hold
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionKind {
+    #[default]
+    Any,
+    Simple,
+    Python,
+    Semver,
+    Cargo,
+    Dotnet,
+    Rubygems,
+    R,
+    Pvp,
+}
+
+/// How a kind validates versions and casts other versions into its format
+pub struct KindSpec {
+    pub kind: VersionKind,
+    /// The name used in config files
+    pub name: &'static str,
+    pub validate: fn(&str) -> Result<(), String>,
+    pub cast: fn(&str) -> Result<String, String>,
+}
+
+/// Every supported kind. To add one, add a `VersionKind` variant and register its
+/// validator and caster here.
+pub static KINDS: &[KindSpec] = &[
+    KindSpec {
+        kind: VersionKind::Any,
+        name: "any",
+        validate: |_| Ok(()),
+        cast: |version| Ok(version.to_string()),
+    },
+    KindSpec {
+        kind: VersionKind::Simple,
+        name: "simple",
+        validate: validate_simple,
+        cast: cast_to_simple,
+    },
+    KindSpec {
+        kind: VersionKind::Python,
+        name: "python",
+        validate: validate_python,
+        cast: cast_to_python,
+    },
+    KindSpec {
+        kind: VersionKind::Semver,
+        name: "semver",
+        validate: |version| validate_semver(version).map(|_| ()),
+        cast: cast_to_semver,
+    },
+    KindSpec {
+        kind: VersionKind::Cargo,
+        name: "cargo",
+        validate: validate_cargo,
+        cast: cast_to_cargo,
+    },
+    KindSpec {
+        kind: VersionKind::Dotnet,
+        name: "dotnet",
+        validate: validate_dotnet,
+        cast: cast_to_dotnet,
+    },
+    KindSpec {
+        kind: VersionKind::Rubygems,
+        name: "rubygems",
+        validate: validate_rubygems,
+        cast: cast_to_rubygems,
+    },
+    KindSpec {
+        kind: VersionKind::R,
+        name: "r",
+        validate: validate_r,
+        cast: cast_to_r,
+    },
+    KindSpec {
+        kind: VersionKind::Pvp,
+        name: "pvp",
+        validate: validate_pvp,
+        cast: cast_to_pvp,
+    },
+];
+
+impl VersionKind {
+    pub const ALL: [VersionKind; 9] = [
+        VersionKind::Any,
+        VersionKind::Simple,
+        VersionKind::Python,
+        VersionKind::Semver,
+        VersionKind::Cargo,
+        VersionKind::Dotnet,
+        VersionKind::Rubygems,
+        VersionKind::R,
+        VersionKind::Pvp,
+    ];
+
+    /// The registered validator and caster for this kind
+    pub fn spec(self) -> &'static KindSpec {
+        KINDS
+            .iter()
+            .find(|spec| spec.kind == self)
+            .expect("every VersionKind is registered in KINDS")
+    }
+
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+}
+
+impl FromStr for VersionKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        KINDS.iter().find(|spec| spec.name == name).map(|spec| spec.kind).ok_or_else(|| {
+            let names: Vec<&str> = KINDS.iter().map(|spec| spec.name).collect();
+            format!("Unknown version kind '{name}' (expected one of: {})", names.join(", "))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_kind_is_registered() {
+        assert_eq!(KINDS.len(), VersionKind::ALL.len());
+        for kind in VersionKind::ALL {
+            let spec = kind.spec();
+            let parsed: VersionKind = serde_json::from_value(spec.name.into()).unwrap();
+            assert_eq!(parsed, kind);
+            assert_eq!(spec.name.parse::<VersionKind>().unwrap(), kind);
+        }
+        assert!("pep440".parse::<VersionKind>().is_err());
+    }
+}
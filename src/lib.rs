@@ -1,20 +1,57 @@
+pub mod artifacts;
+pub mod buffer;
 pub mod bump;
 pub mod cast;
+pub mod check;
+pub mod color;
+pub mod docker;
+pub mod engine;
+pub mod export;
 pub mod finders;
 pub mod git;
+pub mod interrupt;
+pub mod kind;
 pub mod loader;
+pub mod manifest;
+pub mod pager;
+pub mod pep440;
+pub mod pins;
+pub mod presets;
+pub mod process;
 pub mod schema;
+pub mod sort;
+pub mod specifier;
+pub mod template;
+pub mod timings;
 pub mod tui;
 pub mod version;
+pub mod vfs;
 
 #[cfg(feature = "python")]
 mod python;
 
 use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Instant;
 
-use bump::bump_version;
+use bump::{bump_version, list_files, new_version, read_version_from, BumpOptions, BumpTarget};
+use check::check_config;
 use clap::{Parser, Subcommand};
-use loader::load_config;
+use color::ColorChoice;
+use docker::docker_tags;
+use engine::{apply, check_line_spans, propose_changes, ReplaceOptions};
+use export::{format_vars, version_vars, ExportFormat};
+use git::{current_branch, dev_version, push_recorded_bump, tag_current_version};
+use kind::VersionKind;
+use loader::load_config_from;
+use pager::page;
+use pep440::normalize;
+use schema::{Action, Config};
+use sort::{max_version, sort_versions, version_diff};
+use specifier::satisfies;
+use template::{Components, Part};
+use timings::Timings;
+use vfs::OsFs;
 
 #[derive(Parser)]
 #[command(name = "bver")]
@@ -24,23 +61,238 @@ use loader::load_config;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Never page long output (`--plan`, `--diff`, `config`) through `$PAGER`
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// When to color output (`auto` colors a terminal unless `NO_COLOR` is set)
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Use the outermost config above the current directory instead of the nearest one
+    #[arg(long, global = true)]
+    root: bool,
+
+    /// Run as if started in this directory (like `git -C`), without changing the working directory
+    #[arg(short = 'C', long, global = true, value_name = "PATH")]
+    directory: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Show current version
-    Current,
+    Current {
+        /// Show a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+
+        /// Read the version out of this configured file instead of the config
+        #[arg(long, value_name = "FILE")]
+        from: Option<PathBuf>,
+
+        /// Only print one component of the version
+        #[arg(long, value_enum)]
+        part: Option<Part>,
+    },
     /// Show full config
-    Config,
+    Config {
+        /// Only print the path of the config file in use
+        #[arg(long)]
+        path: bool,
+    },
+    /// Validate the whole config and report every problem found, with the line it's on
+    CheckConfig,
+    /// List every configured file with its kind, the version it should contain, and how often it's found
+    Files {
+        /// List the files of a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+    },
+    /// Print the current (and optionally next) version as environment variables, e.g. `eval "$(bver export)"`
+    Export {
+        /// Print as environment variable assignments (the default)
+        #[arg(long, conflicts_with = "format")]
+        env: bool,
+
+        /// Output syntax
+        #[arg(long, value_enum, default_value = "env")]
+        format: ExportFormat,
+
+        /// Also export the version a bump to this target would produce, as `BVER_NEW_*`
+        #[arg(long)]
+        bump: Option<String>,
+
+        /// Export a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+    },
+    /// Check whether a version satisfies a range; exits with 0 if it does, 1 if not
+    Satisfies {
+        /// Version to check (e.g. 2.1.0)
+        version: String,
+        /// PEP 440 specifiers (e.g. ">=2.0, <3.0"), or a semver range if it uses semver-only
+        /// syntax (e.g. "^2.1 || ^3")
+        range: String,
+    },
+    /// Sort versions (given as arguments or one per line on stdin) from oldest to newest
+    Sort {
+        versions: Vec<String>,
+
+        /// Newest first
+        #[arg(short, long)]
+        reverse: bool,
+    },
+    /// Show which components changed between two versions; exits with 0 if `new` is newer, 1 if not
+    Diff {
+        /// Version before the change (e.g. 1.2.3)
+        old: String,
+        /// Version after the change (e.g. 1.3.0rc1)
+        new: String,
+    },
+    /// Print the newest of the given versions (arguments or one per line on stdin)
+    Max { versions: Vec<String> },
+    /// Print a version in canonical PEP 440 form
+    Normalize {
+        /// Version to normalize (e.g. 1.0.0-alpha.1)
+        version: String,
+    },
+    /// Replace a version in text from stdin (or a file) and print the result, without writing anything
+    Replace {
+        /// Version to replace
+        #[arg(long)]
+        old: String,
+
+        /// Version to write instead
+        #[arg(long)]
+        new: String,
+
+        /// Template for the text to find (default: "{current-version}")
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Template for the replacement text (default: "{new-version}")
+        #[arg(long)]
+        replace: Option<String>,
+
+        /// Literal text that must come right before the version (and is kept)
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Literal text that must come right after the version (and is kept)
+        #[arg(long)]
+        suffix: Option<String>,
+
+        /// Only replace between lines containing `bver:start` and `bver:end`
+        #[arg(long)]
+        markers: bool,
+
+        /// File to read, or `-` for stdin
+        #[arg(default_value = "-")]
+        input: String,
+    },
+    /// Print the docker image tags to publish for the current (or next) version, one per line
+    DockerTags {
+        /// Use the version a bump to this target would produce
+        #[arg(long)]
+        bump: Option<String>,
+
+        /// Use a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+    },
+    /// Print a development version from the last release tag and the commits since, e.g. 1.2.4.dev3+g1a2b3c4
+    DevVersion {
+        /// Use a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+    },
+    /// Create the git tag for the current version (e.g. on merge, after the files were bumped in a PR)
+    Tag {
+        /// Push the tag to origin (default: only when the git actions include push)
+        #[arg(long, conflicts_with = "no_push")]
+        push: bool,
+
+        /// Don't push the tag, even when the git actions include push
+        #[arg(long)]
+        no_push: bool,
+
+        /// Overwrite an existing tag (and force push it)
+        #[arg(short, long)]
+        force: bool,
+
+        /// Tag a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
+    },
+    /// Push the commit and tag created by the last bump (e.g. after reviewing it locally)
+    Push {
+        /// Force push
+        #[arg(short, long)]
+        force: bool,
+    },
     /// Bump version
     Bump {
-        /// Version component (major, minor, patch) or explicit version (e.g. 1.2.3)
-        #[arg(default_value = "patch")]
-        target: String,
+        /// Version component (major, minor, patch), components chained with commas
+        /// (e.g. minor,alpha), or explicit version (e.g. 1.2.3) [default: patch]
+        #[arg(conflicts_with = "new_version")]
+        target: Option<String>,
+
+        /// Also start a pre-release of the bumped version (e.g. `minor --pre alpha`)
+        #[arg(long, value_parser = ["alpha", "beta", "rc", "dev"], conflicts_with = "new_version")]
+        pre: Option<String>,
+
+        /// Set this exact version instead of computing one
+        #[arg(long)]
+        new_version: Option<String>,
 
         /// Force git operations (tag, push)
         #[arg(short, long)]
         force: bool,
+
+        /// Allow casting to drop parts of the version (e.g. `.post1` in a semver file)
+        #[arg(long)]
+        allow_lossy: bool,
+
+        /// Allow an explicit version that is older than (or equal to) the current one
+        #[arg(long)]
+        allow_downgrade: bool,
+
+        /// Only update these configured files (paths or globs relative to the project root)
+        #[arg(long, num_args = 1..)]
+        files: Vec<String>,
+
+        /// Leave these configured files untouched for this run
+        #[arg(long, num_args = 1..)]
+        exclude: Vec<String>,
+
+        /// Only update files of this kind (e.g. python, semver)
+        #[arg(long)]
+        only_kind: Option<VersionKind>,
+
+        /// Print the proposed changes as JSON (sorted by path, then line) without applying them
+        #[arg(long)]
+        plan: bool,
+
+        /// Print the proposed changes as a unified diff (e.g. to pipe into a diff viewer) without applying them
+        #[arg(long, conflicts_with = "plan")]
+        diff: bool,
+
+        /// Confirm a major bump up front when `protect-major` is set (instead of typing the version in the TUI)
+        #[arg(long)]
+        confirm_major: bool,
+
+        /// Write the proposed changes to a patch file without applying them (apply it later with `git apply`)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["plan", "diff"])]
+        emit_patch: Option<PathBuf>,
+
+        /// Report how long each phase took (config load, scanning each file, TUI, writing, git) on stderr
+        #[arg(long)]
+        timings: bool,
+
+        /// Bump a named version from `[versions.<name>]` instead of the main one
+        #[arg(long)]
+        version_name: Option<String>,
     },
 }
 
@@ -56,32 +308,427 @@ where
     run_from(Cli::parse_from(args));
 }
 
+/// Apply the `[[branch]]` policy of the checked out branch
+fn with_branch_policy(config: Config) -> Config {
+    if config.branches.is_empty() {
+        return config;
+    }
+    match current_branch() {
+        Some(branch) => config.for_branch(&branch),
+        None => config,
+    }
+}
+
+/// The directory bver works in: `--directory`, or `GIT_WORK_TREE` when started outside of it
+fn work_dir(directory: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    let cwd = std::env::current_dir().ok();
+    let dir = directory.or_else(|| {
+        let tree = std::path::absolute(std::env::var_os("GIT_WORK_TREE")?).ok()?;
+        (!cwd.as_ref().is_some_and(|cwd| cwd.starts_with(&tree))).then_some(tree)
+    });
+    let Some(dir) = dir else {
+        return Ok(None);
+    };
+    let dir = std::path::absolute(&dir).map_err(|e| format!("Invalid directory {}: {e}", dir.display()))?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+    Ok(Some(dir))
+}
+
 fn run_from(cli: Cli) {
-    let config = load_config();
+    let work_dir = match work_dir(cli.directory.clone()) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    // Paths given on the command line are relative to the working directory too
+    let in_work_dir = |path: PathBuf| match &work_dir {
+        Some(dir) => dir.join(path),
+        None => path,
+    };
+    OsFs::set_current_dir(work_dir.clone());
+
+    let started = Instant::now();
+    let config = load_config_from(&OsFs, cli.root);
+    let config_load = started.elapsed();
+
+    // Commands that read the config report a broken config or a bad current version up front
+    let uses_config = matches!(
+        cli.command,
+        Commands::Current { .. }
+            | Commands::Config { .. }
+            | Commands::Files { .. }
+            | Commands::Export { .. }
+            | Commands::DockerTags { .. }
+            | Commands::DevVersion { .. }
+            | Commands::Tag { .. }
+            | Commands::Bump { .. }
+    );
+    let config = match config {
+        Ok(config) => config,
+        Err(e) if uses_config || matches!(cli.command, Commands::CheckConfig) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        Err(_) => None,
+    };
+    if uses_config
+        && let Some(config) = &config
+        && let Err(e) = config.validate_current_version()
+    {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
 
     match cli.command {
-        Commands::Current => {
+        Commands::Current { version_name, from, part } => {
             if let Some(config) = config {
-                if let Some(version) = config.current_version {
-                    println!("{version}");
-                } else {
-                    eprintln!("No current_version found in config");
+                let config = match version_name {
+                    Some(name) => match config.select_version(&name) {
+                        Ok(config) => config,
+                        Err(e) => {
+                            eprintln!("Error: {e}");
+                            std::process::exit(1);
+                        }
+                    },
+                    None => config,
+                };
+                let version = match &from {
+                    Some(path) => read_version_from(&OsFs, &config, path),
+                    None => config.current_version.clone().ok_or_else(|| "No current_version found in config".to_string()),
+                };
+                match version {
+                    Ok(version) => match part {
+                        Some(part) => println!("{}", Components::parse(&version).get(part)),
+                        None => println!("{version}"),
+                    },
+                    Err(e) => {
+                        eprintln!("Error: {e}");
+                        std::process::exit(1);
+                    }
                 }
             } else {
                 eprintln!("No config found");
             }
         }
-        Commands::Config => {
+        Commands::Satisfies { version, range } => match satisfies(&version, &range) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(2);
+            }
+        },
+        Commands::Sort { versions, reverse } => {
+            let (mut sorted, skipped) = sort_versions(&versions_or_stdin(versions));
+            warn_skipped(&skipped);
+            if reverse {
+                sorted.reverse();
+            }
+            for version in sorted {
+                println!("{version}");
+            }
+        }
+        Commands::Diff { old, new } => {
+            let kind = config.as_ref().map(|c| c.default_kind).unwrap_or_default();
+            match version_diff(&old, &new, kind) {
+                Ok(diff) => {
+                    let verdict = match diff.ordering {
+                        std::cmp::Ordering::Less => "upgrade",
+                        std::cmp::Ordering::Equal => "no change",
+                        std::cmp::Ordering::Greater => "downgrade",
+                    };
+                    println!("{old} -> {new}: {verdict}");
+                    for (name, a, b) in &diff.changed {
+                        let show = |v: &str| if v.is_empty() { "-".to_string() } else { v.to_string() };
+                        println!("  {name:<5}  {} -> {}", show(a), show(b));
+                    }
+                    if diff.ordering != std::cmp::Ordering::Less {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Max { versions } => {
+            let versions = versions_or_stdin(versions);
+            warn_skipped(&sort_versions(&versions).1);
+            match max_version(&versions) {
+                Some(max) => println!("{max}"),
+                None => {
+                    eprintln!("Error: no versions given");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Normalize { version } => match normalize(&version) {
+            Ok(normalized) => println!("{normalized}"),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Replace { old, new, search, replace, prefix, suffix, markers, input } => {
+            let content = if input == "-" {
+                std::io::read_to_string(std::io::stdin()).map_err(|e| format!("Failed to read stdin: {e}"))
+            } else {
+                std::fs::read_to_string(in_work_dir(PathBuf::from(&input))).map_err(|e| format!("Failed to read {input}: {e}"))
+            };
+            let options = ReplaceOptions { search, replace, prefix, suffix, markers, ..ReplaceOptions::default() };
+            let result = content.and_then(|content| {
+                let (search, replace) = options.render(&old, &new);
+                check_line_spans(&search, &replace)?;
+                let changes = propose_changes(&content, &old, &new, &options)?;
+                apply(&content, &changes).map(|content| (content, changes.len()))
+            });
+            match result {
+                Ok((content, count)) => {
+                    if count == 0 {
+                        eprintln!("Warning: '{old}' not found in {input}");
+                    }
+                    print!("{content}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Config { path } => {
             if let Some(config) = config {
-                println!("{}", toml::to_string_pretty(&config).unwrap());
+                let source = config.source.as_ref().map(|source| source.path().display().to_string());
+                if path {
+                    println!("{}", source.unwrap_or_default());
+                } else {
+                    // Say which file was picked, as a comment so the output stays valid TOML
+                    let header = source.map(|source| format!("# {source}\n")).unwrap_or_default();
+                    page(&format!("{header}{}\n", toml::to_string_pretty(&config).unwrap()), !cli.no_pager);
+                }
             } else {
                 eprintln!("No config found");
             }
         }
-        Commands::Bump { target, force } => {
+        Commands::CheckConfig => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                std::process::exit(1);
+            };
+            let source = config
+                .source
+                .as_ref()
+                .map_or_else(|| "config".to_string(), |source| source.path().display().to_string());
+            let problems = check_config(&OsFs, &config);
+            for problem in &problems {
+                match problem.line {
+                    Some(line) => println!("{source}:{line}: {}", problem.message),
+                    None => println!("{source}: {}", problem.message),
+                }
+            }
+            if !problems.is_empty() {
+                eprintln!("Found {} problem(s)", problems.len());
+                std::process::exit(1);
+            }
+            println!("No problems found in {source}");
+        }
+        Commands::Files { version_name } => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                return;
+            };
+            let result = match version_name {
+                Some(name) => config.select_version(&name),
+                None => Ok(config),
+            };
+            match result.and_then(|config| list_files(&OsFs, &config)) {
+                Ok(reports) => {
+                    let width = |f: fn(&bump::FileReport) -> usize| reports.iter().map(f).max().unwrap_or(0);
+                    let path_width = width(|r| r.path.len());
+                    let kind_width = width(|r| r.kind.name().len());
+                    let version_width = width(|r| r.version.as_ref().map_or(1, String::len));
+                    let mut healthy = true;
+                    for report in &reports {
+                        let matches = match &report.matches {
+                            Ok(0) => "not found".to_string(),
+                            Ok(count) => format!("{count} match(es)"),
+                            Err(e) => format!("error: {e}"),
+                        };
+                        healthy &= report.matches.as_ref().is_ok_and(|count| *count > 0);
+                        println!(
+                            "{:path_width$}  {:kind_width$}  {:version_width$}  {matches}",
+                            report.path,
+                            report.kind.name(),
+                            report.version.as_deref().unwrap_or("-"),
+                        );
+                    }
+                    if !healthy {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Export { env: _, format, bump, version_name } => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                return;
+            };
+            let result = match version_name {
+                Some(name) => config.select_version(&name),
+                None => Ok(config),
+            };
+            let result = result.and_then(|config| {
+                let current = config.current_version.as_deref().ok_or("No current_version found in config")?;
+                let new = bump
+                    .map(|target| new_version(&config, current, &BumpTarget::Auto(target)))
+                    .transpose()?;
+                Ok(format_vars(&version_vars(current, new.as_deref()), format))
+            });
+            match result {
+                Ok(output) => print!("{output}"),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::DockerTags { bump, version_name } => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                return;
+            };
+            let result = match version_name {
+                Some(name) => config.select_version(&name),
+                None => Ok(config),
+            };
+            let result = result.and_then(|config| {
+                let current = config.current_version.as_deref().ok_or("No current_version found in config")?;
+                let version = match bump {
+                    Some(target) => new_version(&config, current, &BumpTarget::Auto(target))?,
+                    None => current.to_string(),
+                };
+                Ok(docker_tags(&config.docker.clone().unwrap_or_default(), &version))
+            });
+            match result {
+                Ok(tags) => {
+                    for tag in tags {
+                        println!("{tag}");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::DevVersion { version_name } => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                std::process::exit(1);
+            };
+            let result = match version_name {
+                Some(name) => config.select_version(&name),
+                None => Ok(config),
+            };
+            let result = result.map(with_branch_policy).and_then(|config| {
+                let current = config.current_version.as_deref().ok_or("No current_version found in config")?;
+                dev_version(&config.git, current)
+            });
+            match result {
+                Ok(version) => println!("{version}"),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Tag { push, no_push, force, version_name } => {
+            let Some(config) = config else {
+                eprintln!("No config found");
+                return;
+            };
+            let result = match version_name {
+                Some(name) => config.select_version(&name),
+                None => Ok(config),
+            };
+            let result = result.map(with_branch_policy).and_then(|config| {
+                let version = config.current_version.as_deref().ok_or("No current_version found in config")?;
+                let push = push || (!no_push && config.git.has(Action::Push));
+                tag_current_version(&config.git, version, force, push)
+            });
+            if let Err(e) = result {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Push { force } => {
+            if let Err(e) = push_recorded_bump(force) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Bump {
+            target,
+            pre,
+            new_version,
+            force,
+            allow_lossy,
+            allow_downgrade,
+            files,
+            exclude,
+            only_kind,
+            plan,
+            diff,
+            confirm_major,
+            emit_patch,
+            timings,
+            version_name,
+        } => {
+            let target = match (new_version, pre) {
+                (Some(version), _) => BumpTarget::Version(version),
+                (None, Some(pre)) => BumpTarget::Auto(format!("{},{pre}", target.as_deref().unwrap_or("patch"))),
+                (None, None) => BumpTarget::Auto(target.unwrap_or_else(|| "patch".to_string())),
+            };
+            let timings = if timings { Timings::enabled() } else { Timings::default() };
+            timings.add("load config", config_load);
+            let emit_patch = emit_patch.map(in_work_dir);
+            let options = BumpOptions {
+                force,
+                allow_lossy,
+                allow_downgrade,
+                files,
+                exclude,
+                only_kind,
+                plan,
+                diff,
+                emit_patch,
+                pager: !cli.no_pager,
+                color: cli.color.enabled(),
+                confirm_major,
+                timings,
+            };
             if let Some(config) = config {
-                if let Err(e) = bump_version(&config, &target, force) {
+                let result = match version_name {
+                    Some(name) => config.select_version(&name),
+                    None => Ok(config),
+                };
+                let result = result.map(with_branch_policy);
+                let result = result.and_then(|config| bump_version(&OsFs, &config, &target, &options));
+                if options.timings.is_enabled() {
+                    eprint!("{}", options.timings.report());
+                }
+                if let Err(e) = result {
                     eprintln!("Error: {e}");
+                    let code = if interrupt::interrupted() { interrupt::EXIT_CODE } else { 1 };
+                    std::process::exit(code);
                 }
             } else {
                 eprintln!("No config found");
@@ -89,3 +736,17 @@ fn run_from(cli: Cli) {
         }
     }
 }
+
+/// The versions given as arguments, or one per line on stdin when there are none
+fn versions_or_stdin(versions: Vec<String>) -> Vec<String> {
+    if !versions.is_empty() {
+        return versions;
+    }
+    std::io::stdin().lines().map_while(Result::ok).collect()
+}
+
+fn warn_skipped(skipped: &[String]) {
+    for version in skipped {
+        eprintln!("Skipping '{version}': not a version");
+    }
+}
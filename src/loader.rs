@@ -1,62 +1,221 @@
-use std::fs;
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-use crate::finders::{find_bver_toml, find_cargo_toml, find_package_json, find_pyproject_toml};
-use crate::schema::Config;
+use regex::Regex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
 
-pub fn load_config() -> Option<Config> {
-    load_from_bver_toml()
-        .or_else(load_from_pyproject_toml)
-        .or_else(load_from_package_json)
-        .or_else(load_from_cargo_toml)
+use crate::finders::{expand_glob, search_dirs};
+use crate::kind::VersionKind;
+use crate::schema::{Config, ConfigSource, VersionField};
+use crate::vfs::Vfs;
+
+/// A config file that exists but can't be read, e.g. because of a syntax error or an invalid value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    /// The (1-based) line of the problem, when known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.path.display(), self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl ConfigError {
+    fn new(path: &Path, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+// Where manifests keep their bver section. These are only deserialized for their errors, which
+// (unlike those of deserializing an already parsed section) know their position in the file.
+
+/// `bver` of a package.json, or the `bver` table of the two below
+#[derive(Deserialize)]
+struct BverSection {
+    #[serde(rename = "bver")]
+    _bver: Config,
+}
+
+/// `[tool.bver]` of a pyproject.toml
+#[derive(Deserialize)]
+struct PyprojectSection {
+    #[serde(rename = "tool")]
+    _tool: BverSection,
+}
+
+/// `[package.metadata.bver]` of a Cargo.toml
+#[derive(Deserialize)]
+struct CargoSection {
+    #[serde(rename = "package")]
+    _package: CargoPackageSection,
+}
+
+#[derive(Deserialize)]
+struct CargoPackageSection {
+    #[serde(rename = "metadata")]
+    _metadata: BverSection,
+}
+
+/// Find and load the nearest config, walking up from the current directory. Files without a bver
+/// section are skipped, but a config file that can't be parsed is an error rather than a reason
+/// to look elsewhere.
+pub fn load_config(fs: &dyn Vfs) -> Result<Option<Config>, ConfigError> {
+    load_config_from(fs, false)
+}
+
+/// Like [`load_config`], but with `root` the outermost config above the current directory wins
+/// instead of the nearest (e.g. to bump a whole monorepo from inside one of its packages)
+pub fn load_config_from(fs: &dyn Vfs, root: bool) -> Result<Option<Config>, ConfigError> {
+    let mut dirs = search_dirs(fs);
+    if root {
+        dirs.reverse();
+    }
+    // No bver section anywhere: fall back to a plain manifest that carries a version
+    for require_section in [true, false] {
+        for dir in &dirs {
+            if let Some(config) = load_from_dir(fs, dir, require_section)? {
+                return Ok(Some(config));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// The config in `dir`: bver.toml first, then the bver section of a pyproject.toml, package.json
+/// or Cargo.toml (or, without `require_section`, just the version of one of those manifests)
+fn load_from_dir(fs: &dyn Vfs, dir: &Path, require_section: bool) -> Result<Option<Config>, ConfigError> {
+    if require_section && let Some(config) = load_from_bver_toml(fs, &dir.join("bver.toml"))? {
+        return Ok(Some(config));
+    }
+    if let Some(config) = load_from_pyproject_toml(fs, &dir.join("pyproject.toml"), require_section)? {
+        return Ok(Some(config));
+    }
+    if let Some(config) = load_from_package_json(fs, &dir.join("package.json"), require_section)? {
+        return Ok(Some(config));
+    }
+    load_from_cargo_toml(fs, &dir.join("Cargo.toml"), require_section)
 }
 
-fn load_from_bver_toml() -> Option<Config> {
-    let path = find_bver_toml()?;
-    load_toml_config(&path)
+fn load_from_bver_toml(fs: &dyn Vfs, path: &Path) -> Result<Option<Config>, ConfigError> {
+    if !fs.exists(path) {
+        return Ok(None);
+    }
+    let content = read(fs, path)?;
+    let value = parse_toml(path, &content)?;
+    let mut config = toml_config::<Config>(path, &content, value)?;
+    config.source = Some(ConfigSource::BverToml(path.to_path_buf()));
+    Ok(Some(config))
 }
 
-fn load_from_pyproject_toml() -> Option<Config> {
-    let path = find_pyproject_toml()?;
-    let content = fs::read_to_string(&path).ok()?;
-    let value: toml::Value = toml::from_str(&content).ok()?;
-    let bver_config = value.get("tool")?.get("bver")?;
-    let mut config: Config = toml::Value::try_into(bver_config.clone()).ok()?;
+fn load_from_pyproject_toml(fs: &dyn Vfs, path: &Path, require_section: bool) -> Result<Option<Config>, ConfigError> {
+    if !fs.exists(path) {
+        return Ok(None);
+    }
+    let content = read(fs, path)?;
+    let value = parse_toml(path, &content)?;
+    let mut config: Config = match value.get("tool").and_then(|t| t.get("bver")) {
+        Some(bver_config) => toml_config::<PyprojectSection>(path, &content, bver_config.clone())?,
+        None if !require_section => Config::default(),
+        None => return Ok(None),
+    };
 
     if config.current_version.is_none() {
-        config.current_version = value
-            .get("project")
-            .and_then(|p| p.get("version"))
-            .and_then(|v| v.as_str())
-            .map(String::from);
+        // PEP 621 metadata first, then Poetry's legacy layout
+        let version_keys: [&[&str]; 2] = [&["project", "version"], &["tool", "poetry", "version"]];
+        for key in version_keys {
+            let version = key
+                .iter()
+                .try_fold(&value, |v, k| v.get(k))
+                .and_then(|v| v.as_str());
+            if let Some(version) = version {
+                config.current_version = Some(version.to_string());
+                config.version_fields.push(VersionField::new(path, key, VersionKind::Python));
+                break;
+            }
+        }
     }
 
-    Some(config)
+    if !require_section && config.current_version.is_none() {
+        return Ok(None);
+    }
+    config.source = Some(ConfigSource::PyprojectToml(path.to_path_buf()));
+    Ok(Some(config))
 }
 
-fn load_from_package_json() -> Option<Config> {
-    let path = find_package_json()?;
-    let content = fs::read_to_string(&path).ok()?;
-    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
-    let bver_config = value.get("bver")?;
-    let mut config: Config = serde_json::from_value(bver_config.clone()).ok()?;
+fn load_from_package_json(fs: &dyn Vfs, path: &Path, require_section: bool) -> Result<Option<Config>, ConfigError> {
+    if !fs.exists(path) {
+        return Ok(None);
+    }
+    let content = read(fs, path)?;
+    let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| json_error(path, "Invalid JSON: ", &e))?;
+    let mut config: Config = match value.get("bver") {
+        Some(bver_config) => serde_json::from_value(json_with_env(bver_config.clone())).map_err(|e| {
+            // Deserialize from the text again to find where the problem is
+            match serde_json::from_str::<BverSection>(&content) {
+                Err(located) => json_error(path, "", &located),
+                Ok(_) => ConfigError::new(path, None, e.to_string()),
+            }
+        })?,
+        None if !require_section => Config::default(),
+        None => return Ok(None),
+    };
 
     if config.current_version.is_none() {
         config.current_version = value
             .get("version")
             .and_then(|v| v.as_str())
             .map(String::from);
+        if config.current_version.is_some() {
+            config.version_fields.push(VersionField::new(path, &["version"], VersionKind::Semver));
+            let members = match config.sync_workspaces {
+                true => find_workspace_manifests(fs, path, &value, &config.exclude),
+                false => Vec::new(),
+            };
+            if config.sync_lockfiles {
+                config.version_fields.extend(npm_lock_fields(fs, path, &members));
+            }
+            config.version_fields.extend(
+                members
+                    .into_iter()
+                    .map(|member| VersionField::new(member, &["version"], VersionKind::Semver)),
+            );
+        }
     }
 
-    Some(config)
+    if !require_section && config.current_version.is_none() {
+        return Ok(None);
+    }
+    config.source = Some(ConfigSource::PackageJson(path.to_path_buf()));
+    Ok(Some(config))
 }
 
-fn load_from_cargo_toml() -> Option<Config> {
-    let path = find_cargo_toml()?;
-    let content = fs::read_to_string(&path).ok()?;
-    let value: toml::Value = toml::from_str(&content).ok()?;
-    let bver_config = value.get("package")?.get("metadata")?.get("bver")?;
-    let mut config: Config = toml::Value::try_into(bver_config.clone()).ok()?;
+fn load_from_cargo_toml(fs: &dyn Vfs, path: &Path, require_section: bool) -> Result<Option<Config>, ConfigError> {
+    if !fs.exists(path) {
+        return Ok(None);
+    }
+    let content = read(fs, path)?;
+    let value = parse_toml(path, &content)?;
+    let bver_config = value
+        .get("package")
+        .and_then(|p| p.get("metadata"))
+        .and_then(|m| m.get("bver"));
+    let mut config: Config = match bver_config {
+        Some(bver_config) => toml_config::<CargoSection>(path, &content, bver_config.clone())?,
+        None if !require_section => Config::default(),
+        None => return Ok(None),
+    };
 
     if config.current_version.is_none() {
         config.current_version = value
@@ -64,12 +223,318 @@ fn load_from_cargo_toml() -> Option<Config> {
             .and_then(|p| p.get("version"))
             .and_then(|v| v.as_str())
             .map(String::from);
+        if config.current_version.is_some() {
+            config.version_fields.push(VersionField::new(path, &["package", "version"], VersionKind::Cargo));
+            if config.sync_lockfiles
+                && let Some(field) = cargo_lock_field(fs, path, &value)
+            {
+                config.version_fields.push(field);
+            }
+        }
+    }
+
+    if !require_section && config.current_version.is_none() {
+        return Ok(None);
+    }
+    config.source = Some(ConfigSource::CargoToml(path.to_path_buf()));
+    Ok(Some(config))
+}
+
+fn read(fs: &dyn Vfs, path: &Path) -> Result<String, ConfigError> {
+    fs.read_to_string(path).map_err(|e| ConfigError::new(path, None, e))
+}
+
+/// The 1-based line of a byte offset
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+fn parse_toml(path: &Path, content: &str) -> Result<toml::Value, ConfigError> {
+    toml::from_str(content).map_err(|e| {
+        let line = e.span().map(|span| line_at(content, span.start));
+        ConfigError::new(path, line, format!("Invalid TOML: {}", e.message()))
+    })
+}
+
+/// Deserialize a bver section (after interpolating environment variables). On failure, the
+/// file is deserialized again as `S`, the section in its place, to find the offending line.
+fn toml_config<S: DeserializeOwned>(path: &Path, content: &str, section: toml::Value) -> Result<Config, ConfigError> {
+    toml_with_env(section).try_into().map_err(|e: toml::de::Error| {
+        let line = toml::from_str::<S>(content)
+            .err()
+            .and_then(|located| located.span())
+            .map(|span| line_at(content, span.start));
+        // The error names the key it's about on a line of its own, e.g. "in `git.actions`"
+        let key = e.to_string().lines().find_map(|l| l.strip_prefix("in ").map(|key| key.trim_matches('`').to_string()));
+        let message = match key {
+            Some(key) => format!("{key}: {}", e.message()),
+            None => e.message().to_string(),
+        };
+        ConfigError::new(path, line, message)
+    })
+}
+
+fn json_error(path: &Path, prefix: &str, e: &serde_json::Error) -> ConfigError {
+    // serde_json appends the position, which the error already carries as its line
+    let message = e.to_string();
+    let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+    ConfigError::new(path, Some(e.line()).filter(|&line| line > 0), format!("{prefix}{message}"))
+}
+
+/// The `version` fields of the root package (and of synced workspace `members`)
+/// in the package-lock.json and npm-shrinkwrap.json next to `manifest`
+fn npm_lock_fields(fs: &dyn Vfs, manifest: &Path, members: &[PathBuf]) -> Vec<VersionField> {
+    let Some(root) = manifest.parent() else {
+        return Vec::new();
+    };
+    let mut fields = Vec::new();
+    for name in ["package-lock.json", "npm-shrinkwrap.json"] {
+        let lockfile = root.join(name);
+        if !fs.exists(&lockfile) {
+            continue;
+        }
+        fields.push(VersionField::new(&lockfile, &["version"], VersionKind::Semver));
+        fields.push(VersionField::new(&lockfile, &["packages", "", "version"], VersionKind::Semver));
+        for member in members {
+            let Some(dir) = member.parent().and_then(|dir| dir.strip_prefix(root).ok()) else {
+                continue;
+            };
+            let dir: Vec<String> = dir.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+            fields.push(VersionField::new(&lockfile, &["packages", &dir.join("/"), "version"], VersionKind::Semver));
+        }
+    }
+    fields
+}
+
+/// The `version` of the package's own `[[package]]` entry in the nearest Cargo.lock
+/// (next to the manifest, or at the workspace root above it)
+fn cargo_lock_field(fs: &dyn Vfs, manifest: &Path, value: &toml::Value) -> Option<VersionField> {
+    let package = value.get("package")?;
+    let name = package.get("name")?.as_str()?;
+    let version = package.get("version")?.as_str()?;
+
+    let lockfile = manifest
+        .parent()?
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| fs.exists(path))?;
+    let lock: toml::Value = toml::from_str(&fs.read_to_string(&lockfile).ok()?).ok()?;
+    let idx = lock.get("package")?.as_array()?.iter().position(|entry| {
+        entry.get("name").and_then(|n| n.as_str()) == Some(name)
+            && entry.get("version").and_then(|v| v.as_str()) == Some(version)
+            && entry.get("source").is_none()
+    })?;
+    let idx = idx.to_string();
+    Some(VersionField::new(lockfile, &["package", &idx, "version"], VersionKind::Cargo))
+}
+
+/// Find the `package.json` of every npm/pnpm workspace member next to a root `package.json`
+fn find_workspace_manifests(fs: &dyn Vfs, root_manifest: &Path, value: &serde_json::Value, excludes: &[String]) -> Vec<PathBuf> {
+    let Some(root) = root_manifest.parent() else {
+        return Vec::new();
+    };
+
+    // npm/yarn: "workspaces": [...] or "workspaces": { "packages": [...] }
+    let workspaces = value.get("workspaces");
+    let npm_patterns = workspaces
+        .and_then(|w| w.as_array().or_else(|| w.get("packages")?.as_array()))
+        .map(|patterns| patterns.iter().filter_map(|p| p.as_str()).map(String::from).collect())
+        .unwrap_or_default();
+
+    // pnpm: packages listed in pnpm-workspace.yaml
+    let patterns: Vec<String> = if workspaces.is_some() {
+        npm_patterns
+    } else {
+        fs.read_to_string(&root.join("pnpm-workspace.yaml"))
+            .map(|content| parse_pnpm_workspace(&content))
+            .unwrap_or_default()
+    };
+
+    let mut excludes = excludes.to_vec();
+    excludes.push("**/node_modules/**".to_string());
+    excludes.extend(patterns.iter().filter_map(|p| p.strip_prefix('!')).map(String::from));
+
+    let mut manifests: Vec<PathBuf> = patterns
+        .iter()
+        .filter(|p| !p.starts_with('!'))
+        .flat_map(|pattern| {
+            let pattern = format!("{}/package.json", pattern.trim_end_matches('/'));
+            expand_glob(fs, root, &pattern, &excludes).unwrap_or_default()
+        })
+        .filter(|manifest| manifest != root_manifest)
+        .collect();
+    manifests.sort();
+    manifests.dedup();
+    manifests
+}
+
+/// Read the `packages:` list from a pnpm-workspace.yaml
+fn parse_pnpm_workspace(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if in_packages && let Some(item) = trimmed.strip_prefix('-') {
+            patterns.push(item.trim().trim_matches(['"', '\'']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Replace every `${NAME}` in `text` with `lookup(NAME)`, leaving unknown names as they are
+fn interpolate(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    if !text.contains("${") {
+        return text.to_string();
+    }
+    let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| lookup(&caps[1]).unwrap_or_else(|| caps[0].to_string()))
+        .into_owned()
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// A bver config section with environment variables interpolated into its strings
+fn toml_with_env(value: toml::Value) -> toml::Value {
+    match value {
+        toml::Value::String(text) => toml::Value::String(interpolate(&text, &env_var)),
+        toml::Value::Array(items) => toml::Value::Array(items.into_iter().map(toml_with_env).collect()),
+        toml::Value::Table(table) => {
+            toml::Value::Table(table.into_iter().map(|(key, value)| (key, toml_with_env(value))).collect())
+        }
+        other => other,
     }
+}
 
-    Some(config)
+/// A bver config section with environment variables interpolated into its strings
+fn json_with_env(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(text) => serde_json::Value::String(interpolate(&text, &env_var)),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(json_with_env).collect()),
+        serde_json::Value::Object(map) => {
+            serde_json::Value::Object(map.into_iter().map(|(key, value)| (key, json_with_env(value))).collect())
+        }
+        other => other,
+    }
 }
 
-fn load_toml_config(path: &Path) -> Option<Config> {
-    let content = fs::read_to_string(path).ok()?;
-    toml::from_str(&content).ok()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_load_from_memory() {
+        let fs = MemoryFs::new("/project/src")
+            .with_file(
+                "/project/package.json",
+                r#"{"version": "1.2.3", "workspaces": ["packages/*"], "bver": {"sync-workspaces": true}}"#,
+            )
+            .with_file("/project/packages/web/package.json", r#"{"version": "1.2.3"}"#)
+            .with_file("/project/packages/web/node_modules/dep/package.json", r#"{"version": "0.1.0"}"#);
+        let config = load_config(&fs).unwrap().unwrap();
+        assert_eq!(config.current_version.as_deref(), Some("1.2.3"));
+        let paths: Vec<&Path> = config.version_fields.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(paths, vec![Path::new("/project/package.json"), Path::new("/project/packages/web/package.json")]);
+    }
+
+    #[test]
+    fn test_nearest_config() {
+        let fs = MemoryFs::new("/repo/packages/web")
+            .with_file("/repo/bver.toml", "current-version = \"2.0.0\"\n")
+            .with_file("/repo/packages/web/package.json", r#"{"version": "1.2.3", "bver": {}}"#)
+            .with_file("/repo/packages/web/src/pyproject.toml", "[tool.bver]\ncurrent-version = \"0.1.0\"\n");
+        let config = load_config(&fs).unwrap().unwrap();
+        assert_eq!(config.source.unwrap().path(), Path::new("/repo/packages/web/package.json"));
+        let config = load_config_from(&fs, true).unwrap().unwrap();
+        assert_eq!(config.current_version.as_deref(), Some("2.0.0"));
+
+        // A manifest without a bver section only counts when there's no config at all
+        let fs = MemoryFs::new("/repo/packages/web")
+            .with_file("/repo/pyproject.toml", "[tool.bver]\ncurrent-version = \"2.0.0\"\n")
+            .with_file("/repo/packages/web/package.json", r#"{"version": "1.2.3"}"#);
+        let config = load_config(&fs).unwrap().unwrap();
+        assert_eq!(config.current_version.as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn test_config_errors() {
+        // A broken bver.toml doesn't fall through to the pyproject.toml next to it
+        let fs = MemoryFs::new("/project")
+            .with_file("/project/bver.toml", "current-version = \"1.2.3\"\n[git\n")
+            .with_file("/project/pyproject.toml", "[tool.bver]\ncurrent-version = \"1.2.3\"\n");
+        let e = load_config(&fs).unwrap_err();
+        assert_eq!((e.path.as_path(), e.line), (Path::new("/project/bver.toml"), Some(2)));
+
+        let fs = MemoryFs::new("/project").with_file(
+            "/project/pyproject.toml",
+            "[project]\nname = \"x\"\n\n[tool.bver]\ncurrent-version = \"1.2.3\"\n\n[tool.bver.git]\nactions = [\"bogus\"]\n",
+        );
+        let e = load_config(&fs).unwrap_err();
+        assert_eq!(e.line, Some(8));
+        assert!(e.to_string().starts_with("/project/pyproject.toml:8: git.actions: unknown variant `bogus`"), "{e}");
+
+        let fs = MemoryFs::new("/project").with_file("/project/package.json", "{\n  \"version\": \"1.2.3\",\n  \"bver\": {\"context-lines\": \"x\"}\n}\n");
+        let e = load_config(&fs).unwrap_err();
+        assert_eq!(e.line, Some(3));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let lookup = |name: &str| (name == "REMOTE").then(|| "upstream".to_string());
+        assert_eq!(interpolate("git push ${REMOTE} v1", &lookup), "git push upstream v1");
+        assert_eq!(interpolate("${UNSET_VAR} and $REMOTE", &lookup), "${UNSET_VAR} and $REMOTE");
+
+        let value: toml::Value = toml::from_str("a = [\"${PATH}\"]\nb = 1").unwrap();
+        let value = toml_with_env(value);
+        assert_eq!(value["a"][0].as_str(), std::env::var("PATH").ok().as_deref());
+    }
+
+    #[test]
+    fn test_npm_lock_fields() {
+        let lock = "{\n  \"name\": \"app\",\n  \"version\": \"1.2.3\",\n  \"lockfileVersion\": 3,\n  \"packages\": {\n    \"\": {\n      \"name\": \"app\",\n      \"version\": \"1.2.3\"\n    },\n    \"node_modules/dep\": {\n      \"version\": \"1.2.3\"\n    },\n    \"packages/web\": {\n      \"version\": \"1.2.3\"\n    }\n  }\n}\n";
+        let fs = MemoryFs::new("/project")
+            .with_file(
+                "/project/package.json",
+                r#"{"version": "1.2.3", "workspaces": ["packages/*"], "bver": {"sync-workspaces": true, "sync-lockfiles": true}}"#,
+            )
+            .with_file("/project/packages/web/package.json", r#"{"version": "1.2.3"}"#)
+            .with_file("/project/package-lock.json", lock);
+        let config = load_config(&fs).unwrap().unwrap();
+
+        let mut edits: Vec<(usize, String)> = config
+            .version_fields
+            .iter()
+            .filter(|field| field.path == Path::new("/project/package-lock.json"))
+            .flat_map(|field| crate::manifest::propose_field_update(lock, field, "1.2.3", "1.2.4").unwrap())
+            .collect();
+        edits.sort();
+        let lines: Vec<usize> = edits.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(lines, vec![2, 7, 13]);
+    }
+
+    #[test]
+    fn test_cargo_lock_field() {
+        let lock = "version = 4\n\n[[package]]\nname = \"app\"\nversion = \"1.2.3\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"app\"\nversion = \"1.2.3\"\ndependencies = [\n \"serde\",\n]\n";
+        let fs = MemoryFs::new("/project/app")
+            .with_file(
+                "/project/app/Cargo.toml",
+                "[package]\nname = \"app\"\nversion = \"1.2.3\"\n\n[package.metadata.bver]\nsync-lockfiles = true\n",
+            )
+            .with_file("/project/Cargo.lock", lock);
+        let config = load_config(&fs).unwrap().unwrap();
+        let field = &config.version_fields[1];
+        assert_eq!(field.path, Path::new("/project/Cargo.lock"));
+
+        let edits = crate::manifest::propose_field_update(lock, field, "1.2.3", "1.3.0").unwrap();
+        assert_eq!(edits, vec![(9, "version = \"1.3.0\"".to_string())]);
+    }
 }
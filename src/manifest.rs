@@ -0,0 +1,340 @@
+use std::ops::Range;
+
+use toml_edit::{DocumentMut, Item};
+
+use crate::schema::VersionField;
+
+/// Compute the line edits that set a manifest's version field from `old_version` to `new_version`.
+/// The manifest is edited structurally, so only the exact key is touched; the result is
+/// returned as `(line index, new line)` pairs so it can be reviewed like any other change.
+/// Returns no edits when the field is missing or doesn't hold `old_version`.
+pub fn propose_field_update(
+    content: &str,
+    field: &VersionField,
+    old_version: &str,
+    new_version: &str,
+) -> Result<Vec<(usize, String)>, String> {
+    let is_json = field.path.extension().is_some_and(|ext| ext == "json");
+    let updated = if is_json {
+        set_json_version(content, &field.key, old_version, new_version)
+    } else {
+        set_toml_version(content, &field.key, old_version, new_version)
+    }
+    .map_err(|e| format!("{}: {e}", field.path.display()))?;
+
+    match updated {
+        Some(updated) => changed_lines(content, &updated)
+            .ok_or_else(|| format!("Unexpected layout change while updating {}", field.path.display())),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The version string a manifest field currently holds, if the field exists
+pub fn read_field_version(content: &str, field: &VersionField) -> Result<Option<String>, String> {
+    let is_json = field.path.extension().is_some_and(|ext| ext == "json");
+    if is_json {
+        let span = find_json_string(content, &field.key).map_err(|e| format!("{}: {e}", field.path.display()))?;
+        return Ok(span.map(|span| content[span].to_string()));
+    }
+
+    let doc: DocumentMut = content
+        .parse()
+        .map_err(|e| format!("{}: Failed to parse TOML: {e}", field.path.display()))?;
+    let mut item: &Item = doc.as_item();
+    for part in &field.key {
+        let child = match part.parse::<usize>() {
+            Ok(idx) if item.is_array_of_tables() || item.is_array() => item.get(idx),
+            _ => item.get(part.as_str()),
+        };
+        let Some(child) = child else {
+            return Ok(None);
+        };
+        item = child;
+    }
+    Ok(item.as_str().map(String::from))
+}
+
+/// Set the string at `key` in a TOML document, preserving comments, ordering, and whitespace
+fn set_toml_version(
+    content: &str,
+    key: &[String],
+    old_version: &str,
+    new_version: &str,
+) -> Result<Option<String>, String> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .map_err(|e| format!("Failed to parse TOML: {e}"))?;
+
+    let mut item: &mut Item = doc.as_item_mut();
+    for part in key {
+        // Numeric parts index into arrays, e.g. `package.3.version` in Cargo.lock
+        let child = match part.parse::<usize>() {
+            Ok(idx) if item.is_array_of_tables() || item.is_array() => item.get_mut(idx),
+            _ => item.get_mut(part.as_str()),
+        };
+        item = match child {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+    }
+
+    let Some(value) = item.as_value_mut() else {
+        return Ok(None);
+    };
+    if value.as_str() != Some(old_version) {
+        return Ok(None);
+    }
+
+    let decor = value.decor().clone();
+    *value = new_version.into();
+    *value.decor_mut() = decor;
+
+    Ok(Some(doc.to_string()))
+}
+
+/// Set the string at `key` in a JSON document by splicing the value in place,
+/// so indentation, key order, and everything else stays byte-for-byte identical
+fn set_json_version(
+    content: &str,
+    key: &[String],
+    old_version: &str,
+    new_version: &str,
+) -> Result<Option<String>, String> {
+    let Some(span) = find_json_string(content, key)? else {
+        return Ok(None);
+    };
+    if content[span.clone()] != *old_version {
+        return Ok(None);
+    }
+
+    let mut updated = content.to_string();
+    updated.replace_range(span, new_version);
+    Ok(Some(updated))
+}
+
+/// Locate the raw contents (between the quotes) of the string at `key` in a JSON document
+pub fn find_json_string(content: &str, key: &[String]) -> Result<Option<Range<usize>>, String> {
+    let mut scanner = JsonScanner {
+        bytes: content.as_bytes(),
+        pos: 0,
+    };
+    scanner.value(Some(key))
+}
+
+/// A minimal JSON reader that tracks byte offsets instead of building values
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    fn error(&self, msg: &str) -> String {
+        format!("Invalid JSON at byte {}: {msg}", self.pos)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected '{}'", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Scan a value. While `path` is `Some`, look for the string it points to and return its span.
+    fn value(&mut self, path: Option<&[String]>) -> Result<Option<Range<usize>>, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(path),
+            Some(b'[') => {
+                self.array()?;
+                Ok(None)
+            }
+            Some(b'"') => {
+                let span = self.string()?;
+                Ok(path.filter(|p| p.is_empty()).map(|_| span))
+            }
+            Some(_) => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                Ok(None)
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self, path: Option<&[String]>) -> Result<Option<Range<usize>>, String> {
+        self.expect(b'{')?;
+        let mut found = None;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(None);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            let key = &self.bytes[key];
+            self.expect(b':')?;
+
+            let child = path.and_then(|p| match p.split_first() {
+                Some((first, rest)) if found.is_none() && first.as_bytes() == key => Some(rest),
+                _ => None,
+            });
+            if let Some(span) = self.value(child)? {
+                found = Some(span);
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(found);
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), String> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.value(None)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Scan a string literal, returning the span of its raw contents
+    fn string(&mut self) -> Result<Range<usize>, String> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(start..self.pos - 1);
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+}
+
+/// Pair up lines of two renderings that only differ within lines.
+/// Returns `None` if the line count changed.
+fn changed_lines(old: &str, new: &str) -> Option<Vec<(usize, String)>> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    if old_lines.len() != new_lines.len() {
+        return None;
+    }
+
+    Some(
+        old_lines
+            .iter()
+            .zip(&new_lines)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(i, (_, new))| (i, new.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kind::VersionKind;
+
+    fn field(key: &[&str]) -> VersionField {
+        VersionField::new("pyproject.toml", key, VersionKind::Python)
+    }
+
+    #[test]
+    fn test_pyproject_version_update() {
+        let content = "[project]\nname = \"x\"\nversion = \"1.2.3\"  # keep me\n\n[tool.x]\nversion = \"1.2.3\"\n";
+        let edits = propose_field_update(content, &field(&["project", "version"]), "1.2.3", "1.2.4").unwrap();
+        assert_eq!(edits, vec![(2, "version = \"1.2.4\"  # keep me".to_string())]);
+    }
+
+    #[test]
+    fn test_read_field_version() {
+        let content = "[project]\nname = \"x\"\nversion = \"1.2.3\"\n";
+        assert_eq!(read_field_version(content, &field(&["project", "version"])).unwrap().as_deref(), Some("1.2.3"));
+        assert_eq!(read_field_version(content, &field(&["tool", "version"])).unwrap(), None);
+        let json = VersionField::new("package.json", &["version"], VersionKind::Semver);
+        assert_eq!(read_field_version(r#"{"version": "2.0.0"}"#, &json).unwrap().as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn test_poetry_version_update() {
+        let content = "[tool.poetry]\nname = \"x\"\nversion = \"0.3.0\"\n\n[tool.poetry.dependencies]\npython = \"^3.9\"\n";
+        let edits = propose_field_update(content, &field(&["tool", "poetry", "version"]), "0.3.0", "0.4.0").unwrap();
+        assert_eq!(edits, vec![(2, "version = \"0.4.0\"".to_string())]);
+    }
+
+    #[test]
+    fn test_cargo_version_ignores_dependency_pins() {
+        let content = "[package]\nname = \"x\"\nversion = \"1.2.3\"\n\n[dependencies]\nfoo = { version = \"1.2.3\" }\nbar = \"1.2.3\"\n";
+        let field = VersionField::new("Cargo.toml", &["package", "version"], VersionKind::Semver);
+        let edits = propose_field_update(content, &field, "1.2.3", "1.3.0").unwrap();
+        assert_eq!(edits, vec![(2, "version = \"1.3.0\"".to_string())]);
+    }
+
+    #[test]
+    fn test_package_json_top_level_version_only() {
+        let content = "{\n  \"name\": \"x\",\n  \"dependencies\": { \"version\": \"1.2.3\" },\n  \"version\":   \"1.2.3\"\n}\n";
+        let field = VersionField::new("package.json", &["version"], VersionKind::Semver);
+        let edits = propose_field_update(content, &field, "1.2.3", "1.2.4").unwrap();
+        assert_eq!(edits, vec![(3, "  \"version\":   \"1.2.4\"".to_string())]);
+    }
+
+    #[test]
+    fn test_find_json_string_nested() {
+        let content = r#"{"a": [1, {"b": "x"}], "packages": {"": {"version": "0.1.0"}}}"#;
+        let key = vec!["packages".to_string(), String::new(), "version".to_string()];
+        let span = find_json_string(content, &key).unwrap().unwrap();
+        assert_eq!(&content[span], "0.1.0");
+        assert!(find_json_string("{\"a\": ", &key).is_err());
+    }
+
+    #[test]
+    fn test_missing_or_mismatched_field() {
+        let content = "[project]\nversion = \"1.2.3\"\n";
+        assert!(propose_field_update(content, &field(&["tool", "poetry", "version"]), "1.2.3", "1.2.4")
+            .unwrap()
+            .is_empty());
+        assert!(propose_field_update(content, &field(&["project", "version"]), "9.9.9", "1.2.4")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Used when `$PAGER` isn't set: quit if the output fits after all, keep colors, don't clear the screen
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Print `output`, through `$PAGER` when `enabled`, stdout is a terminal and the output is longer
+/// than the screen. Falls back to printing when the pager can't be started.
+pub fn page(output: &str, enabled: bool) {
+    if enabled && io::stdout().is_terminal() && !fits_screen(output) && run_pager(output).is_ok() {
+        return;
+    }
+    print!("{output}");
+}
+
+fn fits_screen(output: &str) -> bool {
+    let Ok((_, rows)) = crossterm::terminal::size() else {
+        return true;
+    };
+    output.lines().count() < rows as usize
+}
+
+/// The pager program and its arguments; an empty `$PAGER` or `cat` means no paging
+fn pager_command(pager: Option<&str>) -> Option<Vec<String>> {
+    let parts: Vec<String> = pager.unwrap_or(DEFAULT_PAGER).split_whitespace().map(String::from).collect();
+    (!parts.is_empty() && parts[0] != "cat").then_some(parts)
+}
+
+fn run_pager(output: &str) -> io::Result<()> {
+    let pager = std::env::var("PAGER").ok();
+    let parts = pager_command(pager.as_deref()).ok_or_else(|| io::Error::other("paging disabled"))?;
+    let mut child = Command::new(&parts[0]).args(&parts[1..]).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None), Some(vec!["less".to_string(), "-FRX".to_string()]));
+        assert_eq!(pager_command(Some("most -s")), Some(vec!["most".to_string(), "-s".to_string()]));
+        assert_eq!(pager_command(Some("")), None);
+        assert_eq!(pager_command(Some("cat")), None);
+    }
+}
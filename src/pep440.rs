@@ -0,0 +1,253 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// The version grammar from PEP 440, including every spelling that normalizes to a valid version
+/// https://peps.python.org/pep-0440/#appendix-b-parsing-version-strings-with-regular-expressions
+static VERSION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)^\s*v?
+        (?:(?P<epoch>[0-9]+)!)?
+        (?P<release>[0-9]+(?:\.[0-9]+)*)
+        (?:[-_.]?(?P<pre_l>alpha|beta|preview|pre|rc|a|b|c)[-_.]?(?P<pre_n>[0-9]+)?)?
+        (?:-(?P<post_n1>[0-9]+)|[-_.]?(?P<post_l>post|rev|r)[-_.]?(?P<post_n2>[0-9]+)?)?
+        (?:[-_.]?(?P<dev_l>dev)[-_.]?(?P<dev_n>[0-9]+)?)?
+        (?:\+(?P<local>[a-z0-9]+(?:[-_.][a-z0-9]+)*))?
+        \s*$",
+    )
+    .unwrap()
+});
+
+/// A pre-release phase, in precedence order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreRelease {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl fmt::Display for PreRelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PreRelease::Alpha => "a",
+            PreRelease::Beta => "b",
+            PreRelease::Rc => "rc",
+        })
+    }
+}
+
+/// A parsed PEP 440 version. `Display` renders the canonical (normalized) form;
+/// equality and ordering follow PEP 440, so `1.0 == 1.0.0`.
+#[derive(Debug, Clone)]
+pub struct Pep440Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    pub pre: Option<(PreRelease, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Vec<String>,
+}
+
+impl FromStr for Pep440Version {
+    type Err = String;
+
+    fn from_str(version: &str) -> Result<Self, String> {
+        if version.trim().is_empty() {
+            return Err("Version string cannot be empty".to_string());
+        }
+        let caps = VERSION_PATTERN
+            .captures(version)
+            .ok_or_else(|| format!("Invalid PEP 440 version: {version}"))?;
+
+        let number = |name: &str| -> Result<Option<u64>, String> {
+            caps.name(name)
+                .map(|m| m.as_str().parse().map_err(|_| format!("Number too large in version: {version}")))
+                .transpose()
+        };
+
+        let release = caps["release"]
+            .split('.')
+            .map(|part| part.parse().map_err(|_| format!("Number too large in version: {version}")))
+            .collect::<Result<Vec<u64>, String>>()?;
+
+        let pre = match caps.name("pre_l").map(|m| m.as_str().to_lowercase()) {
+            Some(label) => {
+                let phase = match label.as_str() {
+                    "a" | "alpha" => PreRelease::Alpha,
+                    "b" | "beta" => PreRelease::Beta,
+                    _ => PreRelease::Rc,
+                };
+                Some((phase, number("pre_n")?.unwrap_or(0)))
+            }
+            None => None,
+        };
+
+        let post = match number("post_n1")? {
+            Some(n) => Some(n),
+            None if caps.name("post_l").is_some() => Some(number("post_n2")?.unwrap_or(0)),
+            None => None,
+        };
+
+        let dev = match caps.name("dev_l") {
+            Some(_) => Some(number("dev_n")?.unwrap_or(0)),
+            None => None,
+        };
+
+        let local = caps
+            .name("local")
+            .map(|m| m.as_str().to_lowercase().split(['-', '_', '.']).map(String::from).collect())
+            .unwrap_or_default();
+
+        Ok(Pep440Version {
+            epoch: number("epoch")?.unwrap_or(0),
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+}
+
+impl fmt::Display for Pep440Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+        f.write_str(&release.join("."))?;
+        if let Some((phase, n)) = self.pre {
+            write!(f, "{phase}{n}")?;
+        }
+        if let Some(n) = self.post {
+            write!(f, ".post{n}")?;
+        }
+        if let Some(n) = self.dev {
+            write!(f, ".dev{n}")?;
+        }
+        if !self.local.is_empty() {
+            write!(f, "+{}", self.local.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl Pep440Version {
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// The release with trailing zeros dropped, which is how releases compare (`1.0 == 1.0.0`)
+    fn trimmed_release(&self) -> &[u64] {
+        let len = self.release.iter().rposition(|&n| n != 0).map_or(0, |i| i + 1);
+        &self.release[..len]
+    }
+
+    /// The sort key from the `packaging` reference implementation
+    fn key(&self) -> SortKey<'_> {
+        let pre = match (self.pre, self.post, self.dev) {
+            // A dev release of a final release sorts before its prereleases
+            (None, None, Some(_)) => Bound::Min,
+            (None, _, _) => Bound::Max,
+            (Some(pre), _, _) => Bound::Value(pre),
+        };
+        let post = self.post.map_or(Bound::Min, Bound::Value);
+        let dev = self.dev.map_or(Bound::Max, Bound::Value);
+        let local = if self.local.is_empty() {
+            Bound::Min
+        } else {
+            Bound::Value(self.local.iter().map(|s| LocalSegment::new(s)).collect())
+        };
+        (self.epoch, self.trimmed_release(), pre, post, dev, local)
+    }
+}
+
+type SortKey<'a> = (u64, &'a [u64], Bound<(PreRelease, u64)>, Bound<u64>, Bound<u64>, Bound<Vec<LocalSegment>>);
+
+/// A value that may be replaced by negative or positive infinity for ordering
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Bound<T> {
+    Min,
+    Value(T),
+    Max,
+}
+
+/// A local version segment: numeric segments sort after (and numerically among) alphanumeric ones
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LocalSegment {
+    Text(String),
+    Number(u64),
+}
+
+impl LocalSegment {
+    fn new(segment: &str) -> Self {
+        segment
+            .parse()
+            .map_or_else(|_| LocalSegment::Text(segment.to_string()), LocalSegment::Number)
+    }
+}
+
+impl PartialEq for Pep440Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Pep440Version {}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Render a version in canonical PEP 440 form (e.g., `1.0.0-alpha.1` -> `1.0.0a1`)
+pub fn normalize(version: &str) -> Result<String, String> {
+    version.parse::<Pep440Version>().map(|parsed| parsed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_forms() {
+        let normalize = |version| normalize(version).unwrap();
+        assert_eq!(normalize("1.0.0"), "1.0.0");
+        assert_eq!(normalize("1.0.0-alpha.1"), "1.0.0a1");
+        assert_eq!(normalize("1.0.0.RC1"), "1.0.0rc1");
+        assert_eq!(normalize("1.0c2"), "1.0rc2");
+        assert_eq!(normalize("1.0-1"), "1.0.post1");
+        assert_eq!(normalize("v1.0.post"), "1.0.post0");
+        assert_eq!(normalize("1.0-dev"), "1.0.dev0");
+        assert_eq!(normalize("1!01.02a01.post02.dev03+Ubuntu-1"), "1!1.2a1.post2.dev3+ubuntu.1");
+    }
+
+    #[test]
+    fn test_ordering() {
+        let ordered = [
+            "1.0.dev0", "1.0a1.dev1", "1.0a1", "1.0a1.post1", "1.0b1", "1.0rc1", "1.0", "1.0+local",
+            "1.0.post1.dev1", "1.0.post1", "1.1", "1!0.1",
+        ];
+        let versions: Vec<Pep440Version> = ordered.iter().map(|v| v.parse().unwrap()).collect();
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!("1.0".parse::<Pep440Version>(), "1.0.0".parse());
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        for version in ["", "a.b.c", "1.0+", "1.0.", ".1.0", "1..0", "1.0abc", "1.0-beta.x"] {
+            assert!(version.parse::<Pep440Version>().is_err(), "{version} should be invalid");
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use regex::Regex;
+
+/// A version comparison operator followed by a version, as found in PEP 508 / npm / Cargo requirements
+const CLAUSE: &str = r"(?:===|==|!=|~=|>=|<=|>|<|\^|~)\s*v?[0-9][\w.*+!-]*";
+
+/// Rewrite every requirement on `package` in `lines` to `range`,
+/// e.g. `mypkg>=1.2,<1.3` -> `mypkg>=1.3,<1.4`. Extras (`mypkg[cli]`) are kept.
+pub fn replace_requirements(lines: &[String], package: &str, range: &str) -> Vec<(usize, String)> {
+    let pattern = format!(
+        r"(?i)(?:^|[^\w.-])(?:{name})(?:\[[^\]]*\])?\s*(?P<spec>{CLAUSE}(?:\s*,\s*{CLAUSE})*)",
+        name = package_pattern(package)
+    );
+    let re = Regex::new(&pattern).expect("requirement pattern is valid");
+
+    let mut edits = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let mut new_line = String::with_capacity(line.len());
+        let mut last = 0;
+        for caps in re.captures_iter(line) {
+            let spec = caps.name("spec").unwrap();
+            new_line.push_str(&line[last..spec.start()]);
+            new_line.push_str(range);
+            last = spec.end();
+        }
+        new_line.push_str(&line[last..]);
+        if new_line != *line {
+            edits.push((idx, new_line));
+        }
+    }
+    edits
+}
+
+/// Match a package name the way installers compare them: `-`, `_` and `.` are interchangeable
+fn package_pattern(package: &str) -> String {
+    package
+        .split(['-', '_', '.'])
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("[-_.]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_replace_requirements() {
+        let content = "my-pkg>=1.2,<1.3\nother-my-pkg>=1.2\npip install \"my_pkg[cli] >= 1.2, < 1.3\"\nmy-pkg is great\n";
+        let edits = replace_requirements(&lines(content), "my-pkg", ">=1.3,<1.4");
+        assert_eq!(
+            edits,
+            vec![
+                (0, "my-pkg>=1.3,<1.4".to_string()),
+                (2, "pip install \"my_pkg[cli] >=1.3,<1.4\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchanged_requirement() {
+        let edits = replace_requirements(&lines("mypkg==1.3.0\n"), "mypkg", "==1.3.0");
+        assert!(edits.is_empty());
+    }
+}
@@ -0,0 +1,1047 @@
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::replace_bounded;
+use crate::kind::VersionKind;
+use crate::manifest::find_json_string;
+use crate::schema::FileConfig;
+use crate::template::render;
+use crate::vfs::Vfs;
+
+/// Built-in rules for well-known version locations.
+/// A preset only replaces the version where its anchors match, so unrelated
+/// occurrences of the same version string in the file are left alone.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// `setup(version="X.Y.Z")` in setup.py
+    SetupPy,
+    /// `version = X.Y.Z` in the `[metadata]` section of setup.cfg
+    SetupCfg,
+    /// `__version__ = "X.Y.Z"` in a Python package's `__init__.py` or `_version.py`
+    DunderVersion,
+    /// `ARG VERSION=X.Y.Z` and `LABEL org.opencontainers.image.version=X.Y.Z` in a Dockerfile
+    Dockerfile,
+    /// `image: name:X.Y.Z` in docker-compose / Kubernetes manifests and kustomize `newTag: X.Y.Z`
+    ContainerImage,
+    /// Both `version` and `appVersion` in a Helm Chart.yaml
+    HelmChart,
+    /// The chart `version` in a Helm Chart.yaml (always strict semver)
+    HelmChartVersion,
+    /// The `appVersion` in a Helm Chart.yaml
+    HelmAppVersion,
+    /// `version=X.Y.Z` in gradle.properties
+    GradleProperties,
+    /// `version 'X.Y.Z'` / `version = "X.Y.Z"` in build.gradle(.kts)
+    Gradle,
+    /// The project's own `<version>` in a Maven pom.xml (and `<parent><version>` with `include-parent`)
+    Maven,
+    /// Both the package version and the assembly versions of a .NET project
+    Dotnet,
+    /// `<Version>` in a .csproj
+    DotnetVersion,
+    /// `<AssemblyVersion>`/`<FileVersion>` in a .csproj and `[assembly: AssemblyVersion("...")]`
+    /// attributes (always 4-component `X.Y.Z.0`)
+    DotnetAssemblyVersion,
+    /// `spec.version = "X.Y.Z"` in a .gemspec
+    Gemspec,
+    /// `VERSION = "X.Y.Z"` in a Ruby gem's version.rb
+    RubyVersion,
+    /// `#define FOO_VERSION_MAJOR 1` / `_MINOR` / `_PATCH` and `#define FOO_VERSION "1.2.3"` in a C/C++ header
+    CHeader,
+    /// `version "X.Y.Z"` and the version in the `url` of a Homebrew formula (not its resources)
+    Homebrew,
+    /// `version = "X.Y.Z";` in a Nix flake or derivation
+    Nix,
+    /// The top-level `version: X.Y.Z+N` in a Dart/Flutter pubspec.yaml, keeping (or incrementing) the build number
+    Pubspec,
+    /// `version: "X.Y.Z"` in the `project` function of an Elixir mix.exs, and `@version "X.Y.Z"` attributes
+    Mix,
+    /// The top-level `version` (JSON pointer `/version`) in a PHP composer.json
+    Composer,
+    /// `Version: X.Y.Z` in an R package's DESCRIPTION file
+    RDescription,
+    /// `.version = "X.Y.Z"` in a Zig build.zig.zon
+    ZigZon,
+    /// The top-level `version:` field of a Haskell .cabal file
+    Cabal,
+    /// The top-level `version:` in an hpack package.yaml
+    Hpack,
+    /// `project(Foo VERSION X.Y.Z)` and `set(FOO_VERSION ...)` / `set(FOO_VERSION_MAJOR ...)` in CMakeLists.txt
+    Cmake,
+    /// `version: 'X.Y.Z'` in the `project()` call of a meson.build
+    Meson,
+    /// `version = "X.Y.Z"` in the `module()` call of a MODULE.bazel
+    BazelModule,
+    /// The top-level `version` (or `version-semver` / `version-string`) in a vcpkg.json
+    Vcpkg,
+    /// The recipe's `version = "X.Y.Z"` in a conanfile.py, or a top-level `version:` in conandata.yml
+    Conan,
+}
+
+impl Preset {
+    /// The file (or glob) this preset targets when the entry has no `src`
+    pub fn default_src(self, fs: &dyn Vfs, project_root: &Path) -> Option<String> {
+        match self {
+            Preset::SetupPy => Some("setup.py".to_string()),
+            Preset::SetupCfg => Some("setup.cfg".to_string()),
+            Preset::DunderVersion => find_dunder_version_file(fs, project_root),
+            Preset::Dockerfile => Some("Dockerfile".to_string()),
+            Preset::ContainerImage => Some("{docker-compose,compose}.{yml,yaml}".to_string()),
+            Preset::HelmChart | Preset::HelmChartVersion | Preset::HelmAppVersion => {
+                Some("Chart.yaml".to_string())
+            }
+            Preset::GradleProperties => Some("gradle.properties".to_string()),
+            Preset::Gradle => Some("build.gradle{,.kts}".to_string()),
+            Preset::Maven => Some("pom.xml".to_string()),
+            Preset::Dotnet | Preset::DotnetVersion | Preset::DotnetAssemblyVersion => {
+                Some("*.csproj".to_string())
+            }
+            Preset::Gemspec => Some("*.gemspec".to_string()),
+            Preset::RubyVersion => Some("lib/**/version.rb".to_string()),
+            Preset::CHeader => None,
+            Preset::Homebrew => Some("Formula/*.rb".to_string()),
+            Preset::Nix => Some("flake.nix".to_string()),
+            Preset::Pubspec => Some("pubspec.yaml".to_string()),
+            Preset::Mix => Some("mix.exs".to_string()),
+            Preset::Composer => Some("composer.json".to_string()),
+            Preset::RDescription => Some("DESCRIPTION".to_string()),
+            Preset::ZigZon => Some("build.zig.zon".to_string()),
+            Preset::Cabal => Some("*.cabal".to_string()),
+            Preset::Hpack => Some("package.yaml".to_string()),
+            Preset::Cmake => Some("CMakeLists.txt".to_string()),
+            Preset::Meson => Some("meson.build".to_string()),
+            Preset::BazelModule => Some("MODULE.bazel".to_string()),
+            Preset::Vcpkg => Some("vcpkg.json".to_string()),
+            Preset::Conan => Some("conanfile.py".to_string()),
+        }
+    }
+
+    /// Split a preset covering several fields into one preset per field,
+    /// so each field can use its own version kind
+    pub fn expand(self) -> Vec<Preset> {
+        match self {
+            Preset::HelmChart => vec![Preset::HelmChartVersion, Preset::HelmAppVersion],
+            Preset::Dotnet => vec![Preset::DotnetVersion, Preset::DotnetAssemblyVersion],
+            preset => vec![preset],
+        }
+    }
+
+    /// A kind mandated by the target format, which the entry's `kind` cannot override
+    pub fn required_kind(self) -> Option<VersionKind> {
+        match self {
+            Preset::HelmChartVersion => Some(VersionKind::Semver),
+            Preset::DotnetAssemblyVersion => Some(VersionKind::Dotnet),
+            _ => None,
+        }
+    }
+
+    /// The version kind used when the entry doesn't set one
+    pub fn default_kind(self) -> Option<VersionKind> {
+        match self {
+            Preset::SetupPy | Preset::SetupCfg | Preset::DunderVersion => Some(VersionKind::Python),
+            Preset::Dockerfile
+            | Preset::ContainerImage
+            | Preset::HelmAppVersion
+            | Preset::GradleProperties
+            | Preset::Gradle
+            | Preset::Maven
+            | Preset::Dotnet
+            | Preset::DotnetVersion
+            | Preset::CHeader
+            | Preset::Cmake
+            | Preset::Meson
+            | Preset::BazelModule
+            | Preset::Vcpkg
+            | Preset::Conan
+            | Preset::Homebrew
+            | Preset::Nix => None,
+            Preset::DotnetAssemblyVersion => Some(VersionKind::Dotnet),
+            Preset::HelmChart | Preset::HelmChartVersion | Preset::Pubspec | Preset::Mix | Preset::Composer | Preset::ZigZon => {
+                Some(VersionKind::Semver)
+            }
+            Preset::Gemspec | Preset::RubyVersion => Some(VersionKind::Rubygems),
+            Preset::RDescription => Some(VersionKind::R),
+            Preset::Cabal | Preset::Hpack => Some(VersionKind::Pvp),
+        }
+    }
+
+    /// Compute `(line index, new line)` edits that replace `old_version` with `new_version`
+    /// only where this preset's anchored patterns match
+    pub fn propose(
+        self,
+        entry: &FileConfig,
+        lines: &[String],
+        old_version: &str,
+        new_version: &str,
+    ) -> Vec<(usize, String)> {
+        match self {
+            Preset::SetupPy => {
+                // Only look inside the setup(...) call
+                let patterns = [r#"\bversion\s*=\s*["'](?P<version>[^"']+)["']"#];
+                replace_in_range(lines, call_range(lines, "setup"), &patterns, old_version, new_version)
+            }
+            Preset::SetupCfg => {
+                let range = ini_section(lines, "metadata");
+                let patterns = [r"^\s*version\s*[=:]\s*(?P<version>\S+)\s*$"];
+                replace_in_range(lines, range, &patterns, old_version, new_version)
+            }
+            Preset::DunderVersion => {
+                let patterns = [r#"^\s*__version__\s*(?::\s*str\s*)?=\s*["'](?P<version>[^"']+)["']"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Dockerfile => {
+                // Base image tags (`FROM python:3.12`) and other ARGs never match these anchors
+                let patterns = [
+                    r#"^\s*(?i:ARG)\s+VERSION=["']?(?P<version>[^"'\s]+)"#,
+                    r#"\borg\.opencontainers\.image\.version=["']?(?P<version>[^"'\s]+)"#,
+                ];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::ContainerImage => {
+                replace_image_tags(lines, entry.image.as_deref(), old_version, new_version)
+            }
+            Preset::HelmChart | Preset::Dotnet => self
+                .expand()
+                .into_iter()
+                .flat_map(|part| part.propose(entry, lines, old_version, new_version))
+                .collect(),
+            Preset::HelmChartVersion => {
+                // Top-level key only; `dependencies[].version` entries are indented
+                let patterns = [r#"^version:\s*["']?(?P<version>[^\s"'#]+)"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::HelmAppVersion => {
+                let patterns = [r#"^appVersion:\s*["']?(?P<version>[^\s"'#]+)"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::GradleProperties => {
+                let patterns = [r"^\s*version\s*[=:]\s*(?P<version>\S+)\s*$"];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Gradle => {
+                // Top-level project version only; dependency coordinates are `group:name:version` strings
+                let patterns = [r#"^(?:project\.)?version\s*(?:=\s*)?["'](?P<version>[^"']+)["']"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Maven => {
+                let mut paths: Vec<&[&str]> = vec![&["project", "version"]];
+                if entry.include_parent {
+                    paths.push(&["project", "parent", "version"]);
+                }
+                replace_xml_elements(lines, &paths, old_version, new_version)
+            }
+            Preset::DotnetVersion => {
+                let paths: [&[&str]; 1] = [&["Project", "PropertyGroup", "Version"]];
+                replace_xml_elements(lines, &paths, old_version, new_version)
+            }
+            Preset::DotnetAssemblyVersion => {
+                let paths: [&[&str]; 2] = [
+                    &["Project", "PropertyGroup", "AssemblyVersion"],
+                    &["Project", "PropertyGroup", "FileVersion"],
+                ];
+                let mut edits = replace_xml_elements(lines, &paths, old_version, new_version);
+                let patterns = [r#"\[assembly:\s*Assembly(?:File)?Version\(\s*"(?P<version>[^"]+)"\s*\)\]"#];
+                edits.extend(replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version));
+                edits
+            }
+            Preset::Gemspec => {
+                // `add_dependency "x", "~> 1.2"` lines never match the `.version =` anchor
+                let patterns = [r#"^\s*\w+\.version\s*=\s*["'](?P<version>[^"']+)["']"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::RubyVersion => {
+                let patterns = [r#"^\s*VERSION\s*=\s*["'](?P<version>[^"']+)["']"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::CHeader => replace_c_header_macros(lines, old_version, new_version),
+            Preset::Cmake => replace_cmake_version(lines, old_version, new_version),
+            Preset::Vcpkg => ["/version", "/version-semver", "/version-string"]
+                .iter()
+                .flat_map(|pointer| replace_json_pointer(lines, pointer, old_version, new_version))
+                .collect(),
+            Preset::Conan => {
+                // `requires = "dep/X.Y.Z"` references never match these anchors
+                let patterns = [
+                    r#"^\s+version\s*=\s*["'](?P<version>[^"']+)["']"#,
+                    r#"^version:\s*["']?(?P<version>[^\s"'#]+)"#,
+                ];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Meson => {
+                // Only the `project()` call, so `dependency(..., version: '>=X.Y.Z')` is left alone
+                let patterns = [r#"\bversion\s*:\s*'(?P<version>[^']+)'"#];
+                replace_in_range(lines, call_range(lines, "project"), &patterns, old_version, new_version)
+            }
+            Preset::BazelModule => {
+                // Only the `module()` call, so `bazel_dep(..., version = "X.Y.Z")` is left alone
+                let patterns = [r#"\bversion\s*=\s*"(?P<version>[^"]+)""#];
+                replace_in_range(lines, call_range(lines, "module"), &patterns, old_version, new_version)
+            }
+            Preset::Homebrew => replace_homebrew_version(lines, old_version, new_version),
+            Preset::Nix => {
+                let patterns = [r#"^\s*version\s*=\s*"(?P<version>[^"]+)"\s*;"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Pubspec => replace_pubspec_version(lines, entry.increment_build, old_version, new_version),
+            Preset::Mix => {
+                // `version:` only inside `def project`, so requirements in `deps` are never touched
+                let attribute = [r#"^\s*@version\s+"(?P<version>[^"]+)""#];
+                let mut edits = replace_in_range(lines, 0..lines.len(), &attribute, old_version, new_version);
+                let keyword = [r#"\bversion:\s*"(?P<version>[^"]+)""#];
+                edits.extend(replace_in_range(lines, elixir_def(lines, "project"), &keyword, old_version, new_version));
+                edits.sort();
+                edits.dedup_by_key(|(idx, _)| *idx);
+                edits
+            }
+            Preset::Composer => replace_json_pointer(lines, "/version", old_version, new_version),
+            Preset::ZigZon => {
+                // `.minimum_zig_version` and dependency `.url`/`.hash` fields never match the anchor
+                let patterns = [r#"^\s*\.version\s*=\s*"(?P<version>[^"]+)""#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Cabal => {
+                // Field names are case-insensitive; fields inside sections are indented, and
+                // `cabal-version:` doesn't start with `version`
+                let patterns = [r"^(?i:version)\s*:\s*(?P<version>\S+)\s*$"];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::Hpack => {
+                let patterns = [r#"^version:\s*["']?(?P<version>[^\s"'#]+)"#];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+            Preset::RDescription => {
+                // DCF fields start at column 0; continuation lines (e.g. in `Depends:`) are indented
+                let patterns = [r"^Version:\s*(?P<version>\S+)\s*$"];
+                replace_in_range(lines, 0..lines.len(), &patterns, old_version, new_version)
+            }
+        }
+    }
+
+    /// Why a file legitimately has nothing for this preset to update, if it doesn't
+    /// (reported as a warning instead of a "version not found" error)
+    pub fn missing_field_note(self, lines: &[String]) -> Option<&'static str> {
+        match self {
+            Preset::Composer => json_pointer_span(lines, "/version").is_none().then_some(
+                "composer.json has no `version` field; Packagist infers the version from git tags",
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Replace the JSON string at `pointer` (RFC 6901, e.g. `/version`) if it equals `old_version`
+fn replace_json_pointer(lines: &[String], pointer: &str, old_version: &str, new_version: &str) -> Vec<(usize, String)> {
+    let Some((line_idx, span)) = json_pointer_span(lines, pointer) else {
+        return Vec::new();
+    };
+    let line = &lines[line_idx];
+    if line[span.clone()] != *old_version {
+        return Vec::new();
+    }
+    vec![(line_idx, format!("{}{}{}", &line[..span.start], new_version, &line[span.end..]))]
+}
+
+/// The line and in-line byte range of the JSON string at `pointer`
+fn json_pointer_span(lines: &[String], pointer: &str) -> Option<(usize, std::ops::Range<usize>)> {
+    let key: Vec<String> = pointer
+        .split('/')
+        .skip(1)
+        .map(|part| part.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let content = lines.join("\n");
+    let span = find_json_string(&content, &key).ok()??;
+    let line_start = content[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_idx = content[..span.start].matches('\n').count();
+    Some((line_idx, span.start - line_start..span.end - line_start))
+}
+
+/// Replace the formula's own `version "X.Y.Z"` and the version inside its `url`.
+/// Only top-level (two-space indented) lines are touched, so `resource` blocks keep their versions.
+fn replace_homebrew_version(lines: &[String], old_version: &str, new_version: &str) -> Vec<(usize, String)> {
+    let version_re = Regex::new(r#"^  version\s+"(?P<version>[^"]+)""#).unwrap();
+    let url_re = Regex::new(r#"^  url\s+"(?P<url>[^"]+)""#).unwrap();
+
+    let mut edits = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some(new_line) = replace_captures(line, &version_re, old_version, new_version) {
+            edits.push((idx, new_line));
+        } else if let Some(url) = url_re.captures(line).and_then(|caps| caps.name("url"))
+            && let Some(new_url) = replace_bounded(url.as_str(), old_version, new_version)
+        {
+            edits.push((idx, format!("{}{}{}", &line[..url.start()], new_url, &line[url.end()..])));
+        }
+    }
+    edits
+}
+
+/// Replace image tags equal to `old_version` in YAML manifests, optionally only for images named `image`.
+/// Handles `image: [registry/]name:tag` references and kustomize `images:` entries (`name:` + `newTag:`).
+fn replace_image_tags(
+    lines: &[String],
+    image: Option<&str>,
+    old_version: &str,
+    new_version: &str,
+) -> Vec<(usize, String)> {
+    let image_re = Regex::new(r#"^\s*(?:-\s+)?image:\s*["']?(?P<ref>[^\s"'#]+)"#).unwrap();
+    let name_re = Regex::new(r#"^(?P<indent>\s*)-\s+name:\s*["']?(?P<name>[^\s"'#]+)"#).unwrap();
+    let new_tag_re = Regex::new(r#"^\s*newTag:\s*["']?(?P<version>[^\s"'#]+)"#).unwrap();
+
+    let name_matches = |name: &str| match image {
+        Some(image) => name == image || name.ends_with(&format!("/{image}")),
+        None => true,
+    };
+
+    let mut edits = Vec::new();
+    // The kustomize `images:` item currently being read, if its name passes the filter
+    let mut kustomize_item_matches = false;
+
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some(caps) = image_re.captures(line) {
+            let reference = caps.name("ref").unwrap();
+            // The tag follows the last ':' that isn't part of a registry host:port
+            let Some((name, tag)) = reference.as_str().rsplit_once(':').filter(|(_, tag)| !tag.contains('/'))
+            else {
+                continue;
+            };
+            let name = name.split('@').next().unwrap_or(name);
+            if tag == old_version && name_matches(name) {
+                let tag_start = reference.end() - tag.len();
+                let new_line = format!("{}{}{}", &line[..tag_start], new_version, &line[reference.end()..]);
+                edits.push((idx, new_line));
+            }
+            continue;
+        }
+
+        if let Some(caps) = name_re.captures(line) {
+            kustomize_item_matches = name_matches(&caps["name"]);
+        } else if line.trim_start().starts_with('-') || !line.starts_with([' ', '\t']) {
+            kustomize_item_matches = false;
+        }
+
+        if kustomize_item_matches
+            && let Some(new_line) = replace_captures(line, &new_tag_re, old_version, new_version)
+        {
+            edits.push((idx, new_line));
+        }
+    }
+
+    edits
+}
+
+/// Locate the file defining `__version__` for the package named in pyproject.toml.
+/// Looks in `src/<pkg>/` and `<pkg>/` for `_version.py`, `__version__.py`, and `__init__.py`.
+fn find_dunder_version_file(fs: &dyn Vfs, project_root: &Path) -> Option<String> {
+    let content = fs.read_to_string(&project_root.join("pyproject.toml")).ok()?;
+    let value: toml::Value = toml::from_str(&content).ok()?;
+    let name = value
+        .get("project")
+        .and_then(|p| p.get("name"))
+        .or_else(|| value.get("tool")?.get("poetry")?.get("name"))?
+        .as_str()?;
+    let package = name.to_lowercase().replace(['-', '.'], "_");
+
+    for dir in [format!("src/{package}"), package.clone()] {
+        for file in ["_version.py", "__version__.py", "__init__.py"] {
+            let rel = format!("{dir}/{file}");
+            let defines_version = fs
+                .read_to_string(&project_root.join(&rel))
+                .is_ok_and(|content| content.contains("__version__"));
+            if defines_version {
+                return Some(rel);
+            }
+        }
+    }
+    None
+}
+
+/// Replace the text of XML elements at the given element paths (e.g. `project/version`)
+/// where it equals `old_version`. Elements elsewhere in the tree, such as dependency
+/// `<version>`s, are never touched. Element text must sit on the same line as its tags.
+fn replace_xml_elements(
+    lines: &[String],
+    paths: &[&[&str]],
+    old_version: &str,
+    new_version: &str,
+) -> Vec<(usize, String)> {
+    let mut stack: Vec<String> = Vec::new();
+    let mut in_comment = false;
+    let mut edits = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        // Byte ranges of element text to replace on this line
+        let mut spans: Vec<std::ops::Range<usize>> = Vec::new();
+        // Byte offset just past the opening tag of a target element opened on this line
+        let mut text_start: Option<usize> = None;
+        let mut pos = 0;
+
+        while pos < line.len() {
+            if in_comment {
+                match line[pos..].find("-->") {
+                    Some(end) => {
+                        in_comment = false;
+                        pos += end + 3;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            let Some(lt) = line[pos..].find('<') else {
+                break;
+            };
+            let tag_start = pos + lt;
+            if line[tag_start..].starts_with("<!--") {
+                in_comment = true;
+                pos = tag_start + 4;
+                continue;
+            }
+            let Some(gt) = line[tag_start..].find('>') else {
+                break;
+            };
+            let tag_end = tag_start + gt + 1;
+            let tag = &line[tag_start + 1..tag_end - 1];
+            pos = tag_end;
+
+            if tag.starts_with(['?', '!']) {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                let name = local_name(name.trim());
+                if let Some(start) = text_start.take()
+                    && paths.iter().any(|p| path_matches(&stack, p))
+                    && line[start..tag_start].trim() == old_version
+                {
+                    spans.push(start..tag_start);
+                }
+                if stack.last().is_some_and(|top| top == name) {
+                    stack.pop();
+                }
+                continue;
+            }
+            let self_closing = tag.ends_with('/');
+            let name = local_name(tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or(""));
+            if !self_closing {
+                stack.push(name.to_string());
+                text_start = paths.iter().any(|p| path_matches(&stack, p)).then_some(tag_end);
+            }
+        }
+
+        if !spans.is_empty() {
+            let mut new_line = line.clone();
+            for span in spans.into_iter().rev() {
+                let replaced = line[span.clone()].replacen(old_version, new_version, 1);
+                new_line.replace_range(span, &replaced);
+            }
+            edits.push((idx, new_line));
+        }
+    }
+
+    edits
+}
+
+/// Replace the top-level `version:` of a pubspec.yaml. A `+N` build number that isn't part of
+/// `old_version` is kept. With `increment_build` it's incremented either way (starting at `+1`
+/// if there is none).
+fn replace_pubspec_version(
+    lines: &[String],
+    increment_build: bool,
+    old_version: &str,
+    new_version: &str,
+) -> Vec<(usize, String)> {
+    let re = Regex::new(r#"^version:\s*["']?(?P<version>[^\s"'#+]+)(?:\+(?P<build>[^\s"'#]+))?"#).unwrap();
+    let mut edits = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(caps) = re.captures(line) else {
+            continue;
+        };
+        let version = caps.name("version").unwrap();
+        let build = caps.name("build");
+        let end = build.map_or(version.end(), |b| b.end());
+        let full = &line[version.start()..end];
+
+        if full != old_version && version.as_str() != old_version {
+            continue;
+        }
+        // The build number is incremented whether or not `old_version` includes it
+        let new_full = match build {
+            _ if new_version.contains('+') => new_version.to_string(),
+            Some(build) => match build.as_str().parse::<u64>() {
+                Ok(n) if increment_build => format!("{new_version}+{}", n + 1),
+                _ if full == old_version => new_version.to_string(),
+                _ => format!("{new_version}+{}", build.as_str()),
+            },
+            None if increment_build => format!("{new_version}+1"),
+            None => new_version.to_string(),
+        };
+        edits.push((idx, format!("{}{}{}", &line[..version.start()], new_full, &line[end..])));
+    }
+    edits
+}
+
+/// Rewrite split `*_VERSION_MAJOR/_MINOR/_PATCH` macros from the new version's components,
+/// plus any combined `*_VERSION` / `*_VERSION_STRING` string macro
+fn replace_c_header_macros(lines: &[String], old_version: &str, new_version: &str) -> Vec<(usize, String)> {
+    let component_re =
+        Regex::new(r"^\s*#\s*define\s+\w*VERSION_(?P<part>MAJOR|MINOR|PATCH)\s+(?P<value>\d+)").unwrap();
+    let string_re = Regex::new(r#"^\s*#\s*define\s+\w*VERSION(?:_STRING)?\s+"(?P<version>[^"]+)""#).unwrap();
+
+    let mut edits = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some(caps) = component_re.captures(line) {
+            if let Some(new_line) = replace_component(line, &caps, old_version, new_version) {
+                edits.push((idx, new_line));
+            }
+        } else if let Some(new_line) = replace_captures(line, &string_re, old_version, new_version) {
+            edits.push((idx, new_line));
+        }
+    }
+    edits
+}
+
+/// Rewrite `project(... VERSION X.Y.Z ...)` (which may span several lines), `set(*_VERSION "X.Y.Z")`,
+/// and split `set(*_VERSION_MAJOR/_MINOR/_PATCH N)` variables from the new version's components
+fn replace_cmake_version(lines: &[String], old_version: &str, new_version: &str) -> Vec<(usize, String)> {
+    let project_re = Regex::new(r"^\s*(?i:project)\s*\(").unwrap();
+    let version_arg_re = Regex::new(r#"\bVERSION\s+"?(?P<version>[^\s")]+)"#).unwrap();
+    let component_re =
+        Regex::new(r"^\s*(?i:set)\s*\(\s*\w*VERSION_(?P<part>MAJOR|MINOR|PATCH)\s+(?P<value>\d+)\s*\)").unwrap();
+    let string_re = Regex::new(r#"^\s*(?i:set)\s*\(\s*\w*VERSION(?:_STRING)?\s+"?(?P<version>[^\s")]+)"?\s*\)"#).unwrap();
+
+    let mut edits = Vec::new();
+    let mut in_project = false;
+    for (idx, line) in lines.iter().enumerate() {
+        let code = line.split('#').next().unwrap_or(line);
+        in_project |= project_re.is_match(code);
+        if in_project {
+            if let Some(new_line) = replace_captures(line, &version_arg_re, old_version, new_version) {
+                edits.push((idx, new_line));
+            }
+            in_project = !code.contains(')');
+        } else if let Some(caps) = component_re.captures(line) {
+            if let Some(new_line) = replace_component(line, &caps, old_version, new_version) {
+                edits.push((idx, new_line));
+            }
+        } else if let Some(new_line) = replace_captures(line, &string_re, old_version, new_version) {
+            edits.push((idx, new_line));
+        }
+    }
+    edits
+}
+
+/// Rewrite the `value` capture of a `part` (`MAJOR`/`MINOR`/`PATCH`) match with that
+/// component of the new version, if it holds the current version's component
+fn replace_component(line: &str, caps: &regex::Captures, old_version: &str, new_version: &str) -> Option<String> {
+    let part = caps["part"].to_lowercase();
+    let old_value = render(&format!("{{current-{part}}}"), old_version, new_version);
+    let new_value = render(&format!("{{{part}}}"), old_version, new_version);
+    let value = caps.name("value")?;
+    (value.as_str() == old_value && old_value != new_value)
+        .then(|| format!("{}{}{}", &line[..value.start()], new_value, &line[value.end()..]))
+}
+
+/// Strip an XML namespace prefix (`ns:tag` -> `tag`)
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn path_matches(stack: &[String], path: &[&str]) -> bool {
+    stack.len() == path.len() && stack.iter().zip(path).all(|(a, b)| a == b)
+}
+
+/// Replace the `version` capture group of each pattern wherever it equals `old_version`, within `range`
+fn replace_in_range(
+    lines: &[String],
+    range: std::ops::Range<usize>,
+    patterns: &[&str],
+    old_version: &str,
+    new_version: &str,
+) -> Vec<(usize, String)> {
+    let regexes: Vec<Regex> = patterns
+        .iter()
+        .map(|p| Regex::new(p).expect("invalid preset pattern"))
+        .collect();
+
+    lines[range.clone()]
+        .iter()
+        .zip(range)
+        .filter_map(|(line, idx)| {
+            let mut current: Option<String> = None;
+            for re in &regexes {
+                let text = current.as_deref().unwrap_or(line);
+                if let Some(replaced) = replace_captures(text, re, old_version, new_version) {
+                    current = Some(replaced);
+                }
+            }
+            current.map(|new_line| (idx, new_line))
+        })
+        .collect()
+}
+
+/// Replace every `version` capture of `re` in `line` that equals `old_version`.
+/// Returns `None` if nothing was replaced.
+pub fn replace_captures(line: &str, re: &Regex, old_version: &str, new_version: &str) -> Option<String> {
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+    for caps in re.captures_iter(line) {
+        let Some(version) = caps.name("version") else {
+            continue;
+        };
+        if version.as_str() != old_version {
+            continue;
+        }
+        result.push_str(&line[last..version.start()]);
+        result.push_str(new_version);
+        last = version.end();
+    }
+    if last == 0 {
+        return None;
+    }
+    result.push_str(&line[last..]);
+    Some(result)
+}
+
+/// The line range of the first `name(...)` call (possibly qualified, as in `setuptools.setup(`),
+/// from its opening line to the line that closes it
+fn call_range(lines: &[String], name: &str) -> std::ops::Range<usize> {
+    let re = Regex::new(&format!(r"^\s*(?:[\w.]+\.)?{name}\s*\(")).unwrap();
+    let Some(start) = lines.iter().position(|l| re.is_match(l)) else {
+        return 0..0;
+    };
+    let mut depth = 0;
+    for (idx, line) in lines.iter().enumerate().skip(start) {
+        depth += line.matches('(').count() as isize - line.matches(')').count() as isize;
+        if depth <= 0 {
+            return start..idx + 1;
+        }
+    }
+    start..lines.len()
+}
+
+/// The line range of an INI-style `[name]` section (excluding its header)
+fn ini_section(lines: &[String], name: &str) -> std::ops::Range<usize> {
+    let header = format!("[{name}]");
+    let Some(start) = lines.iter().position(|l| l.trim() == header) else {
+        return 0..0;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    start + 1..end
+}
+
+/// The line range of the body of `def name do ... end` in an Elixir module (excluding the `def` line),
+/// ending at the first `end` indented no deeper than the `def`
+fn elixir_def(lines: &[String], name: &str) -> std::ops::Range<usize> {
+    let re = Regex::new(&format!(r"^(?P<indent>\s*)defp?\s+{name}\b")).unwrap();
+    let Some((start, indent)) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(idx, l)| re.captures(l).map(|caps| (idx, caps["indent"].len())))
+    else {
+        return 0..0;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim() == "end" && l.len() - l.trim_start().len() <= indent)
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    start + 1..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_setup_py() {
+        let content = "REQ = 'foo==1.2.3'\nsetup(\n    name='x',\n    version=\"1.2.3\",\n    install_requires=['bar==1.2.3'],\n)\n";
+        let edits = Preset::SetupPy.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(3, "    version=\"1.2.4\",".to_string())]);
+
+        // Calls nested in setup(...) don't end it, and nothing after it is touched
+        let content = "setuptools.setup(\n    packages=find_packages(),\n    version='1.2.3',\n)\nplugin(version='1.2.3')\n";
+        let edits = Preset::SetupPy.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "    version='1.2.4',".to_string())]);
+    }
+
+    #[test]
+    fn test_dunder_version() {
+        let content = "from ._version import x\n__version__: str = '1.2.3'\nREQUIRES = '1.2.3'\n";
+        let edits = Preset::DunderVersion.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "__version__: str = '1.2.4'".to_string())]);
+    }
+
+    #[test]
+    fn test_dockerfile() {
+        let content = "FROM myapp:1.2.3\nARG VERSION=1.2.3\nARG BASE_VERSION=1.2.3\nLABEL org.opencontainers.image.version=\"1.2.3\" other=1.2.3\n";
+        let edits = Preset::Dockerfile.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(
+            edits,
+            vec![
+                (1, "ARG VERSION=1.2.4".to_string()),
+                (3, "LABEL org.opencontainers.image.version=\"1.2.4\" other=1.2.3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_image() {
+        let content = "services:\n  app:\n    image: registry.io:5000/team/myapp:1.2.3\n  db:\n    image: postgres:1.2.3\nimages:\n  - name: myapp\n    newTag: \"1.2.3\"\n  - name: other\n    newTag: 1.2.3\n";
+        let entry = FileConfig {
+            image: Some("myapp".to_string()),
+            ..FileConfig::default()
+        };
+        let edits = Preset::ContainerImage.propose(&entry, &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(
+            edits,
+            vec![
+                (2, "    image: registry.io:5000/team/myapp:1.2.4".to_string()),
+                (7, "    newTag: \"1.2.4\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_helm_chart() {
+        let content = "apiVersion: v2\nversion: 1.2.3\nappVersion: \"1.2.3\"\ndependencies:\n  - name: redis\n    version: 1.2.3\n";
+        let chart = Preset::HelmChartVersion.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.3.0");
+        assert_eq!(chart, vec![(1, "version: 1.3.0".to_string())]);
+        let app = Preset::HelmAppVersion.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.3.0");
+        assert_eq!(app, vec![(2, "appVersion: \"1.3.0\"".to_string())]);
+
+        let entry = FileConfig {
+            preset: Some(Preset::HelmChart),
+            kind: Some(VersionKind::Python),
+            ..FileConfig::default()
+        };
+        let kinds: Vec<VersionKind> = entry.expand().iter().map(|e| e.resolve_kind(VersionKind::Any)).collect();
+        assert_eq!(kinds, vec![VersionKind::Semver, VersionKind::Python]);
+    }
+
+    #[test]
+    fn test_gradle() {
+        let groovy = "group 'com.example'\nversion '1.2.3'\ndependencies {\n    implementation 'com.example:lib:1.2.3'\n}\n";
+        let edits = Preset::Gradle.propose(&FileConfig::default(), &lines(groovy), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "version '1.2.4'".to_string())]);
+
+        let kotlin = "version = \"1.2.3\"\n";
+        let edits = Preset::Gradle.propose(&FileConfig::default(), &lines(kotlin), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "version = \"1.2.4\"".to_string())]);
+
+        let properties = "org.gradle.jvmargs=-Xmx2g\nversion=1.2.3\nlibVersion=1.2.3\n";
+        let edits = Preset::GradleProperties.propose(&FileConfig::default(), &lines(properties), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "version=1.2.4".to_string())]);
+    }
+
+    #[test]
+    fn test_maven() {
+        let content = "<?xml version=\"1.0\"?>\n<project>\n  <parent>\n    <version>1.2.3</version>\n  </parent>\n  <!-- <version>1.2.3</version> -->\n  <version>1.2.3</version>\n  <dependencies>\n    <dependency><artifactId>x</artifactId><version>1.2.3</version></dependency>\n  </dependencies>\n</project>\n";
+        let edits = Preset::Maven.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(6, "  <version>1.2.4</version>".to_string())]);
+
+        let entry = FileConfig {
+            include_parent: true,
+            ..FileConfig::default()
+        };
+        let edits = Preset::Maven.propose(&entry, &lines(content), "1.2.3", "1.2");
+        assert_eq!(edits.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(edits[0].1, "    <version>1.2</version>");
+    }
+
+    #[test]
+    fn test_dotnet() {
+        let csproj = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Version>1.2.3</Version>\n    <AssemblyVersion>1.2.3.0</AssemblyVersion>\n    <FileVersion>1.2.3.0</FileVersion>\n  </PropertyGroup>\n  <ItemGroup>\n    <PackageReference Include=\"X\" Version=\"1.2.3\" />\n  </ItemGroup>\n</Project>\n";
+        let package = Preset::DotnetVersion.propose(&FileConfig::default(), &lines(csproj), "1.2.3", "1.3.0");
+        assert_eq!(package, vec![(2, "    <Version>1.3.0</Version>".to_string())]);
+        let assembly =
+            Preset::DotnetAssemblyVersion.propose(&FileConfig::default(), &lines(csproj), "1.2.3.0", "1.3.0.0");
+        assert_eq!(assembly.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![3, 4]);
+
+        let info = "[assembly: AssemblyVersion(\"1.2.3.0\")]\n[assembly: AssemblyFileVersion(\"1.2.3.0\")]\n";
+        let edits = Preset::DotnetAssemblyVersion.propose(&FileConfig::default(), &lines(info), "1.2.3.0", "1.3.0.0");
+        assert_eq!(edits[1], (1, "[assembly: AssemblyFileVersion(\"1.3.0.0\")]".to_string()));
+    }
+
+    #[test]
+    fn test_ruby() {
+        let gemspec = "Gem::Specification.new do |spec|\n  spec.version = \"1.2.3\"\n  spec.add_dependency \"x\", \"1.2.3\"\nend\n";
+        let edits = Preset::Gemspec.propose(&FileConfig::default(), &lines(gemspec), "1.2.3", "1.2.4.pre1");
+        assert_eq!(edits, vec![(1, "  spec.version = \"1.2.4.pre1\"".to_string())]);
+
+        let version_rb = "module Foo\n  VERSION = '1.2.3'.freeze\nend\n";
+        let edits = Preset::RubyVersion.propose(&FileConfig::default(), &lines(version_rb), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "  VERSION = '1.2.4'.freeze".to_string())]);
+    }
+
+    #[test]
+    fn test_c_header() {
+        let content = "#define MYAPP_VERSION_MAJOR 1\n#define MYAPP_VERSION_MINOR 2\n#define MYAPP_VERSION_PATCH 3\n#define MYAPP_VERSION \"1.2.3\"\n#define OTHER 3\n";
+        let edits = Preset::CHeader.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.3.0");
+        assert_eq!(
+            edits,
+            vec![
+                (1, "#define MYAPP_VERSION_MINOR 3".to_string()),
+                (2, "#define MYAPP_VERSION_PATCH 0".to_string()),
+                (3, "#define MYAPP_VERSION \"1.3.0\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_setup_cfg() {
+        let content = "[metadata]\nname = x\nversion = 1.2.3\n\n[options]\nversion = 1.2.3\n";
+        let edits = Preset::SetupCfg.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "version = 1.2.4".to_string())]);
+    }
+
+    #[test]
+    fn test_homebrew() {
+        let content = "class Foo < Formula\n  url \"https://example.com/foo/v1.2.3/foo-1.2.3.tar.gz\"\n  version \"1.2.3\"\n  sha256 \"abc\"\n\n  resource \"bar\" do\n    url \"https://example.com/bar-1.2.3.tar.gz\"\n    version \"1.2.3\"\n  end\nend\n";
+        let edits = Preset::Homebrew.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(
+            edits,
+            vec![
+                (1, "  url \"https://example.com/foo/v1.2.4/foo-1.2.4.tar.gz\"".to_string()),
+                (2, "  version \"1.2.4\"".to_string()),
+            ]
+        );
+
+        // Longer versions in the URL are left alone
+        let content = "  url \"https://example.com/v11.2.30/foo-1.2.3.tar.gz?deps=1.2.3.4\"\n";
+        let edits = Preset::Homebrew.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "  url \"https://example.com/v11.2.30/foo-1.2.4.tar.gz?deps=1.2.3.4\"".to_string())]);
+    }
+
+    #[test]
+    fn test_nix() {
+        let content = "{\n  version = \"1.2.3\";\n  src.url = \"https://example.com/v1.2.3.tar.gz\";\n}\n";
+        let edits = Preset::Nix.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "  version = \"1.2.4\";".to_string())]);
+    }
+
+    #[test]
+    fn test_pubspec() {
+        let content = "name: app\nversion: 1.2.3+7\ndependencies:\n  foo:\n    version: 1.2.3\n";
+        let propose = |increment_build| {
+            let entry = FileConfig {
+                increment_build,
+                ..FileConfig::default()
+            };
+            Preset::Pubspec.propose(&entry, &lines(content), "1.2.3", "1.2.4")
+        };
+        assert_eq!(propose(false), vec![(1, "version: 1.2.4+7".to_string())]);
+        assert_eq!(propose(true), vec![(1, "version: 1.2.4+8".to_string())]);
+
+        let edits = Preset::Pubspec.propose(&FileConfig::default(), &lines("version: 1.2.3\n"), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "version: 1.2.4".to_string())]);
+
+        // A current version that includes the build number
+        let entry = FileConfig {
+            increment_build: true,
+            ..FileConfig::default()
+        };
+        let edits = Preset::Pubspec.propose(&entry, &lines("version: 1.2.3+4\n"), "1.2.3+4", "1.2.4");
+        assert_eq!(edits, vec![(0, "version: 1.2.4+5".to_string())]);
+        let edits = Preset::Pubspec.propose(&FileConfig::default(), &lines("version: 1.2.3+4\n"), "1.2.3+4", "1.2.4");
+        assert_eq!(edits, vec![(0, "version: 1.2.4".to_string())]);
+    }
+
+    #[test]
+    fn test_mix() {
+        let content = "defmodule App.MixProject do\n  use Mix.Project\n\n  def project do\n    [\n      app: :app,\n      version: \"1.2.3\",\n      deps: deps()\n    ]\n  end\n\n  defp deps do\n    [{:dep, version: \"1.2.3\"}]\n  end\nend\n";
+        let edits = Preset::Mix.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(6, "      version: \"1.2.4\",".to_string())]);
+
+        let content = "defmodule App.MixProject do\n  @version \"1.2.3\"\n\n  def project do\n    [app: :app, version: @version, deps: [{:dep, \"1.2.3\"}]]\n  end\nend\n";
+        let edits = Preset::Mix.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "  @version \"1.2.4\"".to_string())]);
+    }
+
+    #[test]
+    fn test_composer() {
+        let content = "{\n    \"name\": \"vendor/pkg\",\n    \"version\": \"1.2.3\",\n    \"require\": {\n        \"vendor/dep\": \"1.2.3\"\n    }\n}\n";
+        let edits = Preset::Composer.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "    \"version\": \"1.2.4\",".to_string())]);
+        assert_eq!(Preset::Composer.missing_field_note(&lines(content)), None);
+
+        let content = "{\"name\": \"vendor/pkg\", \"require\": {\"vendor/dep\": \"1.2.3\"}}";
+        assert!(Preset::Composer.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4").is_empty());
+        assert!(Preset::Composer.missing_field_note(&lines(content)).is_some());
+    }
+
+    #[test]
+    fn test_r_description() {
+        let content = "Package: pkg\nVersion: 1.2.3.9000\nDepends:\n    R (>= 1.2.3.9000)\n";
+        let edits = Preset::RDescription.propose(&FileConfig::default(), &lines(content), "1.2.3.9000", "1.2.4");
+        assert_eq!(edits, vec![(1, "Version: 1.2.4".to_string())]);
+    }
+
+    #[test]
+    fn test_zig_zon() {
+        let content = ".{\n    .name = .app,\n    .version = \"1.2.3\",\n    .minimum_zig_version = \"1.2.3\",\n    .dependencies = .{},\n}\n";
+        let edits = Preset::ZigZon.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "    .version = \"1.2.4\",".to_string())]);
+    }
+
+    #[test]
+    fn test_haskell() {
+        let content = "cabal-version: 1.2.3.0\nname: app\nVersion:        1.2.3.0\n\nlibrary\n  build-depends: base, dep == 1.2.3.0\n";
+        let edits = Preset::Cabal.propose(&FileConfig::default(), &lines(content), "1.2.3.0", "1.2.4.0");
+        assert_eq!(edits, vec![(2, "Version:        1.2.4.0".to_string())]);
+
+        let content = "name: app\nversion: '1.2.3.0'\ndependencies:\n  - dep == 1.2.3.0\n";
+        let edits = Preset::Hpack.propose(&FileConfig::default(), &lines(content), "1.2.3.0", "1.2.4.0");
+        assert_eq!(edits, vec![(1, "version: '1.2.4.0'".to_string())]);
+    }
+
+    #[test]
+    fn test_cmake() {
+        let content = "cmake_minimum_required(VERSION 3.20)\nproject(MyApp\n  VERSION 1.2.3\n  LANGUAGES CXX)\nset(MYAPP_VERSION \"1.2.3\")\nset(MYAPP_VERSION_MAJOR 1)\nset(MYAPP_VERSION_MINOR 2)\nset(MYAPP_VERSION_PATCH 3)\nfind_package(Dep 1.2.3 REQUIRED)\n";
+        let edits = Preset::Cmake.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.3.0");
+        assert_eq!(
+            edits,
+            vec![
+                (2, "  VERSION 1.3.0".to_string()),
+                (4, "set(MYAPP_VERSION \"1.3.0\")".to_string()),
+                (6, "set(MYAPP_VERSION_MINOR 3)".to_string()),
+                (7, "set(MYAPP_VERSION_PATCH 0)".to_string()),
+            ]
+        );
+
+        let content = "project(MyApp VERSION 1.2.3 LANGUAGES C)\n";
+        let edits = Preset::Cmake.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "project(MyApp VERSION 1.2.4 LANGUAGES C)".to_string())]);
+    }
+
+    #[test]
+    fn test_meson_and_bazel() {
+        let content = "project('app', 'c',\n  version : '1.2.3',\n  license : 'MIT')\ndep = dependency('foo', version : '1.2.3')\n";
+        let edits = Preset::Meson.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(1, "  version : '1.2.4',".to_string())]);
+
+        let content = "module(name = \"app\", version = \"1.2.3\")\n\nbazel_dep(name = \"dep\", version = \"1.2.3\")\n";
+        let edits = Preset::BazelModule.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "module(name = \"app\", version = \"1.2.4\")".to_string())]);
+    }
+
+    #[test]
+    fn test_vcpkg_and_conan() {
+        let content = "{\n  \"name\": \"app\",\n  \"version-semver\": \"1.2.3\",\n  \"dependencies\": [{ \"name\": \"dep\", \"version>=\": \"1.2.3\" }]\n}\n";
+        let edits = Preset::Vcpkg.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "  \"version-semver\": \"1.2.4\",".to_string())]);
+
+        let content = "class AppConan(ConanFile):\n    name = \"app\"\n    version = \"1.2.3\"\n    requires = \"dep/1.2.3\"\n";
+        let edits = Preset::Conan.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(2, "    version = \"1.2.4\"".to_string())]);
+
+        let content = "version: \"1.2.3\"\nsources:\n  \"1.2.3\":\n    url: https://example.com/app-1.2.3.tar.gz\n";
+        let edits = Preset::Conan.propose(&FileConfig::default(), &lines(content), "1.2.3", "1.2.4");
+        assert_eq!(edits, vec![(0, "version: \"1.2.4\"".to_string())]);
+    }
+}
@@ -0,0 +1,142 @@
+use std::ffi::OsStr;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long hooks may run when `hook-timeout` isn't set
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The program to run for a command name. On Windows, `Command` only finds `.exe` files by
+/// itself, so `PATHEXT` is searched too to pick up `.cmd`/`.bat` shims (e.g. from npm or pipx).
+pub fn program(name: &str) -> PathBuf {
+    if cfg!(windows)
+        && let Some(path) = std::env::var_os("PATH")
+    {
+        let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        let exts: Vec<&str> = exts.split(';').filter(|ext| !ext.is_empty()).collect();
+        if let Some(found) = find_program(name, &path, &exts) {
+            return found;
+        }
+    }
+    PathBuf::from(name)
+}
+
+/// The first `name` + extension found in the directories of a `PATH`-style list
+fn find_program(name: &str, path: &OsStr, exts: &[&str]) -> Option<PathBuf> {
+    std::env::split_paths(path).find_map(|dir| {
+        exts.iter()
+            .map(|ext| dir.join(format!("{name}{}", ext.to_lowercase())))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// The result of a command run with `run_captured`
+pub struct Captured {
+    /// The exit status, or `None` if the command was killed after the timeout
+    pub status: Option<ExitStatus>,
+    /// Everything the command wrote to stdout, then to stderr
+    pub output: String,
+}
+
+impl Captured {
+    pub fn success(&self) -> bool {
+        self.status.is_some_and(|status| status.success())
+    }
+
+    /// Why the command failed (e.g. `timed out after 600s`), followed by its output
+    pub fn failure(&self, timeout: Duration) -> String {
+        let reason = match self.status {
+            Some(status) => format!("failed ({status})"),
+            None => format!("timed out after {}s", timeout.as_secs()),
+        };
+        let output = self.output.trim_end();
+        if output.is_empty() { reason } else { format!("{reason}:\n{output}") }
+    }
+}
+
+/// Run a command with its output captured, killing it once `timeout` has passed
+pub fn run_captured(command: &mut Command, timeout: Duration) -> Result<Captured, String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // Read both pipes on threads so a chatty command can't block on a full pipe. The buffers are
+    // shared rather than joined, since a killed command's children may keep the pipes open.
+    let collect = |pipe: Option<Box<dyn Read + Send>>| {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let reader = pipe.map(|mut pipe| {
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || {
+                let mut chunk = [0; 4096];
+                while let Ok(n) = pipe.read(&mut chunk)
+                    && n > 0
+                {
+                    buffer.lock().unwrap().extend_from_slice(&chunk[..n]);
+                }
+            })
+        });
+        (buffer, reader)
+    };
+    let (stdout, stdout_reader) = collect(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let (stderr, stderr_reader) = collect(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break Some(status);
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    // Give the readers a moment to drain what the command wrote last
+    let drained = Instant::now();
+    while [&stdout_reader, &stderr_reader].iter().any(|r| r.as_ref().is_some_and(|r| !r.is_finished()))
+        && drained.elapsed() < Duration::from_secs(1)
+    {
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    let mut output = String::from_utf8_lossy(&stdout.lock().unwrap()).into_owned();
+    output.push_str(&String::from_utf8_lossy(&stderr.lock().unwrap()));
+    Ok(Captured { status, output })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_find_program() {
+        let dir = std::env::temp_dir().join(format!("bver-find-program-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("prek.cmd"), "").unwrap();
+        let path = std::env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        assert_eq!(find_program("prek", &path, &[".EXE", ".CMD"]), Some(dir.join("prek.cmd")));
+        assert_eq!(find_program("git", &path, &[".EXE", ".CMD"]), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_captured() {
+        let done = run_captured(Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]), Duration::from_secs(5)).unwrap();
+        assert!(!done.success());
+        assert_eq!(done.output, "out\nerr\n");
+        assert!(done.failure(Duration::from_secs(5)).ends_with(":\nout\nerr"));
+
+        let hung = run_captured(Command::new("sh").args(["-c", "echo waiting; sleep 5"]), Duration::from_millis(300)).unwrap();
+        assert!(hung.status.is_none());
+        assert!(hung.failure(Duration::from_secs(1)).starts_with("timed out after 1s"));
+        assert!(hung.output.contains("waiting"));
+    }
+}
@@ -1,5 +1,18 @@
+use regex::Regex;
+use serde::de::IgnoredAny;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cast::CastRules;
+use crate::docker::DockerConfig;
+use crate::engine::ReplaceOptions;
+use crate::kind::VersionKind;
+use crate::presets::Preset;
+use crate::process::DEFAULT_HOOK_TIMEOUT;
+use crate::version::validate_version;
+use crate::vfs::Vfs;
 
 #[derive(Debug, Deserialize, Serialize, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -8,28 +21,355 @@ pub struct Config {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context_lines: Option<usize>,
     #[serde(default)]
-    pub default_kind: FileKind,
+    pub default_kind: VersionKind,
     #[serde(default)]
     pub on_invalid_version: OnInvalidVersion,
+    #[serde(default, skip_serializing_if = "OnWrite::is_keep")]
+    pub on_write: OnWrite,
+    #[serde(default, skip_serializing_if = "ZeroVer::is_standard")]
+    pub zero_ver: ZeroVer,
+    /// The only bump targets `bver bump` accepts (e.g. `["patch", "minor"]`); empty allows all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_targets: Vec<String>,
+    /// The lowest new version a bump may produce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_version: Option<String>,
+    /// New versions must stay below this one (e.g. `3.0.0` to stay on 2.x)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_version: Option<String>,
+    /// Seconds a `post-hook` or the pre-commit hooks may run before they're killed (default: 600)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_timeout: Option<u64>,
+    /// Major bumps need `--confirm-major`, or the new version typed into the TUI
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protect_major: bool,
+    /// How prereleases are rendered per kind, e.g. `semver = "-{label}.{n}"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prerelease_style: BTreeMap<VersionKind, String>,
+    /// How casts to a kind write the segments after the release (`[cast.<kind>]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cast: BTreeMap<VersionKind, CastRules>,
     #[serde(default)]
     pub git: GitConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sync_workspaces: bool,
+    /// Also update the package's own entries in its lockfile (`Cargo.lock`, `package-lock.json`, `npm-shrinkwrap.json`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sync_lockfiles: bool,
+    #[serde(default, rename = "file")]
+    pub files: Vec<FileConfig>,
+    /// Requirements on this package in other files to keep in range (`[[pin]]`)
+    #[serde(default, rename = "pin", skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<PinConfig>,
+    /// A shields.io endpoint JSON file written on every bump (`[badge]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badge: Option<BadgeConfig>,
+    /// The image tags `bver docker-tags` suggests (`[docker]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docker: Option<DockerConfig>,
+    /// Files rendered from a template on every bump (`[[generate]]`)
+    #[serde(default, rename = "generate", skip_serializing_if = "Vec::is_empty")]
+    pub generated: Vec<GenerateConfig>,
+    /// Settings for branches matching a pattern, e.g. patch bumps only on `release/*` (`[[branch]]`)
+    #[serde(default, rename = "branch", skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<BranchPolicy>,
+    /// The pattern of the `[[branch]]` policy applied to this config, if any
+    #[serde(skip)]
+    pub branch_pattern: Option<String>,
+    /// Additional, independently bumped versions (`[versions.<name>]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub versions: BTreeMap<String, NamedVersion>,
+    /// Keys bver doesn't know, reported by `bver check-config`
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
+    #[serde(skip)]
+    pub source: Option<ConfigSource>,
+    #[serde(skip)]
+    pub version_fields: Vec<VersionField>,
+}
+
+impl Config {
+    /// How long hooks may run (`hook-timeout`)
+    pub fn hook_timeout(&self) -> Duration {
+        self.hook_timeout.map_or(DEFAULT_HOOK_TIMEOUT, Duration::from_secs)
+    }
+
+    /// Files to update when no `[[file]]` entries are configured:
+    /// the manifest the config (and its version) was loaded from, unless its
+    /// version field is already updated structurally
+    pub fn default_files(&self, project_root: &Path) -> Vec<FileConfig> {
+        if !self.version_fields.is_empty() {
+            return Vec::new();
+        }
+        let Some(source) = &self.source else {
+            return Vec::new();
+        };
+        let path = source.path();
+        let src = path.strip_prefix(project_root).unwrap_or(path).to_path_buf();
+        vec![FileConfig {
+            src: Some(src),
+            kind: source.kind(),
+            ..FileConfig::default()
+        }]
+    }
+
+    /// Check that `current-version` (and that of every named version) is valid for its kind,
+    /// so a bad version is reported where it's defined rather than halfway through a command
+    pub fn validate_current_version(&self) -> Result<(), String> {
+        if let Some(version) = &self.current_version {
+            validate_version(version, self.default_kind).map_err(|e| {
+                format!(
+                    "Invalid current version '{version}' in {} (kind: {}): {e}",
+                    self.current_version_origin(None),
+                    self.default_kind.name()
+                )
+            })?;
+        }
+        for (name, named) in &self.versions {
+            let kind = named.default_kind.unwrap_or(self.default_kind);
+            if let Some(version) = &named.current_version {
+                validate_version(version, kind).map_err(|e| {
+                    format!(
+                        "Invalid current version '{version}' in {} (kind: {}): {e}",
+                        self.current_version_origin(Some(name)),
+                        kind.name()
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Where a current version is defined, e.g. `pyproject.toml (project.version)`
+    fn current_version_origin(&self, name: Option<&str>) -> String {
+        let (path, key) = match (name, self.version_fields.first(), &self.source) {
+            (None, Some(field), _) => (field.path.as_path(), field.key.clone()),
+            (_, _, Some(source)) => {
+                let mut key: Vec<String> = source.section_key().iter().map(|k| k.to_string()).collect();
+                if let Some(name) = name {
+                    key.extend(["versions".to_string(), name.to_string()]);
+                }
+                key.push("current-version".to_string());
+                (source.path(), key)
+            }
+            _ => return "the config".to_string(),
+        };
+        format!("{} ({})", path.display(), key.join("."))
+    }
+
+    /// Switch to the named version `name`: its current version, files, and git settings
+    /// replace the top-level ones, and its `current-version` key in the config file is
+    /// written back on bump
+    pub fn select_version(mut self, name: &str) -> Result<Config, String> {
+        let Some(named) = self.versions.remove(name) else {
+            let known: Vec<&str> = self.versions.keys().map(String::as_str).collect();
+            return Err(if known.is_empty() {
+                format!("Unknown version name '{name}': no [versions] are configured")
+            } else {
+                format!("Unknown version name '{name}' (expected one of: {})", known.join(", "))
+            });
+        };
+        let source = self.source.as_ref().ok_or("Named versions require a config file")?;
+
+        let mut key = source.section_key().to_vec();
+        key.extend(["versions", name, "current-version"]);
+        let kind = named.default_kind.unwrap_or(self.default_kind);
+
+        self.version_fields = vec![VersionField::new(source.path(), &key, kind)];
+        self.current_version = named.current_version;
+        self.default_kind = kind;
+        self.files = named.files;
+        self.badge = named.badge;
+        self.generated = named.generated;
+        if let Some(git) = named.git {
+            self.git = git;
+        }
+        self.versions.clear();
+        Ok(self)
+    }
+
+    /// The config with the settings of the first `[[branch]]` policy matching `branch` applied
+    pub fn for_branch(mut self, branch: &str) -> Config {
+        let Some(policy) = self.branches.iter().find(|policy| policy.matches(branch)).cloned() else {
+            return self;
+        };
+        if let Some(targets) = policy.allowed_targets {
+            self.allowed_targets = targets;
+        }
+        if let Some(template) = policy.tag_template {
+            self.git.tag_template = template;
+        }
+        self.branch_pattern = Some(policy.pattern);
+        self
+    }
+}
+
+/// Settings that apply on branches matching `pattern` (`[[branch]]`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct BranchPolicy {
+    /// A branch name, where `*` matches anything (e.g. `release/*`)
+    pub pattern: String,
+    /// Overrides `allowed-targets` on these branches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_targets: Option<Vec<String>>,
+    /// Overrides `git.tag-template` on these branches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_template: Option<String>,
+}
+
+impl BranchPolicy {
+    pub fn matches(&self, branch: &str) -> bool {
+        let pattern = format!("^{}$", regex::escape(&self.pattern).replace(r"\*", ".*"));
+        Regex::new(&pattern).is_ok_and(|re| re.is_match(branch))
+    }
+}
+
+/// A version tracked alongside the main one, with its own files and bump state
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct NamedVersion {
+    pub current_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_kind: Option<VersionKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitConfig>,
     #[serde(default, rename = "file")]
     pub files: Vec<FileConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badge: Option<BadgeConfig>,
+    #[serde(default, rename = "generate", skip_serializing_if = "Vec::is_empty")]
+    pub generated: Vec<GenerateConfig>,
+}
+
+/// A version badge, written as shields.io endpoint JSON to `path` (relative to the project root)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct BadgeConfig {
+    pub path: PathBuf,
+    #[serde(default = "default_badge_label")]
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+fn default_badge_label() -> String {
+    "version".to_string()
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// A file (e.g. `src/version.rs`) rendered on every bump, from an inline `template`
+/// or a `template-file` (paths relative to the project root)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct GenerateConfig {
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_file: Option<PathBuf>,
+}
+
+/// A rule rewriting requirements on `package` (e.g. `mypkg>=1.2,<1.3`) in `src` to `range`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PinConfig {
+    pub src: PathBuf,
+    pub package: String,
+    /// Template for the new requirement, e.g. `>={major}.{minor},<{major}.{next-minor}`
+    #[serde(default = "default_pin_range")]
+    pub range: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<VersionKind>,
+}
+
+fn default_pin_range() -> String {
+    ">={new-version}".to_string()
+}
+
+/// A manifest key the current version was read from; it is written back structurally on bump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionField {
+    pub path: PathBuf,
+    pub key: Vec<String>,
+    pub kind: VersionKind,
+}
+
+impl VersionField {
+    pub fn new(path: impl Into<PathBuf>, key: &[&str], kind: VersionKind) -> Self {
+        Self {
+            path: path.into(),
+            key: key.iter().map(|k| k.to_string()).collect(),
+            kind,
+        }
+    }
+}
+
+/// The file a config was loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    BverToml(PathBuf),
+    PyprojectToml(PathBuf),
+    PackageJson(PathBuf),
+    CargoToml(PathBuf),
+}
+
+impl ConfigSource {
+    pub fn path(&self) -> &Path {
+        match self {
+            ConfigSource::BverToml(path)
+            | ConfigSource::PyprojectToml(path)
+            | ConfigSource::PackageJson(path)
+            | ConfigSource::CargoToml(path) => path,
+        }
+    }
+
+    /// The keys of the table holding the bver config within the file
+    pub fn section_key(&self) -> &'static [&'static str] {
+        match self {
+            ConfigSource::BverToml(_) => &[],
+            ConfigSource::PyprojectToml(_) => &["tool", "bver"],
+            ConfigSource::PackageJson(_) => &["bver"],
+            ConfigSource::CargoToml(_) => &["package", "metadata", "bver"],
+        }
+    }
+
+    /// The version kind used by the manifest's own version field
+    pub fn kind(&self) -> Option<VersionKind> {
+        match self {
+            ConfigSource::BverToml(_) => None,
+            ConfigSource::PyprojectToml(_) => Some(VersionKind::Python),
+            ConfigSource::PackageJson(_) => Some(VersionKind::Semver),
+            ConfigSource::CargoToml(_) => Some(VersionKind::Cargo),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct GitConfig {
     #[serde(default = "default_actions")]
     pub actions: Vec<Action>,
     #[serde(default)]
     pub run_pre_commit: RunPreCommit,
+    /// How often to re-run failing pre-commit hooks (re-staging the files they fixed)
+    #[serde(default = "default_pre_commit_retries")]
+    pub pre_commit_retries: usize,
+    /// Run the hooks on all files or only on the files the bump changed
+    #[serde(default)]
+    pub pre_commit_scope: PreCommitScope,
+    /// Only run these hooks (by id); all hooks when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_commit_hooks: Vec<String>,
     #[serde(default = "default_tag_template")]
     pub tag_template: String,
     #[serde(default = "default_commit_template")]
     pub commit_template: String,
     #[serde(default = "default_branch_template")]
     pub branch_template: String,
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
 }
 
 impl Default for GitConfig {
@@ -37,9 +377,13 @@ impl Default for GitConfig {
         Self {
             actions: default_actions(),
             run_pre_commit: RunPreCommit::default(),
+            pre_commit_retries: default_pre_commit_retries(),
+            pre_commit_scope: PreCommitScope::default(),
+            pre_commit_hooks: Vec::new(),
             tag_template: default_tag_template(),
             commit_template: default_commit_template(),
             branch_template: default_branch_template(),
+            unknown: BTreeMap::new(),
         }
     }
 }
@@ -73,6 +417,10 @@ fn default_actions() -> Vec<Action> {
     vec![Action::AddAll, Action::Commit, Action::Tag]
 }
 
+fn default_pre_commit_retries() -> usize {
+    1
+}
+
 fn default_tag_template() -> String {
     "{new-version}".to_string()
 }
@@ -85,21 +433,144 @@ fn default_branch_template() -> String {
     "release/{new-version}".to_string()
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
 pub struct FileConfig {
-    pub src: PathBuf,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub kind: Option<FileKind>,
+    pub src: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<VersionKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<Preset>,
+    /// Image name filter for the `container-image` preset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Also update `<parent><version>` with the `maven` preset
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_parent: bool,
+    /// Increment the `+N` build number with the `pubspec` preset
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub increment_build: bool,
+    /// Accept casts that drop parts of the version (e.g. `.post1` in a semver file)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_lossy: bool,
+    /// Lines of context shown around this file's changes, instead of the global `context-lines`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<usize>,
+    /// What to do when the version is invalid for this file's kind, instead of the global setting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_invalid_version: Option<OnInvalidVersion>,
+    /// How casts for this file write the segments after the release, over `[cast.<kind>]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<CastRules>,
+    /// Text to look for, as a template (defaults to `{current-version}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Text to replace it with, as a template (defaults to `{new-version}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+    /// Literal text the version must follow, e.g. `version = "` (a lighter alternative to `search`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Literal text the version must be followed by, e.g. `"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// Only replace between lines containing `bver:start` and `bver:end`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub markers: bool,
+    /// Only replace within these lines, e.g. `"1..50"` (1-based and inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<String>,
+    /// Only replace after the first line matching this regex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Only replace before the first line matching this regex (that follows `after`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Shell command run after the file is written (e.g. to update a source hash),
+    /// as a template that also supports `{file}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_hook: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-pub enum FileKind {
-    #[default]
-    Any,
-    Simple,
-    Python,
-    Semver,
+impl FileConfig {
+    /// The configured `src`, falling back to the preset's default target
+    pub fn resolve_src(&self, fs: &dyn Vfs, project_root: &Path) -> Result<PathBuf, String> {
+        match (&self.src, self.preset) {
+            (Some(src), _) => Ok(src.clone()),
+            (None, Some(preset)) => preset
+                .default_src(fs, project_root)
+                .map(PathBuf::from)
+                .ok_or_else(|| format!("Could not determine a file for preset {preset:?}; set `src`")),
+            (None, None) => Err("[[file]] entry is missing `src`".to_string()),
+        }
+    }
+
+    /// The entry's kind, falling back to the preset's kind and then `default_kind`.
+    /// Presets for fields with a mandated format (e.g. a Helm chart version) always use that kind.
+    pub fn resolve_kind(&self, default_kind: VersionKind) -> VersionKind {
+        self.preset
+            .and_then(Preset::required_kind)
+            .or(self.kind)
+            .or_else(|| self.preset.and_then(Preset::default_kind))
+            .unwrap_or(default_kind)
+    }
+
+    /// The entry's invalid-version policy, falling back to the global one. Presets with a
+    /// mandated kind always cast, since their format can't be chosen by the user.
+    pub fn resolve_on_invalid(&self, on_invalid: OnInvalidVersion) -> OnInvalidVersion {
+        if self.preset.and_then(Preset::required_kind).is_some() {
+            OnInvalidVersion::Cast
+        } else {
+            self.on_invalid_version.unwrap_or(on_invalid)
+        }
+    }
+
+    /// How to find and replace the version in the entry's files
+    pub fn replace_options(&self) -> ReplaceOptions {
+        ReplaceOptions {
+            search: self.search.clone(),
+            replace: self.replace.clone(),
+            prefix: self.prefix.clone(),
+            suffix: self.suffix.clone(),
+            markers: self.markers,
+            lines: self.lines.clone(),
+            after: self.after.clone(),
+            before: self.before.clone(),
+        }
+    }
+
+    /// The entry's cast rules, with the segments they leave open taken from those of its kind
+    pub fn resolve_cast(&self, kind_rules: Option<&CastRules>) -> CastRules {
+        match &self.cast {
+            Some(rules) => rules.or(kind_rules),
+            None => kind_rules.cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Whether lossy casts are accepted. Presets with a mandated kind always accept them,
+    /// since their format can't hold everything by design.
+    pub fn resolve_allow_lossy(&self) -> bool {
+        self.allow_lossy || self.preset.and_then(Preset::required_kind).is_some()
+    }
+
+    /// Split an entry whose preset covers several independently-versioned fields
+    /// into one entry per field
+    pub fn expand(&self) -> Vec<FileConfig> {
+        match self.preset {
+            Some(preset) => preset
+                .expand()
+                .into_iter()
+                .map(|part| FileConfig {
+                    preset: Some(part),
+                    ..self.clone()
+                })
+                .collect(),
+            None => vec![self.clone()],
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
@@ -110,6 +581,40 @@ pub enum OnInvalidVersion {
     Cast,
 }
 
+/// How new versions are rendered into files
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnWrite {
+    /// Write the version as computed or given
+    #[default]
+    Keep,
+    /// Write `python` versions in canonical PEP 440 form
+    Normalize,
+}
+
+impl OnWrite {
+    fn is_keep(&self) -> bool {
+        *self == OnWrite::Keep
+    }
+}
+
+/// How `major` bumps treat 0.x versions
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ZeroVer {
+    /// `major` always goes to the next major version (0.4.2 -> 1.0.0)
+    #[default]
+    Standard,
+    /// While major is 0, `major` bumps the minor version (0.4.2 -> 0.5.0)
+    Strict,
+}
+
+impl ZeroVer {
+    fn is_standard(&self) -> bool {
+        *self == ZeroVer::Standard
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum RunPreCommit {
@@ -119,6 +624,14 @@ pub enum RunPreCommit {
     WhenPresent,
 }
 
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreCommitScope {
+    #[default]
+    AllFiles,
+    ChangedFiles,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Action {
@@ -129,3 +642,88 @@ pub enum Action {
     Push,
     Pr,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_branch() {
+        let content = r#"
+current-version = "1.2.3"
+
+[git]
+tag-template = "v{new-version}"
+
+[[branch]]
+pattern = "release/*"
+allowed-targets = ["patch"]
+tag-template = "release-{new-version}"
+
+[[branch]]
+pattern = "main"
+"#;
+        let load = || -> Config { toml::from_str(content).unwrap() };
+        let config = load().for_branch("release/1.2");
+        assert_eq!(config.allowed_targets, vec!["patch"]);
+        assert_eq!(config.git.tag_template, "release-{new-version}");
+        assert_eq!(config.branch_pattern.as_deref(), Some("release/*"));
+
+        let config = load().for_branch("main");
+        assert!(config.allowed_targets.is_empty());
+        assert_eq!(config.git.tag_template, "v{new-version}");
+        assert!(load().for_branch("feature/release/x").branch_pattern.is_none());
+    }
+
+    #[test]
+    fn test_select_named_version() {
+        let content = r#"
+current-version = "1.2.3"
+
+[versions.api]
+current-version = "2.0.0"
+default-kind = "semver"
+
+[[versions.api.file]]
+src = "openapi.yaml"
+"#;
+        let mut config: Config = toml::from_str(content).unwrap();
+        config.source = Some(ConfigSource::PyprojectToml(PathBuf::from("pyproject.toml")));
+
+        let api = config.select_version("api").unwrap();
+        assert_eq!(api.current_version.as_deref(), Some("2.0.0"));
+        assert_eq!(api.files[0].src, Some(PathBuf::from("openapi.yaml")));
+        assert_eq!(
+            api.version_fields,
+            vec![VersionField::new("pyproject.toml", &["tool", "bver", "versions", "api", "current-version"], VersionKind::Semver)]
+        );
+    }
+
+    #[test]
+    fn test_validate_current_version() {
+        let mut config: Config = toml::from_str("current-version = \"1.2\"\ndefault-kind = \"semver\"").unwrap();
+        config.source = Some(ConfigSource::BverToml(PathBuf::from("bver.toml")));
+        let err = config.validate_current_version().unwrap_err();
+        assert!(err.contains("bver.toml (current-version)"), "{err}");
+
+        let mut config: Config = toml::from_str("default-kind = \"semver\"").unwrap();
+        config.current_version = Some("1.2.3".to_string());
+        config.versions.insert(
+            "api".to_string(),
+            NamedVersion {
+                current_version: Some("v2".to_string()),
+                ..NamedVersion::default()
+            },
+        );
+        config.source = Some(ConfigSource::PyprojectToml(PathBuf::from("pyproject.toml")));
+        config.version_fields.push(VersionField::new("pyproject.toml", &["project", "version"], VersionKind::Python));
+        let err = config.validate_current_version().unwrap_err();
+        assert!(err.contains("pyproject.toml (tool.bver.versions.api.current-version)"), "{err}");
+    }
+
+    #[test]
+    fn test_select_unknown_version() {
+        let config: Config = toml::from_str("current-version = \"1.0.0\"").unwrap();
+        assert!(config.select_version("api").is_err());
+    }
+}
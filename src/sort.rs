@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+
+use crate::kind::VersionKind;
+use crate::pep440::Pep440Version;
+
+/// A version parsed for ordering, keeping the original spelling for output
+struct Sortable {
+    original: String,
+    semver: Option<semver::Version>,
+    pep440: Option<Pep440Version>,
+}
+
+impl Sortable {
+    fn parse(original: &str) -> Option<Self> {
+        let bare = original.strip_prefix(['v', 'V']).unwrap_or(original);
+        let semver = semver::Version::parse(bare).ok();
+        let pep440 = original.parse().ok();
+        (semver.is_some() || pep440.is_some()).then(|| Sortable {
+            original: original.to_string(),
+            semver,
+            pep440,
+        })
+    }
+}
+
+/// Sort versions oldest to newest. Semver precedence is used when every version is semver,
+/// PEP 440 ordering otherwise. Returns the sorted versions and the inputs that aren't versions.
+pub fn sort_versions<S: AsRef<str>>(versions: &[S]) -> (Vec<String>, Vec<String>) {
+    let mut parsed = Vec::new();
+    let mut skipped = Vec::new();
+    for version in versions {
+        let version = version.as_ref().trim();
+        if version.is_empty() {
+            continue;
+        }
+        match Sortable::parse(version) {
+            Some(sortable) => parsed.push(sortable),
+            None => skipped.push(version.to_string()),
+        }
+    }
+
+    let all_semver = parsed.iter().all(|v| v.semver.is_some());
+    let (mut ordered, unordered): (Vec<Sortable>, Vec<Sortable>) = parsed
+        .into_iter()
+        .partition(|v| all_semver || v.pep440.is_some());
+    skipped.extend(unordered.into_iter().map(|v| v.original));
+
+    ordered.sort_by(|a, b| {
+        let ordering = if all_semver {
+            a.semver.cmp(&b.semver)
+        } else {
+            a.pep440.cmp(&b.pep440)
+        };
+        ordering.then_with(|| a.original.cmp(&b.original))
+    });
+    (ordered.into_iter().map(|v| v.original).collect(), skipped)
+}
+
+/// The newest of the given versions
+pub fn max_version<S: AsRef<str>>(versions: &[S]) -> Option<String> {
+    sort_versions(versions).0.pop()
+}
+
+/// Compare two versions under a kind's scheme: semver precedence for `semver` and `cargo` when both
+/// are semver, PEP 440 ordering otherwise (or semver when they aren't PEP 440)
+pub fn compare_versions(old: &str, new: &str, kind: VersionKind) -> Result<Ordering, String> {
+    let old = Sortable::parse(old).ok_or_else(|| format!("'{old}' is not a version"))?;
+    let new = Sortable::parse(new).ok_or_else(|| format!("'{new}' is not a version"))?;
+    let semver_kind = matches!(kind, VersionKind::Semver | VersionKind::Cargo);
+    match (&old, &new) {
+        (Sortable { semver: Some(a), .. }, Sortable { semver: Some(b), .. }) if semver_kind => Ok(a.cmp(b)),
+        (Sortable { pep440: Some(a), .. }, Sortable { pep440: Some(b), .. }) => Ok(a.cmp(b)),
+        (Sortable { semver: Some(a), .. }, Sortable { semver: Some(b), .. }) => Ok(a.cmp(b)),
+        _ => Err(format!("'{}' and '{}' can't be compared", old.original, new.original)),
+    }
+}
+
+/// What changed between two versions, as reported by `bver diff`
+#[derive(Debug, PartialEq)]
+pub struct VersionDiff {
+    pub ordering: Ordering,
+    /// The components that differ, as (name, old, new); missing components are empty
+    pub changed: Vec<(&'static str, String, String)>,
+}
+
+/// Split a version into major, minor, patch, pre, post and dev for `bver diff`
+fn components(version: &Sortable) -> [(&'static str, String); 6] {
+    let names = ["major", "minor", "patch", "pre", "post", "dev"];
+    let values = if let Some(v) = &version.pep440 {
+        let release = |i: usize| v.release.get(i).copied().unwrap_or(0).to_string();
+        [
+            release(0),
+            release(1),
+            release(2),
+            v.pre.map(|(phase, n)| format!("{phase}{n}")).unwrap_or_default(),
+            v.post.map(|n| n.to_string()).unwrap_or_default(),
+            v.dev.map(|n| n.to_string()).unwrap_or_default(),
+        ]
+    } else {
+        let v = version.semver.as_ref().expect("a version is semver or PEP 440");
+        [v.major.to_string(), v.minor.to_string(), v.patch.to_string(), v.pre.to_string(), String::new(), String::new()]
+    };
+    let mut values = values.into_iter();
+    names.map(|name| (name, values.next().unwrap_or_default()))
+}
+
+/// Which components changed between two versions and whether `new` is newer under the kind's scheme
+pub fn version_diff(old: &str, new: &str, kind: VersionKind) -> Result<VersionDiff, String> {
+    let ordering = compare_versions(old, new, kind)?;
+    let (old, new) = (Sortable::parse(old).unwrap(), Sortable::parse(new).unwrap());
+    let changed = components(&old)
+        .into_iter()
+        .zip(components(&new))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((name, a), (_, b))| (name, a, b))
+        .collect();
+    Ok(VersionDiff { ordering, changed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_semver_tags() {
+        let (sorted, skipped) = sort_versions(&["v1.10.0", "v1.2.0", "v1.2.0-rc.1", "latest", "v1.9.3"]);
+        assert_eq!(sorted, vec!["v1.2.0-rc.1", "v1.2.0", "v1.9.3", "v1.10.0"]);
+        assert_eq!(skipped, vec!["latest"]);
+    }
+
+    #[test]
+    fn test_sort_pep440() {
+        let (sorted, _) = sort_versions(&["1.0", "1.0.post1", "1.0rc1", "1.0.dev0", "0.9"]);
+        assert_eq!(sorted, vec!["0.9", "1.0.dev0", "1.0rc1", "1.0", "1.0.post1"]);
+        assert_eq!(max_version(&["2.0a1", "1.9", "2.0"]).as_deref(), Some("2.0"));
+    }
+
+    #[test]
+    fn test_version_diff() {
+        let diff = version_diff("1.2.3", "1.3.0rc1", VersionKind::Python).unwrap();
+        assert_eq!(diff.ordering, Ordering::Less);
+        let names: Vec<_> = diff.changed.iter().map(|(name, _, _)| *name).collect();
+        assert_eq!(names, vec!["minor", "patch", "pre"]);
+        assert_eq!(diff.changed[2], ("pre", String::new(), "rc1".to_string()));
+
+        assert_eq!(version_diff("2.0.0", "1.9.9", VersionKind::Any).unwrap().ordering, Ordering::Greater);
+        assert_eq!(version_diff("1.0", "1.0.0", VersionKind::Any).unwrap().changed, vec![]);
+        // semver orders prerelease identifiers PEP 440 doesn't know
+        let diff = version_diff("1.0.0-alpha.2", "1.0.0-alpha.10", VersionKind::Semver).unwrap();
+        assert_eq!(diff.ordering, Ordering::Less);
+        assert!(version_diff("1.0", "latest", VersionKind::Any).is_err());
+    }
+}
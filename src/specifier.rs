@@ -0,0 +1,190 @@
+use crate::pep440::Pep440Version;
+
+/// Check whether `version` satisfies `range`.
+///
+/// A range using syntax only semver has (`^1.2`, `~1.2.3`, `1.x`, `*`, a bare `1.2`, `||` between
+/// alternatives) is a Cargo-style semver requirement. There, a prerelease only matches a
+/// comparator with the same major.minor.patch, so `2.1.0-rc.1` isn't in `^2.0`. Any other range
+/// is a PEP 440 specifier set (`>=2.0, <3.0`, `~=1.4`, `==1.2.*`, `!=1.3.1`), in which
+/// prereleases are ordered like any other version, except that `<V` excludes V's own prereleases.
+pub fn satisfies(version: &str, range: &str) -> Result<bool, String> {
+    if is_semver_range(range) {
+        let parsed = semver::Version::parse(version).map_err(|e| format!("Invalid semver version '{version}': {e}"))?;
+        let alternatives = parse_semver_range(range).map_err(|e| format!("Invalid semver range '{range}': {e}"))?;
+        return Ok(alternatives.iter().any(|req| req.matches(&parsed)));
+    }
+
+    let parsed: Pep440Version = version.parse()?;
+    let specifiers = range
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(Specifier::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if specifiers.is_empty() {
+        return Err(format!("Empty version range: '{range}'"));
+    }
+    Ok(specifiers.iter().all(|spec| spec.contains(&parsed)))
+}
+
+/// Whether a range uses syntax that PEP 440 doesn't have, so it has to be read as semver
+fn is_semver_range(range: &str) -> bool {
+    range.contains("||")
+        || range.split(',').map(str::trim).filter(|c| !c.is_empty()).any(|comparator| {
+            let operator_len = comparator
+                .find(|c: char| !matches!(c, '<' | '>' | '=' | '!' | '~' | '^'))
+                .unwrap_or(comparator.len());
+            let (operator, version) = comparator.split_at(operator_len);
+            let wildcard = version.trim().split('.').any(|part| matches!(part, "x" | "X" | "*"));
+            matches!(operator, "" | "=" | "^" | "~")
+                || (wildcard && !matches!(operator, "==" | "!="))
+        })
+}
+
+fn parse_semver_range(range: &str) -> Result<Vec<semver::VersionReq>, semver::Error> {
+    range.split("||").map(|alt| semver::VersionReq::parse(alt.trim())).collect()
+}
+
+/// A single PEP 440 version specifier, e.g. `>=1.2` or `==1.4.*`
+/// https://packaging.python.org/en/latest/specifications/version-specifiers/
+struct Specifier<'a> {
+    operator: &'a str,
+    version: &'a str,
+}
+
+impl<'a> Specifier<'a> {
+    fn parse(spec: &'a str) -> Result<Self, String> {
+        let operator_len = spec
+            .find(|c: char| !matches!(c, '<' | '>' | '=' | '!' | '~'))
+            .unwrap_or(spec.len());
+        let (operator, version) = spec.split_at(operator_len);
+        if !matches!(operator, "~=" | "==" | "!=" | "<=" | ">=" | "<" | ">" | "===") {
+            return Err(format!("Invalid version specifier: '{spec}'"));
+        }
+        let version = version.trim();
+        if version.is_empty() {
+            return Err(format!("Invalid version specifier: '{spec}'"));
+        }
+        Ok(Self { operator, version })
+    }
+
+    fn contains(&self, candidate: &Pep440Version) -> bool {
+        if self.operator == "===" {
+            return candidate.to_string() == self.version;
+        }
+        if let Some(prefix) = self.version.strip_suffix(".*") {
+            let Ok(prefix) = prefix.parse::<Pep440Version>() else {
+                return false;
+            };
+            let matches = prefix_matches(candidate, &prefix);
+            return match self.operator {
+                "==" => matches,
+                "!=" => !matches,
+                _ => false,
+            };
+        }
+
+        let Ok(spec) = self.version.parse::<Pep440Version>() else {
+            return false;
+        };
+        // Without a local label in the specifier, the candidate's local label is ignored
+        let public = |v: &Pep440Version| Pep440Version {
+            local: Vec::new(),
+            ..v.clone()
+        };
+        let candidate_public = if spec.local.is_empty() {
+            public(candidate)
+        } else {
+            candidate.clone()
+        };
+
+        match self.operator {
+            "==" => candidate_public == spec,
+            "!=" => candidate_public != spec,
+            "<=" => candidate_public <= spec,
+            ">=" => candidate_public >= spec,
+            // `<V` excludes prereleases of V itself, `>V` excludes post releases of V
+            "<" => {
+                candidate_public < spec
+                    && (spec.is_prerelease() || !candidate.is_prerelease() || !same_release(candidate, &spec))
+            }
+            ">" => {
+                candidate_public > spec
+                    && (spec.post.is_some() || candidate.post.is_none() || !same_release(candidate, &spec))
+            }
+            "~=" => {
+                if spec.release.len() < 2 {
+                    return false;
+                }
+                let mut prefix = spec.clone();
+                prefix.release.pop();
+                candidate_public >= spec && prefix_matches(candidate, &prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the candidate's release starts with the prefix's release (zero-padded), in the same epoch
+fn prefix_matches(candidate: &Pep440Version, prefix: &Pep440Version) -> bool {
+    candidate.epoch == prefix.epoch
+        && prefix
+            .release
+            .iter()
+            .enumerate()
+            .all(|(i, n)| candidate.release.get(i).copied().unwrap_or(0) == *n)
+}
+
+fn same_release(a: &Pep440Version, b: &Pep440Version) -> bool {
+    a.epoch == b.epoch && prefix_matches(a, b) && prefix_matches(b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pep440_specifiers() {
+        assert!(satisfies("2.1", ">=2.0, <3.0").unwrap());
+        assert!(!satisfies("3.0", ">=2.0, <3.0").unwrap());
+        assert!(!satisfies("3.0rc1", "<3.0").unwrap());
+        assert!(satisfies("1.4.5", "~=1.4.2").unwrap());
+        assert!(!satisfies("1.5.0", "~=1.4.2").unwrap());
+        assert!(satisfies("1.2.7.post1", "==1.2.*").unwrap());
+        assert!(!satisfies("1.3.1", "!=1.3.1").unwrap());
+        assert!(satisfies("1.0+local", "==1.0").unwrap());
+        assert!(!satisfies("1.0.post1", ">1.0").unwrap());
+        assert!(satisfies("2.0a1", ">=2.0a1").unwrap());
+    }
+
+    #[test]
+    fn test_semver_ranges() {
+        assert!(satisfies("1.4.0", "^1.2").unwrap());
+        assert!(!satisfies("2.0.0", "^1.2").unwrap());
+        assert!(satisfies("2.0.0", "^1.2 || ^2").unwrap());
+        assert!(satisfies("1.2.9", "~1.2.3").unwrap());
+        assert!(satisfies("2.5.0", ">=2.0, <3.0").unwrap());
+        assert!(satisfies("1.2.5", "1.2").unwrap());
+        assert!(satisfies("1.2.5", "1.x").unwrap());
+        // Prereleases only match comparators on their own major.minor.patch
+        assert!(!satisfies("2.1.0-rc.1", "^2.0").unwrap());
+        assert!(satisfies("2.1.0-rc.1", "^2.1.0-rc.0").unwrap());
+    }
+
+    #[test]
+    fn test_operators_follow_pep440() {
+        // Semver would read `>1.2` as `>=1.3.0`
+        assert!(satisfies("1.2.3", ">1.2").unwrap());
+        assert!(satisfies("2.1.0-rc.1", ">=1.0.0, <3.0.0").unwrap());
+        assert!(!satisfies("3.0.0-rc.1", ">=1.0.0, <3.0.0").unwrap());
+        assert!(satisfies("1.2.3", "==1.2.*").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_range() {
+        assert!(satisfies("1.0", "=>1.0").is_err());
+        assert!(satisfies("1.0", "").is_err());
+        assert!(satisfies("not-a-version", ">=1.0").is_err());
+        assert!(satisfies("1.2.3a1", "^1.2").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use clap::ValueEnum;
+
+/// A single component of a version (`bver current --part`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Part {
+    Major,
+    Minor,
+    Patch,
+    /// The pre-release suffix, e.g. `a1` or `beta.2` (empty for releases)
+    Pre,
+}
+
+/// The numeric components and pre-release suffix of a version string
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Components {
+    pub major: String,
+    pub minor: String,
+    pub patch: String,
+    /// Everything after the release segment (without the separator), e.g. `a1` or `beta.2`
+    pub pre: String,
+}
+
+impl Components {
+    pub fn parse(version: &str) -> Self {
+        // Drop a PEP 440 epoch and any local/build metadata
+        let version = version.split_once('!').map_or(version, |(_, v)| v);
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+
+        let release_len = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+        let release = version[..release_len].trim_end_matches('.');
+        let pre = version[release.len()..].trim_start_matches(['.', '-', '_']);
+
+        let mut parts = release.split('.').filter(|p| !p.is_empty());
+        let mut next = || parts.next().unwrap_or("0").to_string();
+        Self {
+            major: next(),
+            minor: next(),
+            patch: next(),
+            pre: pre.to_string(),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> &str {
+        match part {
+            Part::Major => &self.major,
+            Part::Minor => &self.minor,
+            Part::Patch => &self.patch,
+            Part::Pre => &self.pre,
+        }
+    }
+}
+
+/// Render a template, substituting:
+/// - `{current-version}` / `{new-version}`
+/// - `{current-version-underscored}` / `{new-version-underscored}` (`1_2_3`)
+/// - `{current-version-nodots}` / `{new-version-nodots}` (`123`)
+/// - `{major}`, `{minor}`, `{patch}`, `{pre}` (or `{new-major}`, ...) of the new version
+/// - `{current-major}`, `{current-minor}`, `{current-patch}`, `{current-pre}` of the current version
+/// - `{next-major}`, `{next-minor}`, `{next-patch}`: the new version's components plus one (for range upper bounds)
+pub fn render(template: &str, current_version: &str, new_version: &str) -> String {
+    if !template.contains('{') {
+        return template.to_string();
+    }
+
+    let mut rendered = template.to_string();
+    for (prefix, version) in [("current", current_version), ("new", new_version)] {
+        rendered = rendered
+            .replace(&format!("{{{prefix}-version-underscored}}"), &version.replace(['.', '-'], "_"))
+            .replace(&format!("{{{prefix}-version-nodots}}"), &version.replace('.', ""))
+            .replace(&format!("{{{prefix}-version}}"), version);
+    }
+
+    for (prefix, version) in [("current-", current_version), ("new-", new_version), ("", new_version)] {
+        let parts = Components::parse(version);
+        rendered = rendered
+            .replace(&format!("{{{prefix}major}}"), &parts.major)
+            .replace(&format!("{{{prefix}minor}}"), &parts.minor)
+            .replace(&format!("{{{prefix}patch}}"), &parts.patch)
+            .replace(&format!("{{{prefix}pre}}"), &parts.pre);
+    }
+
+    let parts = Components::parse(new_version);
+    let next = |part: &str| part.parse::<u64>().map_or_else(|_| part.to_string(), |n| (n + 1).to_string());
+    rendered
+        .replace("{next-major}", &next(&parts.major))
+        .replace("{next-minor}", &next(&parts.minor))
+        .replace("{next-patch}", &next(&parts.patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components() {
+        let parts = Components::parse("1.3.0a1");
+        assert_eq!((parts.major.as_str(), parts.minor.as_str(), parts.patch.as_str()), ("1", "3", "0"));
+        assert_eq!(parts.pre, "a1");
+        assert_eq!(Components::parse("2.0.0-beta.2+build").pre, "beta.2");
+        assert_eq!(Components::parse("1!4.5").patch, "0");
+        assert_eq!(Components::parse("v2.5.1").get(Part::Minor), "5");
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render("v{new-version}", "1.2.3", "1.3.0"), "v1.3.0");
+        assert_eq!(render("{new-version-underscored}", "1.2.3", "1.3.0"), "1_3_0");
+        assert_eq!(render("libfoo.so.{major}{minor}", "1.2.3", "1.3.0"), "libfoo.so.13");
+        assert_eq!(render("{current-version-nodots}->{new-version-nodots}", "1.2.3", "1.3.0"), "123->130");
+        assert_eq!(render("{current-major}.{minor}-{pre}", "1.2.3", "2.0.0rc1"), "1.0-rc1");
+        assert_eq!(render("{current-minor}->{new-minor}", "1.2.3", "1.3.0"), "2->3");
+        assert_eq!(render(">={major}.{minor},<{major}.{next-minor}", "1.2.3", "1.3.0"), ">=1.3,<1.4");
+    }
+}
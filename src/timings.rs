@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Wall-clock time spent in each phase of a run, reported with `--timings`.
+/// A disabled `Timings` (the default) still runs every phase, it just doesn't record it.
+#[derive(Debug, Default)]
+pub struct Timings {
+    enabled: bool,
+    phases: RefCell<Vec<(String, Duration)>>,
+}
+
+impl Timings {
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record a phase that was timed elsewhere
+    pub fn add(&self, phase: impl Into<String>, duration: Duration) {
+        if self.enabled {
+            self.phases.borrow_mut().push((phase.into(), duration));
+        }
+    }
+
+    /// Run `f`, recording how long it took
+    pub fn time<T>(&self, phase: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.add(phase, started.elapsed());
+        result
+    }
+
+    /// A table of phases in the order they ran, with the total
+    pub fn report(&self) -> String {
+        let phases = self.phases.borrow();
+        let width = phases.iter().map(|(phase, _)| phase.len()).max().unwrap_or(0).max("total".len());
+        let line = |phase: &str, duration: Duration| {
+            format!("  {phase:<width$}  {:>9.1}ms\n", duration.as_secs_f64() * 1000.0)
+        };
+
+        let mut report = String::from("Timings:\n");
+        for (phase, duration) in phases.iter() {
+            report.push_str(&line(phase, *duration));
+        }
+        report.push_str(&line("total", phases.iter().map(|(_, d)| *d).sum()));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let timings = Timings::enabled();
+        timings.add("load config", Duration::from_millis(2));
+        assert_eq!(timings.time("scan a.txt", || 42), 42);
+        let report = timings.report();
+        assert!(report.starts_with("Timings:\n  load config   "), "{report}");
+        assert!(report.contains("scan a.txt"));
+        assert!(report.lines().last().unwrap().trim_start().starts_with("total"));
+
+        let disabled = Timings::default();
+        disabled.time("scan", || ());
+        assert!(disabled.phases.borrow().is_empty());
+    }
+}
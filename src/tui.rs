@@ -1,16 +1,28 @@
-use std::io::{self, stdout};
-use std::path::PathBuf;
+use std::io::{self, stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::{Duration, Instant};
 
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+use crate::git::{toggle_git_step, GitStep};
+use crate::interrupt;
+use crate::kind::VersionKind;
+use crate::vfs::{OsFs, Vfs};
+
 /// A proposed change to a file
 #[derive(Clone)]
 pub struct ProposedChange {
@@ -23,69 +35,605 @@ pub struct ProposedChange {
     pub selected: bool,
 }
 
-/// Run the TUI to select which changes to apply
-/// Returns the indices of selected changes
-pub fn select_changes(changes: &mut [ProposedChange]) -> io::Result<bool> {
+/// What the bump is about, shown in the help overlay
+pub struct BumpSummary<'a> {
+    pub current_version: &'a str,
+    pub new_version: &'a str,
+    pub kind: VersionKind,
+    /// Whether applying needs the new version typed in first (`protect-major`)
+    pub confirm_major: bool,
+}
+
+/// Scans quicker than this never show progress
+const PROGRESS_DELAY: Duration = Duration::from_millis(200);
+
+/// A status line on stderr counting scanned files and matches, so a long scan doesn't look frozen
+pub struct ScanProgress {
+    enabled: bool,
+    started: Instant,
+    last_draw: Option<Instant>,
+    files: usize,
+    matches: usize,
+}
+
+impl ScanProgress {
+    /// Progress that is only drawn if `enabled` (e.g. when stderr is a terminal)
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            started: Instant::now(),
+            last_draw: None,
+            files: 0,
+            matches: 0,
+        }
+    }
+
+    /// Count a scanned file and its matches, redrawing at most every 50ms
+    pub fn scanned(&mut self, matches: usize) {
+        self.files += 1;
+        self.matches += matches;
+        if !self.enabled || self.started.elapsed() < PROGRESS_DELAY {
+            return;
+        }
+        if self.last_draw.is_none_or(|last| last.elapsed() >= Duration::from_millis(50)) {
+            self.last_draw = Some(Instant::now());
+            let mut stderr = io::stderr();
+            let _ = write!(stderr, "\r\x1b[2KScanning: {} file(s), {} match(es)", self.files, self.matches);
+            let _ = stderr.flush();
+        }
+    }
+
+    /// Clear the status line, if it was drawn
+    pub fn finish(&mut self) {
+        if self.last_draw.take().is_some() {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+/// Every keybinding, as shown in the help overlay
+const KEYS: &[(&str, &str)] = &[
+    ("↑↓ / jk", "navigate files and changes"),
+    ("←→ / hl", "collapse / expand a file"),
+    ("space", "toggle a change, or all changes of a file"),
+    ("t", "toggle all changes of the current file"),
+    ("a / n", "select / deselect all (visible) changes"),
+    ("i", "invert the selection of all (visible) changes"),
+    ("/", "search changes by path or line content (esc clears)"),
+    ("f", "only show changes not reviewed yet"),
+    ("PgUp / PgDn", "scroll the preview"),
+    ("+ / -", "grow / shrink the preview"),
+    ("e", "edit the file at the current change in $VISUAL / $EDITOR, then rescan it"),
+    ("1-9", "toggle a git action for this run"),
+    ("mouse", "click to select, click a checkbox to toggle, wheel to scroll"),
+    ("?", "show / hide this help"),
+    ("enter", "apply the selected changes"),
+    ("q / esc", "cancel"),
+    ("ctrl-c", "abort (exit code 130)"),
+];
+
+/// Files start expanded when there are at most this many changes in total
+const EXPAND_LIMIT: usize = 20;
+
+/// The changes of one file: a contiguous run of `changes` (which are sorted by path)
+struct FileGroup {
+    path: PathBuf,
+    changes: std::ops::Range<usize>,
+    expanded: bool,
+}
+
+/// A row of the changes tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    File(usize),
+    Change(usize),
+}
+
+/// The changes grouped by file, with the cursor position and the active filters
+struct ChangeTree {
+    changes: Vec<ProposedChange>,
+    groups: Vec<FileGroup>,
+    cursor: usize,
+    /// Changes that have been shown in the preview or toggled
+    reviewed: Vec<bool>,
+    /// Only show changes whose path or lines contain this (case-insensitive)
+    query: String,
+    /// Only show changes that weren't reviewed when the filter was turned on
+    unreviewed_only: Option<Vec<bool>>,
+}
+
+impl ChangeTree {
+    fn new(changes: Vec<ProposedChange>) -> Self {
+        let expanded = changes.len() <= EXPAND_LIMIT;
+        let mut groups: Vec<FileGroup> = Vec::new();
+        for (idx, change) in changes.iter().enumerate() {
+            match groups.last_mut() {
+                Some(group) if group.path == change.path => group.changes.end = idx + 1,
+                _ => groups.push(FileGroup {
+                    path: change.path.clone(),
+                    changes: idx..idx + 1,
+                    expanded,
+                }),
+            }
+        }
+        Self {
+            reviewed: vec![false; changes.len()],
+            changes,
+            groups,
+            cursor: 0,
+            query: String::new(),
+            unreviewed_only: None,
+        }
+    }
+
+    /// Whether a change passes the search and unreviewed filters
+    fn is_visible(&self, idx: usize) -> bool {
+        if self.unreviewed_only.as_ref().is_some_and(|reviewed| reviewed[idx]) {
+            return false;
+        }
+        if self.query.is_empty() {
+            return true;
+        }
+        let query = self.query.to_lowercase();
+        let change = &self.changes[idx];
+        [change.path.to_string_lossy().as_ref(), &change.old_line, &change.new_line]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    /// The visible changes of a file
+    fn visible_changes(&self, group_idx: usize) -> Vec<usize> {
+        self.groups[group_idx].changes.clone().filter(|&idx| self.is_visible(idx)).collect()
+    }
+
+    /// The visible rows: every file with visible changes, followed by them if it's expanded
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (group_idx, group) in self.groups.iter().enumerate() {
+            let visible = self.visible_changes(group_idx);
+            if visible.is_empty() {
+                continue;
+            }
+            rows.push(Row::File(group_idx));
+            if group.expanded {
+                rows.extend(visible.into_iter().map(Row::Change));
+            }
+        }
+        rows
+    }
+
+    fn current(&self) -> Option<Row> {
+        self.rows().get(self.cursor).copied()
+    }
+
+    /// The group a row belongs to
+    fn group_of(&self, row: Row) -> usize {
+        match row {
+            Row::File(group_idx) => group_idx,
+            Row::Change(idx) => self.groups.iter().position(|g| g.changes.contains(&idx)).unwrap_or(0),
+        }
+    }
+
+    fn move_by(&mut self, up: bool) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        self.cursor = match (up, self.cursor) {
+            (true, 0) => len - 1,
+            (true, i) => i - 1,
+            (false, i) if i + 1 >= len => 0,
+            (false, i) => i + 1,
+        };
+    }
+
+    /// Mark the change under the cursor as reviewed
+    fn mark_reviewed(&mut self) {
+        if let Some(Row::Change(idx)) = self.current() {
+            self.reviewed[idx] = true;
+        }
+    }
+
+    /// Toggle the change under the cursor, or the visible changes of the file under the cursor
+    /// (selecting them all unless they already are)
+    fn toggle(&mut self) {
+        match self.current() {
+            Some(Row::Change(idx)) => {
+                self.changes[idx].selected = !self.changes[idx].selected;
+                self.reviewed[idx] = true;
+            }
+            Some(Row::File(group_idx)) => self.toggle_file(group_idx),
+            None => {}
+        }
+    }
+
+    /// Toggle the visible changes of the file under the cursor, also from one of its changes
+    fn toggle_current_file(&mut self) {
+        if let Some(row) = self.current() {
+            self.toggle_file(self.group_of(row));
+        }
+    }
+
+    /// Select the visible changes of a file, or deselect them if they all already are
+    fn toggle_file(&mut self, group_idx: usize) {
+        let visible = self.visible_changes(group_idx);
+        let select = !visible.iter().all(|&idx| self.changes[idx].selected);
+        self.set_selected(&visible, select);
+    }
+
+    /// Select or deselect every visible change
+    fn set_all(&mut self, selected: bool) {
+        let visible: Vec<usize> = (0..self.changes.len()).filter(|&idx| self.is_visible(idx)).collect();
+        self.set_selected(&visible, selected);
+    }
+
+    /// Flip the selection of every visible change
+    fn invert(&mut self) {
+        for idx in 0..self.changes.len() {
+            if self.is_visible(idx) {
+                self.changes[idx].selected = !self.changes[idx].selected;
+                self.reviewed[idx] = true;
+            }
+        }
+    }
+
+    fn set_selected(&mut self, indices: &[usize], selected: bool) {
+        for &idx in indices {
+            self.changes[idx].selected = selected;
+            self.reviewed[idx] = true;
+        }
+    }
+
+    fn expand(&mut self) {
+        if let Some(row) = self.current() {
+            let group_idx = self.group_of(row);
+            self.groups[group_idx].expanded = true;
+        }
+    }
+
+    /// Collapse the file under the cursor, moving the cursor to the file's row
+    fn collapse(&mut self) {
+        let Some(row) = self.current() else {
+            return;
+        };
+        let group_idx = self.group_of(row);
+        self.groups[group_idx].expanded = false;
+        self.cursor = self.rows().iter().position(|r| *r == Row::File(group_idx)).unwrap_or(0);
+    }
+
+    /// Call after changing a filter: expand the files with matches so they're visible,
+    /// and keep the cursor in range
+    fn apply_filter(&mut self) {
+        if !self.query.is_empty() || self.unreviewed_only.is_some() {
+            for group in &mut self.groups {
+                group.expanded = true;
+            }
+        }
+        self.cursor = self.cursor.min(self.rows().len().saturating_sub(1));
+    }
+
+    /// The file and (1-based) line of the change under the cursor, or of the first change
+    /// of the file under it
+    fn current_location(&self) -> Option<(PathBuf, usize)> {
+        let idx = match self.current()? {
+            Row::Change(idx) => idx,
+            Row::File(group_idx) => self.groups[group_idx].changes.start,
+        };
+        let change = &self.changes[idx];
+        Some((change.path.clone(), change.line_idx + 1))
+    }
+
+    /// Swap the changes of `path` for a fresh scan of it, keeping everything else's review state.
+    /// The file stays expanded so its new changes are visible.
+    fn replace_file(&mut self, path: &Path, changes: Vec<ProposedChange>) {
+        let expanded: Vec<PathBuf> = self.groups.iter().filter(|g| g.expanded).map(|g| g.path.clone()).collect();
+        let hidden = self.unreviewed_only.take();
+        let mut entries: Vec<(ProposedChange, (bool, bool))> = std::mem::take(&mut self.changes)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, change)| change.path != path)
+            .map(|(idx, change)| {
+                let state = (self.reviewed[idx], hidden.as_ref().is_some_and(|hidden| hidden[idx]));
+                (change, state)
+            })
+            .collect();
+        entries.extend(changes.into_iter().map(|change| (change, (false, false))));
+        entries.sort_by(|(a, _), (b, _)| (&a.path, a.line_idx).cmp(&(&b.path, b.line_idx)));
+        let (changes, states): (Vec<ProposedChange>, Vec<(bool, bool)>) = entries.into_iter().unzip();
+
+        let query = std::mem::take(&mut self.query);
+        let cursor = self.cursor;
+        *self = ChangeTree::new(changes);
+        for group in &mut self.groups {
+            group.expanded = group.path == path || expanded.contains(&group.path);
+        }
+        self.reviewed = states.iter().map(|(reviewed, _)| *reviewed).collect();
+        self.unreviewed_only = hidden.map(|_| states.iter().map(|(_, hidden)| *hidden).collect());
+        self.query = query;
+        self.cursor = cursor.min(self.rows().len().saturating_sub(1));
+    }
+
+    /// Turn the unreviewed-only filter on (hiding everything reviewed so far) or off
+    fn toggle_unreviewed_only(&mut self) {
+        self.unreviewed_only = match self.unreviewed_only {
+            Some(_) => None,
+            None => Some(self.reviewed.clone()),
+        };
+        self.apply_filter();
+    }
+}
+
+/// Layout and input state of the TUI that isn't about the changes themselves
+struct View {
+    /// Whether keys go to the search box
+    searching: bool,
+    /// Height of the preview pane; the changes list gets the rest
+    preview_height: u16,
+    /// Lines scrolled off the top of the preview
+    preview_scroll: u16,
+    /// Whether the help overlay is shown
+    show_help: bool,
+    /// Shown instead of the help line until the next key
+    message: Option<String>,
+    /// What has been typed to confirm a protected major bump, while keys go to the confirmation
+    confirming: Option<String>,
+    /// Where the changes list and the preview were last drawn, to place mouse events
+    list_area: Rect,
+    preview_area: Rect,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            searching: false,
+            preview_height: 10,
+            preview_scroll: 0,
+            show_help: false,
+            message: None,
+            confirming: None,
+            list_area: Rect::default(),
+            preview_area: Rect::default(),
+        }
+    }
+}
+
+/// Scans a file again for changes, after it was edited
+pub type RescanFn<'a> = dyn FnMut(&Path) -> Result<Vec<ProposedChange>, String> + 'a;
+
+/// Run the TUI to select which changes to apply, and which git actions to run.
+/// Returns whether the selected changes should be applied.
+pub fn select_changes(
+    changes: &mut Vec<ProposedChange>,
+    git_steps: &mut [GitStep],
+    summary: &BumpSummary,
+    rescan: &mut RescanFn,
+) -> io::Result<bool> {
     if changes.is_empty() {
         return Ok(true);
     }
 
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+    let screen = ScreenGuard::enter()?;
 
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let mut state = ListState::default();
-    state.select(Some(0));
+    let mut tree = ChangeTree::new(std::mem::take(changes));
 
-    let result = run_tui(&mut terminal, changes, &mut state);
+    let result = run_tui(&mut terminal, &mut tree, git_steps, summary, rescan);
 
-    disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
+    screen.leave()?;
+    *changes = tree.changes;
 
     result
 }
 
+/// Whether the terminal is in raw mode on the alternate screen, and needs restoring
+static IN_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Restores the terminal when dropped, so an error or a panic in the TUI never leaves the
+/// user's shell in raw mode on the alternate screen
+struct ScreenGuard;
+
+impl ScreenGuard {
+    fn enter() -> io::Result<Self> {
+        install_panic_hook();
+        // Created first, so a half-entered screen is restored too
+        let guard = ScreenGuard;
+        enter_screen()?;
+        Ok(guard)
+    }
+
+    /// Restore the terminal, reporting errors the drop would ignore
+    fn leave(self) -> io::Result<()> {
+        leave_screen()
+    }
+}
+
+impl Drop for ScreenGuard {
+    fn drop(&mut self) {
+        let _ = leave_screen();
+    }
+}
+
+/// Restore the terminal before a panic message is printed, so it isn't lost on the alternate screen
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = leave_screen();
+            previous(info);
+        }));
+    });
+}
+
+fn enter_screen() -> io::Result<()> {
+    IN_SCREEN.store(true, Ordering::SeqCst);
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
+    Ok(())
+}
+
+/// Leave the screen if it was entered; does nothing otherwise
+fn leave_screen() -> io::Result<()> {
+    if !IN_SCREEN.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    // Try every step, so one failing doesn't leave the terminal in raw mode
+    let raw = disable_raw_mode();
+    let mouse = stdout().execute(DisableMouseCapture).map(|_| ());
+    let screen = stdout().execute(LeaveAlternateScreen).map(|_| ());
+    raw.and(mouse).and(screen)
+}
+
+/// Open `path` at `line` in `$VISUAL` or `$EDITOR` (default `vi`), waiting for it to exit
+fn open_in_editor(path: &Path, line: usize) -> Result<(), String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(format!("+{line}"))
+        .arg(path)
+        .status()
+        .map_err(|e| format!("Failed to run {editor}: {e}"))?;
+    if !status.success() {
+        return Err(format!("{editor} exited with {status}"));
+    }
+    Ok(())
+}
+
 fn run_tui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    changes: &mut [ProposedChange],
-    state: &mut ListState,
+    tree: &mut ChangeTree,
+    git_steps: &mut [GitStep],
+    summary: &BumpSummary,
+    rescan: &mut RescanFn,
 ) -> io::Result<bool> {
+    let mut state = ListState::default();
+    let mut view = View::default();
     loop {
-        terminal.draw(|frame| draw(frame, changes, state))?;
+        tree.mark_reviewed();
+        if state.selected() != tree.current().map(|_| tree.cursor) {
+            // A different change is previewed, show it from the top
+            view.preview_scroll = 0;
+        }
+        state.select(tree.current().map(|_| tree.cursor));
+        terminal.draw(|frame| {
+            draw(frame, tree, git_steps, &mut state, &mut view, summary);
+            if view.show_help {
+                draw_help(frame, git_steps, summary);
+            }
+        })?;
 
-        if let Event::Key(key) = event::read()? {
+        // Poll, so a SIGINT sent from elsewhere also ends the TUI
+        let event = loop {
+            if interrupt::interrupted() {
+                return Ok(false);
+            }
+            if event::poll(Duration::from_millis(100))? {
+                break event::read()?;
+            }
+        };
+        if let Event::Mouse(mouse) = event {
+            if !view.show_help {
+                handle_mouse(tree, &mut view, state.offset(), mouse);
+            }
+            continue;
+        }
+        if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 continue;
             }
+            // Raw mode turns Ctrl-C into a key instead of a signal
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                interrupt::interrupt();
+                return Ok(false);
+            }
 
-            match key.code {
-                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
-                KeyCode::Enter => return Ok(true),
-                KeyCode::Up | KeyCode::Char('k') => {
-                    let i = state.selected().unwrap_or(0);
-                    let new_i = if i == 0 { changes.len() - 1 } else { i - 1 };
-                    state.select(Some(new_i));
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    let i = state.selected().unwrap_or(0);
-                    let new_i = if i >= changes.len() - 1 { 0 } else { i + 1 };
-                    state.select(Some(new_i));
-                }
-                KeyCode::Char(' ') => {
-                    if let Some(i) = state.selected() {
-                        changes[i].selected = !changes[i].selected;
+            view.message = None;
+            if view.show_help {
+                // Any key closes the help
+                view.show_help = false;
+                continue;
+            }
+
+            if let Some(typed) = &mut view.confirming {
+                match key.code {
+                    KeyCode::Enter if typed.as_str() == summary.new_version => return Ok(true),
+                    KeyCode::Enter => {
+                        view.message = Some(format!(" '{typed}' doesn't match {}, not applying", summary.new_version));
+                        view.confirming = None;
+                    }
+                    KeyCode::Esc => view.confirming = None,
+                    KeyCode::Backspace => {
+                        typed.pop();
                     }
+                    KeyCode::Char(c) => typed.push(c),
+                    _ => {}
                 }
-                KeyCode::Char('a') => {
-                    // Select all
-                    for change in changes.iter_mut() {
-                        change.selected = true;
+                continue;
+            }
+
+            if view.searching {
+                match key.code {
+                    KeyCode::Enter => view.searching = false,
+                    KeyCode::Esc => {
+                        view.searching = false;
+                        tree.query.clear();
+                    }
+                    KeyCode::Backspace => {
+                        tree.query.pop();
                     }
+                    KeyCode::Char(c) => tree.query.push(c),
+                    _ => {}
+                }
+                tree.apply_filter();
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Enter if summary.confirm_major => view.confirming = Some(String::new()),
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Up | KeyCode::Char('k') => tree.move_by(true),
+                KeyCode::Down | KeyCode::Char('j') => tree.move_by(false),
+                KeyCode::Right | KeyCode::Char('l') => tree.expand(),
+                KeyCode::Left | KeyCode::Char('h') => tree.collapse(),
+                KeyCode::Char(' ') => tree.toggle(),
+                KeyCode::Char('a') => tree.set_all(true),
+                KeyCode::Char('n') => tree.set_all(false),
+                KeyCode::Char('t') => tree.toggle_current_file(),
+                KeyCode::Char('i') => tree.invert(),
+                KeyCode::Char('/') => view.searching = true,
+                KeyCode::Char('?') => view.show_help = true,
+                KeyCode::Char('e') => {
+                    let Some((path, line)) = tree.current_location() else {
+                        continue;
+                    };
+                    leave_screen()?;
+                    let edited = open_in_editor(&path, line);
+                    enter_screen()?;
+                    terminal.clear()?;
+                    view.message = Some(match edited.and_then(|()| rescan(&path)) {
+                        Ok(changes) => {
+                            let message = format!(" Rescanned {}: {} change(s)", path.display(), changes.len());
+                            tree.replace_file(&path, changes);
+                            message
+                        }
+                        Err(e) => format!(" Error: {e}"),
+                    });
                 }
-                KeyCode::Char('n') => {
-                    // Deselect all
-                    for change in changes.iter_mut() {
-                        change.selected = false;
+                KeyCode::PageDown => view.preview_scroll = view.preview_scroll.saturating_add(view.preview_height / 2),
+                KeyCode::PageUp => view.preview_scroll = view.preview_scroll.saturating_sub(view.preview_height / 2),
+                KeyCode::Char('+') | KeyCode::Char('=') => view.preview_height = view.preview_height.saturating_add(1),
+                KeyCode::Char('-') => view.preview_height = view.preview_height.saturating_sub(1).max(3),
+                KeyCode::Char('f') => tree.toggle_unreviewed_only(),
+                KeyCode::Char(c @ '1'..='9') => {
+                    let idx = c as usize - '1' as usize;
+                    if idx < git_steps.len() {
+                        toggle_git_step(git_steps, idx);
                     }
                 }
                 _ => {}
@@ -94,60 +642,145 @@ fn run_tui(
     }
 }
 
-fn draw(frame: &mut Frame, changes: &[ProposedChange], state: &mut ListState) {
+/// Click a row to move to it, click its checkbox to toggle it, and scroll the list or preview with the wheel.
+/// `offset` is the first row shown in the list.
+fn handle_mouse(tree: &mut ChangeTree, view: &mut View, offset: usize, mouse: MouseEvent) {
+    let position = Position::new(mouse.column, mouse.row);
+    let in_preview = view.preview_area.contains(position);
+    let rows = tree.rows();
+    match mouse.kind {
+        MouseEventKind::ScrollDown if in_preview => view.preview_scroll = view.preview_scroll.saturating_add(1),
+        MouseEventKind::ScrollUp if in_preview => view.preview_scroll = view.preview_scroll.saturating_sub(1),
+        MouseEventKind::ScrollDown => tree.cursor = (tree.cursor + 1).min(rows.len().saturating_sub(1)),
+        MouseEventKind::ScrollUp => tree.cursor = tree.cursor.saturating_sub(1),
+        MouseEventKind::Down(MouseButton::Left) if view.list_area.contains(position) => {
+            // Inside the border: the highlight symbol, then the (indented, for changes) checkbox
+            let line = (mouse.row - view.list_area.y) as usize;
+            let column = mouse.column.saturating_sub(view.list_area.x + 1) as usize;
+            let Some(idx) = line.checked_sub(1).map(|line| offset + line) else {
+                return;
+            };
+            let Some(row) = rows.get(idx) else {
+                return;
+            };
+            tree.cursor = idx;
+            let checkbox = match row {
+                Row::File(_) => 2..5,
+                Row::Change(_) => 6..9,
+            };
+            if checkbox.contains(&column) {
+                tree.toggle();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn draw(
+    frame: &mut Frame,
+    tree: &ChangeTree,
+    git_steps: &[GitStep],
+    state: &mut ListState,
+    view: &mut View,
+    summary: &BumpSummary,
+) {
+    // The git pane is left out when no git actions are configured
+    let git_height = if git_steps.is_empty() { 0 } else { git_steps.len() as u16 + 2 };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(5),
-            Constraint::Length(10),
+            Constraint::Length(view.preview_height),
+            Constraint::Length(git_height),
             Constraint::Length(1),
         ])
         .split(frame.area());
 
-    // Changes list
-    let cwd = std::env::current_dir().unwrap_or_default();
-    let items: Vec<ListItem> = changes
+    view.list_area = chunks[0];
+    view.preview_area = chunks[1];
+
+    // Changes tree
+    let cwd = OsFs.current_dir().unwrap_or_default();
+    let rows = tree.rows();
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|change| {
-            let checkbox = if change.selected { "[x] " } else { "[ ] " };
-            let rel_path = change.path.strip_prefix(&cwd).unwrap_or(&change.path);
-            let parent = rel_path
-                .parent()
-                .map(|p| p.to_string_lossy())
-                .unwrap_or_default();
-            let filename = rel_path
-                .file_name()
-                .map(|f| f.to_string_lossy())
-                .unwrap_or_default();
-            let line_num = change.line_idx + 1;
-
-            let mut spans = vec![Span::raw(checkbox)];
-            if !parent.is_empty() {
+        .map(|row| match *row {
+            Row::File(group_idx) => {
+                let group = &tree.groups[group_idx];
+                let changes = &tree.changes[group.changes.clone()];
+                let selected = changes.iter().filter(|c| c.selected).count();
+                let checkbox = match selected {
+                    0 => "[ ] ",
+                    n if n == changes.len() => "[x] ",
+                    _ => "[-] ",
+                };
+                let arrow = if group.expanded { "▾ " } else { "▸ " };
+                let rel_path = group.path.strip_prefix(&cwd).unwrap_or(&group.path);
+                let parent = rel_path
+                    .parent()
+                    .map(|p| p.to_string_lossy())
+                    .unwrap_or_default();
+                let filename = rel_path
+                    .file_name()
+                    .map(|f| f.to_string_lossy())
+                    .unwrap_or_default();
+
+                let mut spans = vec![Span::raw(checkbox), Span::raw(arrow)];
+                if !parent.is_empty() {
+                    spans.push(Span::styled(
+                        format!("{}/", parent),
+                        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                    ));
+                }
                 spans.push(Span::styled(
-                    format!("{}/", parent),
-                    Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                    filename.to_string(),
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
                 ));
+                spans.push(Span::styled(
+                    format!(" ({selected}/{} changes)", changes.len()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+            Row::Change(idx) => {
+                let change = &tree.changes[idx];
+                let checkbox = if change.selected { "[x] " } else { "[ ] " };
+                ListItem::new(Line::from(vec![
+                    Span::raw("    "),
+                    Span::raw(checkbox),
+                    Span::raw(format!("{:>5}: ", change.line_idx + 1)),
+                    Span::styled(change.new_line.trim().to_string(), Style::default().fg(Color::DarkGray)),
+                ]))
             }
-            spans.push(Span::styled(
-                filename.to_string(),
-                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
-            ));
-            spans.push(Span::raw(format!(":{}", line_num)));
-
-            ListItem::new(Line::from(spans))
         })
         .collect();
 
+    let mut title = format!(
+        " Changes: {}/{} selected in {} files ",
+        tree.changes.iter().filter(|c| c.selected).count(),
+        tree.changes.len(),
+        tree.groups.len()
+    );
+    if !tree.query.is_empty() {
+        title.push_str(&format!("│ matching '{}' ", tree.query));
+    }
+    if tree.unreviewed_only.is_some() {
+        title.push_str("│ unreviewed only ");
+    }
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(" Changes (space: toggle, a: all, n: none) "))
+        .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol("> ");
 
     frame.render_stateful_widget(list, chunks[0], state);
 
-    // Preview pane
-    if let Some(i) = state.selected() {
-        let change = &changes[i];
+    // Preview pane: the change under the cursor, or the first change of the file under it
+    let preview_change = match tree.current() {
+        Some(Row::Change(idx)) => Some(&tree.changes[idx]),
+        Some(Row::File(group_idx)) => tree.changes.get(tree.groups[group_idx].changes.start),
+        None => None,
+    };
+    if let Some(change) = preview_change {
         let mut preview_lines: Vec<Line> = Vec::new();
 
         let start_line = change.line_idx.saturating_sub(change.context_before.len());
@@ -163,15 +796,20 @@ fn draw(frame: &mut Frame, changes: &[ProposedChange], state: &mut ListState) {
 
         let line_num = change.line_idx + 1;
         if change.selected {
-            // Show diff: old line (red) and new line (green)
-            preview_lines.push(Line::from(vec![
-                Span::styled(format!("- {:4} │ ", line_num), Style::default().fg(Color::Red)),
-                Span::styled(&change.old_line, Style::default().fg(Color::Red)),
-            ]));
-            preview_lines.push(Line::from(vec![
-                Span::styled(format!("+ {:4} │ ", line_num), Style::default().fg(Color::Green)),
-                Span::styled(&change.new_line, Style::default().fg(Color::Green)),
-            ]));
+            // Show diff: old line (red) and new line (green), highlighting the part that changed
+            let (old_span, new_span) = changed_spans(&change.old_line, &change.new_line);
+            preview_lines.push(highlighted_line(
+                format!("- {:4} │ ", line_num),
+                &change.old_line,
+                old_span,
+                Color::Red,
+            ));
+            preview_lines.push(highlighted_line(
+                format!("+ {:4} │ ", line_num),
+                &change.new_line,
+                new_span,
+                Color::Green,
+            ));
         } else {
             // No change: show original line normally
             preview_lines.push(Line::from(vec![
@@ -189,14 +827,255 @@ fn draw(frame: &mut Frame, changes: &[ProposedChange], state: &mut ListState) {
             ]));
         }
 
+        // Don't scroll past the last (wrapped) line
+        let width = chunks[1].width.saturating_sub(2).max(1) as usize;
+        let height: usize = preview_lines.iter().map(|line| line.width().max(1).div_ceil(width)).sum();
+        view.preview_scroll = view.preview_scroll.min(height.saturating_sub(1) as u16);
         let preview = Paragraph::new(preview_lines)
-            .block(Block::default().borders(Borders::ALL).title(" Preview "))
-            .wrap(Wrap { trim: false });
+            .block(Block::default().borders(Borders::ALL).title(" Preview (PgUp/PgDn: scroll, +/-: resize) "))
+            .wrap(Wrap { trim: false })
+            .scroll((view.preview_scroll, 0));
 
         frame.render_widget(preview, chunks[1]);
     }
 
-    // Help line
-    let help = Paragraph::new(" ↑↓/jk: navigate │ space: toggle │ a: all │ n: none │ enter: apply │ q/esc: cancel ");
-    frame.render_widget(help, chunks[2]);
+    // Git actions for this run
+    if !git_steps.is_empty() {
+        let lines: Vec<Line> = git_steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let checkbox = if step.enabled { "[x]" } else { "[ ]" };
+                let style = if step.enabled { Style::default() } else { Style::default().fg(Color::DarkGray) };
+                Line::from(vec![
+                    Span::raw(format!(" {} {checkbox} ", i + 1)),
+                    Span::styled(&step.description, style),
+                ])
+            })
+            .collect();
+        let git = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Git (1-9: toggle) "));
+        frame.render_widget(git, chunks[2]);
+    }
+
+    // Help line, or the search box while typing a query
+    let help = if let Some(typed) = &view.confirming {
+        Paragraph::new(format!(
+            " Major bump: type {} to confirm: {typed}█  (esc: back)",
+            summary.new_version
+        ))
+        .style(Style::default().fg(Color::Yellow))
+    } else if view.searching {
+        Paragraph::new(format!(" /{}█  (enter: done │ esc: clear)", tree.query))
+    } else if let Some(message) = &view.message {
+        Paragraph::new(message.as_str())
+    } else {
+        Paragraph::new(" ↑↓/jk: navigate │ space: toggle │ /: search │ ?: help │ enter: apply │ q/esc: cancel ")
+    };
+    frame.render_widget(help, chunks[3]);
+}
+
+/// A full-screen overlay with every keybinding and what the bump will do
+fn draw_help(frame: &mut Frame, git_steps: &[GitStep], summary: &BumpSummary) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(Span::styled("Bump", bold)),
+        Line::from(format!("  {} -> {} (kind: {})", summary.current_version, summary.new_version, summary.kind.name())),
+        Line::from(""),
+        Line::from(Span::styled("Git actions", bold)),
+    ];
+    if git_steps.is_empty() {
+        lines.push(Line::from("  none"));
+    }
+    for step in git_steps {
+        let state = if step.enabled { "on " } else { "off" };
+        lines.push(Line::from(format!("  [{state}] {}", step.description)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Keys", bold)));
+    let width = KEYS.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+    for (key, action) in KEYS {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {key:<width$}  "), Style::default().fg(Color::Cyan)),
+            Span::raw(*action),
+        ]));
+    }
+
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+    let help = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(" Help (any key to close) "))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(help, area);
+}
+
+/// The byte ranges of `old` and `new` that differ, between their common prefix and suffix
+fn changed_spans(old: &str, new: &str) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    let prefix: usize = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    (prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
+
+/// A diff line in `color`, with the `changed` part on a background of that color
+fn highlighted_line(gutter: String, line: &str, changed: std::ops::Range<usize>, color: Color) -> Line<'_> {
+    Line::from(vec![
+        Span::styled(gutter, Style::default().fg(color)),
+        Span::styled(&line[..changed.start], Style::default().fg(color)),
+        Span::styled(&line[changed.clone()], Style::default().fg(Color::Black).bg(color)),
+        Span::styled(&line[changed.end..], Style::default().fg(color)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &str, line_idx: usize) -> ProposedChange {
+        ProposedChange {
+            path: PathBuf::from(path),
+            line_idx,
+            old_line: "1.2.3".to_string(),
+            new_line: "1.2.4".to_string(),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            selected: true,
+        }
+    }
+
+    #[test]
+    fn test_change_tree() {
+        let changes = vec![change("a.txt", 0), change("a.txt", 4), change("b.txt", 1)];
+        let mut tree = ChangeTree::new(changes);
+        assert_eq!(
+            tree.rows(),
+            vec![Row::File(0), Row::Change(0), Row::Change(1), Row::File(1), Row::Change(2)]
+        );
+
+        // Toggling a file rejects (or accepts) all its changes
+        tree.toggle();
+        assert!(tree.changes[..2].iter().all(|c| !c.selected) && tree.changes[2].selected);
+        tree.move_by(false);
+        tree.toggle();
+        tree.move_by(true);
+        tree.toggle();
+        assert!(tree.changes.iter().all(|c| c.selected));
+
+        // Collapsing from a change moves to its file
+        tree.move_by(false);
+        tree.move_by(false);
+        tree.collapse();
+        assert_eq!(tree.rows(), vec![Row::File(0), Row::File(1), Row::Change(2)]);
+        assert_eq!(tree.current(), Some(Row::File(0)));
+        tree.move_by(true);
+        assert_eq!(tree.current(), Some(Row::Change(2)));
+
+        // From a change, `t` toggles its whole file; inverting flips every change
+        tree.toggle_current_file();
+        tree.invert();
+        let selected: Vec<bool> = tree.changes.iter().map(|c| c.selected).collect();
+        assert_eq!(selected, vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_change_tree_filters() {
+        let mut changes = vec![change("a.txt", 0), change("b.txt", 1), change("b.txt", 2)];
+        changes[2].new_line = "image: app:1.2.4".to_string();
+        let mut tree = ChangeTree::new(changes);
+
+        tree.query = "IMAGE".to_string();
+        tree.apply_filter();
+        assert_eq!(tree.rows(), vec![Row::File(1), Row::Change(2)]);
+        // Bulk actions only touch what's visible
+        tree.set_all(false);
+        assert_eq!(tree.changes.iter().map(|c| c.selected).collect::<Vec<_>>(), vec![true, true, false]);
+
+        tree.query = "b.txt".to_string();
+        tree.apply_filter();
+        assert_eq!(tree.rows(), vec![Row::File(1), Row::Change(1), Row::Change(2)]);
+
+        tree.query.clear();
+        tree.toggle_unreviewed_only();
+        assert_eq!(tree.rows(), vec![Row::File(0), Row::Change(0), Row::File(1), Row::Change(1)]);
+    }
+
+    #[test]
+    fn test_replace_file() {
+        let changes = vec![change("a.txt", 0), change("a.txt", 4), change("b.txt", 1)];
+        let mut tree = ChangeTree::new(changes);
+        tree.move_by(false);
+        tree.mark_reviewed();
+        tree.collapse();
+        tree.move_by(false);
+
+        // b.txt was edited: it now has two changes, and a.txt keeps its state
+        tree.replace_file(Path::new("b.txt"), vec![change("b.txt", 3), change("b.txt", 0)]);
+        let lines: Vec<usize> = tree.changes.iter().map(|c| c.line_idx).collect();
+        assert_eq!(lines, vec![0, 4, 0, 3]);
+        assert_eq!(tree.reviewed, vec![true, false, false, false]);
+        assert_eq!(tree.rows(), vec![Row::File(0), Row::File(1), Row::Change(2), Row::Change(3)]);
+        assert_eq!(tree.current_location(), Some((PathBuf::from("b.txt"), 1)));
+
+        // A file without changes left disappears
+        tree.replace_file(Path::new("b.txt"), Vec::new());
+        assert_eq!((tree.rows(), tree.current()), (vec![Row::File(0)], Some(Row::File(0))));
+    }
+
+    #[test]
+    fn test_changed_spans() {
+        let old = "version = \"1.2.3\"  # ünïcode";
+        let new = "version = \"1.10.0\"  # ünïcode";
+        let (old_span, new_span) = changed_spans(old, new);
+        assert_eq!((&old[old_span], &new[new_span]), ("2.3", "10.0"));
+
+        // The suffix never overlaps the prefix
+        let (old_span, new_span) = changed_spans("1.1", "1.1.1");
+        assert_eq!((old_span, new_span), (3..3, 3..5));
+    }
+
+    #[test]
+    fn test_handle_mouse() {
+        let mut tree = ChangeTree::new(vec![change("a.txt", 0), change("a.txt", 4)]);
+        let mut view = View {
+            list_area: Rect::new(0, 0, 40, 10),
+            preview_area: Rect::new(0, 10, 40, 10),
+            ..View::default()
+        };
+        let click = |column, row| MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+
+        // The third line is the second change: clicking its text selects it, its checkbox toggles it
+        handle_mouse(&mut tree, &mut view, 0, click(20, 3));
+        assert_eq!((tree.current(), tree.changes[1].selected), (Some(Row::Change(1)), true));
+        handle_mouse(&mut tree, &mut view, 0, click(8, 3));
+        assert!(!tree.changes[1].selected);
+
+        let scroll = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            ..click(5, 12)
+        };
+        handle_mouse(&mut tree, &mut view, 0, scroll);
+        assert_eq!(view.preview_scroll, 1);
+    }
+
+    #[test]
+    fn test_screen_guard_without_screen() {
+        // Nothing to restore (and nothing written) unless the screen was entered
+        assert!(leave_screen().is_ok());
+        drop(ScreenGuard);
+        assert!(!IN_SCREEN.load(Ordering::SeqCst));
+    }
 }
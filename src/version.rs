@@ -1,17 +1,13 @@
-use crate::schema::FileKind;
+use crate::kind::VersionKind;
+use crate::pep440::Pep440Version;
 
 /// Validate a version string according to the file kind
-pub fn validate_version(version: &str, kind: FileKind) -> Result<(), String> {
-    match kind {
-        FileKind::Any => Ok(()),
-        FileKind::Simple => validate_simple(version),
-        FileKind::Python => validate_python(version),
-        FileKind::Semver => validate_semver(version),
-    }
+pub fn validate_version(version: &str, kind: VersionKind) -> Result<(), String> {
+    (kind.spec().validate)(version)
 }
 
 /// Validate a simple semver version (N.N.N)
-fn validate_simple(version: &str) -> Result<(), String> {
+pub(crate) fn validate_simple(version: &str) -> Result<(), String> {
     let parts: Vec<&str> = version.split('.').collect();
     if parts.len() != 3 {
         return Err(format!(
@@ -30,181 +26,112 @@ fn validate_simple(version: &str) -> Result<(), String> {
 /// Validate a Python version string (PEP 440)
 /// https://peps.python.org/pep-0440/
 ///
-/// Valid forms:
-/// - N[.N]+                           (e.g., 1.0, 1.0.0, 1.2.3.4)
-/// - N[.N]+{a|b|rc}N                  (e.g., 1.0a1, 1.0b2, 1.0rc1)
-/// - N[.N]+.postN                     (e.g., 1.0.post1)
-/// - N[.N]+.devN                      (e.g., 1.0.dev1)
-/// - N[.N]+{a|b|rc}N.postN            (e.g., 1.0a1.post1)
-/// - N[.N]+{a|b|rc}N.devN             (e.g., 1.0a1.dev1)
-/// - N[.N]+.postN.devN                (e.g., 1.0.post1.dev1)
-/// - N[.N]+{a|b|rc}N.postN.devN       (e.g., 1.0a1.post1.dev1)
-/// - Any of the above with +local     (e.g., 1.0+local.version)
-/// - Any of the above with N! prefix  (e.g., 1!1.0)
-fn validate_python(version: &str) -> Result<(), String> {
-    if version.is_empty() {
-        return Err("Version string cannot be empty".to_string());
-    }
-
-    let version = version.to_lowercase();
-
-    // Handle epoch (e.g., "1!1.0")
-    let version = if let Some(pos) = version.find('!') {
-        let epoch = &version[..pos];
-        if !epoch.chars().all(|c| c.is_ascii_digit()) {
-            return Err(format!("Invalid epoch: {epoch}"));
-        }
-        &version[pos + 1..]
-    } else {
-        version.as_str()
-    };
-
-    // Handle local version (e.g., "1.0+local")
-    let version = if let Some(pos) = version.find('+') {
-        let local = &version[pos + 1..];
-        if !is_valid_local(local) {
-            return Err(format!("Invalid local version: {local}"));
-        }
-        &version[..pos]
-    } else {
-        version
-    };
-
-    // Parse the main version parts
-    parse_main_version(version)
+/// Accepts every spelling PEP 440 normalizes (e.g., 1.0, 1.0a1, 1.0.post1, 1.0.dev1,
+/// 1!1.0+local, 1.0-alpha.1, 1.0.RC1)
+pub(crate) fn validate_python(version: &str) -> Result<(), String> {
+    version.parse::<Pep440Version>().map(|_| ())
 }
 
-fn is_valid_local(local: &str) -> bool {
-    if local.is_empty() {
-        return false;
-    }
-    // Local version: alphanumerics and dots, segments separated by dots
-    local
-        .split('.')
-        .all(|segment| !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric()))
-}
-
-fn parse_main_version(version: &str) -> Result<(), String> {
+/// Validate a semver version string (used by npm, Cargo, etc.)
+/// https://semver.org/
+///
+/// Format: major.minor.patch[-prerelease][+build]
+/// - Prerelease: -alpha.1, -beta.2, -rc.1, etc.
+/// - Build metadata: +build.123 (ignored for precedence)
+///
+/// Note: post and dev releases are NOT supported in semver
+pub(crate) fn validate_semver(version: &str) -> Result<semver::Version, String> {
     if version.is_empty() {
         return Err("Version string cannot be empty".to_string());
     }
-
-    // Try to find pre-release marker (a, b, rc, alpha, beta, preview, c)
-    let (release_part, remainder) = split_at_prerelease(version);
-
-    // Validate release part (N.N.N...)
-    if !is_valid_release(release_part) {
-        return Err(format!("Invalid release version: {release_part}"));
-    }
-
-    if remainder.is_empty() {
-        return Ok(());
-    }
-
-    // Parse pre-release, post-release, and dev markers
-    parse_suffixes(remainder)
+    semver::Version::parse(version).map_err(|e| format!("Invalid semver '{version}': {e}"))
 }
 
-fn split_at_prerelease(version: &str) -> (&str, &str) {
-    // Find first occurrence of pre-release markers
-    let markers = ["alpha", "beta", "preview", "rc", "a", "b", "c"];
-
-    let mut earliest_pos = None;
-
-    for marker in markers {
-        if let Some(pos) = version.find(marker) {
-            // Make sure it's not part of a segment (e.g., "1.0abc" should not match)
-            let before = &version[..pos];
-            if before.is_empty() || before.ends_with('.') || before.chars().last().unwrap().is_ascii_digit() {
-                match earliest_pos {
-                    None => earliest_pos = Some(pos),
-                    Some(current) if pos < current => earliest_pos = Some(pos),
-                    _ => {}
-                }
-            }
+/// Validate a version Cargo can publish: strict semver without build metadata.
+/// Prerelease numbers must be their own identifier (`alpha.10`, not `alpha10`),
+/// since alphanumeric identifiers compare as text and `alpha10` would sort before `alpha9`.
+pub(crate) fn validate_cargo(version: &str) -> Result<(), String> {
+    let parsed = validate_semver(version)?;
+    if !parsed.build.is_empty() {
+        return Err(format!("Build metadata is not allowed in Cargo versions: +{}", parsed.build));
+    }
+
+    for identifier in parsed.pre.split('.').filter(|id| !id.is_empty()) {
+        let numeric = identifier.chars().all(|c| c.is_ascii_digit());
+        if !numeric && identifier.ends_with(|c: char| c.is_ascii_digit()) {
+            let label = identifier.trim_end_matches(|c: char| c.is_ascii_digit());
+            let number = &identifier[label.len()..];
+            return Err(format!(
+                "Invalid prerelease identifier: {identifier}. Use '{label}.{number}' so prereleases order numerically"
+            ));
         }
     }
+    Ok(())
+}
 
-    // Also check for .post and .dev at the start of suffix
-    if let Some(pos) = version.find(".post") {
-        match earliest_pos {
-            None => earliest_pos = Some(pos),
-            Some(current) if pos < current => earliest_pos = Some(pos),
-            _ => {}
-        }
+/// Validate a .NET assembly version (major.minor.build.revision)
+/// https://learn.microsoft.com/en-us/dotnet/api/system.version
+///
+/// Each component must be a number no larger than 65534.
+pub(crate) fn validate_dotnet(version: &str) -> Result<(), String> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() != 4 {
+        return Err(format!(
+            "Invalid .NET version: {version}. Expected format: major.minor.build.revision"
+        ));
     }
-    if let Some(pos) = version.find(".dev") {
-        match earliest_pos {
-            None => earliest_pos = Some(pos),
-            Some(current) if pos < current => earliest_pos = Some(pos),
-            _ => {}
+    for (i, part) in parts.iter().enumerate() {
+        let name = ["major", "minor", "build", "revision"][i];
+        match part.parse::<u32>() {
+            Ok(n) if n <= 65534 => {}
+            _ => return Err(format!("Invalid {name} version component: {part}")),
         }
     }
-
-    match earliest_pos {
-        Some(pos) => (&version[..pos], &version[pos..]),
-        None => (version, ""),
-    }
+    Ok(())
 }
 
-fn is_valid_release(release: &str) -> bool {
-    if release.is_empty() {
-        return false;
+/// Validate a Haskell PVP version in its 4-component form (A.B.C.D, where A.B is the major version)
+/// https://pvp.haskell.org
+pub(crate) fn validate_pvp(version: &str) -> Result<(), String> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() != 4 {
+        return Err(format!("Invalid PVP version: {version}. Expected format: A.B.C.D"));
     }
-
-    // Must be N or N.N or N.N.N etc.
-    release.split('.').all(|part| {
-        !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())
-    })
+    for part in parts {
+        if part.parse::<u64>().is_err() {
+            return Err(format!("Invalid PVP version component: {part}"));
+        }
+    }
+    Ok(())
 }
 
-/// Validate a semver version string (used by npm, Cargo, etc.)
-/// https://semver.org/
+/// Validate a RubyGems version
+/// https://guides.rubygems.org/patterns/#prerelease-gems
 ///
-/// Format: major.minor.patch[-prerelease][+build]
-/// - Prerelease: -alpha.1, -beta.2, -rc.1, etc.
-/// - Build metadata: +build.123 (ignored for precedence)
-///
-/// Note: post and dev releases are NOT supported in semver
-fn validate_semver(version: &str) -> Result<(), String> {
+/// Format: N[.segment]*[-pre[.pre]*], where any segment containing a letter
+/// marks a prerelease (e.g., 1.2.3, 1.2.3.pre1, 1.2.3.beta2, 1.2.3-rc.1)
+pub(crate) fn validate_rubygems(version: &str) -> Result<(), String> {
     if version.is_empty() {
         return Err("Version string cannot be empty".to_string());
     }
 
-    // Split off build metadata (e.g., "1.0.0+build")
-    let version = if let Some(pos) = version.find('+') {
-        let build = &version[pos + 1..];
-        if build.is_empty() || !is_valid_semver_identifier(build) {
-            return Err(format!("Invalid build metadata: {build}"));
-        }
-        &version[..pos]
-    } else {
-        version
-    };
-
-    // Split off prerelease (e.g., "1.0.0-alpha.1")
-    let (release, prerelease) = if let Some(pos) = version.find('-') {
-        (&version[..pos], Some(&version[pos + 1..]))
-    } else {
-        (version, None)
+    let (main, pre) = match version.split_once('-') {
+        Some((main, pre)) => (main, Some(pre)),
+        None => (version, None),
     };
 
-    // Validate release part (must be exactly major.minor.patch)
-    let parts: Vec<&str> = release.split('.').collect();
-    if parts.len() != 3 {
-        return Err(format!(
-            "Invalid semver: {release}. Expected format: major.minor.patch"
-        ));
+    let mut segments = main.split('.');
+    let first = segments.next().unwrap_or("");
+    if first.is_empty() || !first.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid RubyGems version: {version}. Must start with a number"));
     }
-    for (i, part) in parts.iter().enumerate() {
-        let name = ["major", "minor", "patch"][i];
-        if part.parse::<u32>().is_err() {
-            return Err(format!("Invalid {name} version: {part}"));
+    for segment in segments {
+        if segment.is_empty() || !segment.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid RubyGems version segment: {segment}"));
         }
     }
 
-    // Validate prerelease if present
-    if let Some(pre) = prerelease
+    if let Some(pre) = pre
         && (pre.is_empty() || !is_valid_semver_identifier(pre))
     {
         return Err(format!("Invalid prerelease: {pre}"));
@@ -213,6 +140,21 @@ fn validate_semver(version: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Validate an R package version: at least two non-negative integers separated by `.` or `-`
+/// (e.g., 1.2.3, 1.2-3, or the development version 1.2.3.9000)
+pub(crate) fn validate_r(version: &str) -> Result<(), String> {
+    let parts: Vec<&str> = version.split(['.', '-']).collect();
+    if parts.len() < 2 {
+        return Err(format!("Invalid R version: {version}. Expected at least major.minor"));
+    }
+    for part in parts {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid R version component: {part}"));
+        }
+    }
+    Ok(())
+}
+
 fn is_valid_semver_identifier(id: &str) -> bool {
     // Identifiers are dot-separated, each part is alphanumeric or hyphen
     id.split('.').all(|part| {
@@ -220,65 +162,6 @@ fn is_valid_semver_identifier(id: &str) -> bool {
     })
 }
 
-fn parse_suffixes(suffix: &str) -> Result<(), String> {
-    if suffix.is_empty() {
-        return Ok(());
-    }
-
-    let suffix = suffix.to_lowercase();
-    let mut remaining = suffix.as_str();
-
-    // Parse pre-release (a, b, rc, alpha, beta, preview, c)
-    let pre_markers = [
-        ("alpha", "a"),
-        ("beta", "b"),
-        ("preview", "rc"),
-        ("rc", "rc"),
-        ("a", "a"),
-        ("b", "b"),
-        ("c", "rc"),
-    ];
-
-    for (marker, _normalized) in pre_markers {
-        if remaining.starts_with(marker) {
-            remaining = &remaining[marker.len()..];
-            // Consume optional number
-            let num_end = remaining
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .count();
-            remaining = &remaining[num_end..];
-            break;
-        }
-    }
-
-    // Parse .post
-    if remaining.starts_with(".post") || remaining.starts_with("post") {
-        remaining = remaining.trim_start_matches('.').trim_start_matches("post");
-        let num_end = remaining
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .count();
-        remaining = &remaining[num_end..];
-    }
-
-    // Parse .dev
-    if remaining.starts_with(".dev") || remaining.starts_with("dev") {
-        remaining = remaining.trim_start_matches('.').trim_start_matches("dev");
-        let num_end = remaining
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .count();
-        remaining = &remaining[num_end..];
-    }
-
-    if remaining.is_empty() {
-        Ok(())
-    } else {
-        Err(format!("Invalid version suffix: {remaining}"))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,6 +252,54 @@ mod tests {
         assert!(validate_semver("1.0.0-alpha.1+build").is_ok());
     }
 
+    #[test]
+    fn test_cargo_versions() {
+        assert!(validate_cargo("1.2.3").is_ok());
+        assert!(validate_cargo("1.2.3-alpha.10").is_ok());
+        assert!(validate_cargo("1.2.3-alpha10").is_err());
+        assert!(validate_cargo("1.2.3+build").is_err());
+        assert!(validate_cargo("01.2.3").is_err());
+        assert!(validate_cargo("1.2.3-rc.01").is_err());
+    }
+
+    #[test]
+    fn test_dotnet_versions() {
+        assert!(validate_dotnet("1.2.3.0").is_ok());
+        assert!(validate_dotnet("1.2.3").is_err());
+        assert!(validate_dotnet("1.2.3.65535").is_err());
+        assert!(validate_dotnet("1.2.3-beta.0").is_err());
+    }
+
+    #[test]
+    fn test_rubygems_versions() {
+        assert!(validate_rubygems("1.2.3").is_ok());
+        assert!(validate_rubygems("1.2.3.pre1").is_ok());
+        assert!(validate_rubygems("7.1.0.beta1").is_ok());
+        assert!(validate_rubygems("1.2.3-rc.1").is_ok());
+        assert!(validate_rubygems("").is_err());
+        assert!(validate_rubygems("v1.2.3").is_err());
+        assert!(validate_rubygems("1..2").is_err());
+        assert!(validate_rubygems("1.2.3+build").is_err());
+    }
+
+    #[test]
+    fn test_pvp_versions() {
+        assert!(validate_pvp("0.1.0.0").is_ok());
+        assert!(validate_pvp("1.2.3.70000").is_ok());
+        assert!(validate_pvp("1.2.3").is_err());
+        assert!(validate_pvp("1.2.3.0-rc1").is_err());
+    }
+
+    #[test]
+    fn test_r_versions() {
+        assert!(validate_r("1.2.3").is_ok());
+        assert!(validate_r("1.2-3").is_ok());
+        assert!(validate_r("0.1.0.9000").is_ok());
+        assert!(validate_r("1").is_err());
+        assert!(validate_r("1.2.3a1").is_err());
+        assert!(validate_r("1..2").is_err());
+    }
+
     #[test]
     fn test_invalid_semver_versions() {
         assert!(validate_semver("").is_err());
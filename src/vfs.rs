@@ -0,0 +1,203 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use ignore::WalkBuilder;
+use ignore::overrides::Override;
+
+/// A line-by-line edit of a file, from the old content to the new
+pub type RewriteFn<'a> = dyn FnMut(&mut dyn BufRead, &mut dyn Write) -> Result<(), String> + 'a;
+
+/// The filesystem that config and project files are read from and written to.
+/// [`OsFs`] is the real filesystem; [`MemoryFs`] keeps files in memory for embedding and tests.
+pub trait Vfs {
+    /// The directory config files and the project root are searched upwards from
+    fn current_dir(&self) -> Option<PathBuf>;
+
+    /// Whether a file or directory exists
+    fn exists(&self, path: &Path) -> bool;
+
+    /// The size of a file in bytes
+    fn file_size(&self, path: &Path) -> Option<u64>;
+
+    fn read_to_string(&self, path: &Path) -> Result<String, String>;
+
+    fn write(&self, path: &Path, content: &str) -> Result<(), String>;
+
+    /// Open a file to read it line by line
+    fn open(&self, path: &Path) -> Result<Box<dyn BufRead + '_>, String>;
+
+    /// Rewrite a file through `edit` without holding it in memory.
+    /// The file is only replaced if `edit` succeeds.
+    fn rewrite(&self, path: &Path, edit: &mut RewriteFn) -> Result<(), String>;
+
+    /// Every file under `root`, sorted. Skips `.git/`, anything matching an ignore
+    /// pattern in `skip` (built with `!pattern` globs), and git-ignored files where supported.
+    fn list_files(&self, root: &Path, skip: &Override) -> Result<Vec<PathBuf>, String>;
+}
+
+/// The directory `OsFs` searches from instead of the process working directory (`--directory`)
+static WORK_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The real filesystem, relative to the process working directory
+pub struct OsFs;
+
+impl OsFs {
+    /// Search from `dir` (or the process working directory again for `None`), without changing
+    /// the process working directory
+    pub fn set_current_dir(dir: Option<PathBuf>) {
+        *WORK_DIR.lock().unwrap() = dir;
+    }
+}
+
+impl Vfs for OsFs {
+    fn current_dir(&self) -> Option<PathBuf> {
+        WORK_DIR.lock().unwrap().clone().or_else(|| std::env::current_dir().ok())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn file_size(&self, path: &Path) -> Option<u64> {
+        fs::metadata(path).map(|m| m.len()).ok()
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, String> {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    }
+
+    fn write(&self, path: &Path, content: &str) -> Result<(), String> {
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn BufRead + '_>, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+
+    /// Writes into a temporary sibling, then moves it into place
+    fn rewrite(&self, path: &Path, edit: &mut RewriteFn) -> Result<(), String> {
+        let read_err = |e: std::io::Error| format!("Failed to read {}: {e}", path.display());
+        let write_err = |e: std::io::Error| format!("Failed to write {}: {e}", path.display());
+
+        let file_name = path.file_name().map(|f| f.to_string_lossy()).unwrap_or_default();
+        let tmp_path = path.with_file_name(format!(".{file_name}.bver-tmp"));
+
+        let result = (|| {
+            let mut reader = BufReader::new(File::open(path).map_err(read_err)?);
+            let mut writer = BufWriter::new(File::create(&tmp_path).map_err(write_err)?);
+            edit(&mut reader, &mut writer)?;
+            writer.flush().map_err(write_err)?;
+            let permissions = fs::metadata(path).map_err(read_err)?.permissions();
+            fs::set_permissions(&tmp_path, permissions).map_err(write_err)?;
+            fs::rename(&tmp_path, path).map_err(write_err)
+        })();
+
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    /// Respects `.gitignore`
+    fn list_files(&self, root: &Path, skip: &Override) -> Result<Vec<PathBuf>, String> {
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .require_git(false)
+            .overrides(skip.clone())
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+
+        let mut paths = Vec::new();
+        for entry in walker {
+            let entry = entry.map_err(|e| format!("Failed to scan {}: {e}", root.display()))?;
+            if entry.file_type().is_some_and(|t| t.is_file()) {
+                paths.push(entry.into_path());
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+/// An in-memory filesystem. Paths are used as given, so use absolute paths
+/// under the working directory passed to [`MemoryFs::new`].
+pub struct MemoryFs {
+    current_dir: PathBuf,
+    files: RefCell<BTreeMap<PathBuf, String>>,
+}
+
+impl MemoryFs {
+    pub fn new(current_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            current_dir: current_dir.into(),
+            files: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Add (or replace) a file
+    pub fn with_file(self, path: impl Into<PathBuf>, content: &str) -> Self {
+        self.files.borrow_mut().insert(path.into(), content.to_string());
+        self
+    }
+
+    /// The current content of a file
+    pub fn file(&self, path: &Path) -> Option<String> {
+        self.files.borrow().get(path).cloned()
+    }
+}
+
+impl Vfs for MemoryFs {
+    fn current_dir(&self) -> Option<PathBuf> {
+        Some(self.current_dir.clone())
+    }
+
+    /// Directories exist as long as they contain a file
+    fn exists(&self, path: &Path) -> bool {
+        self.files.borrow().keys().any(|file| file.starts_with(path))
+    }
+
+    fn file_size(&self, path: &Path) -> Option<u64> {
+        self.files.borrow().get(path).map(|content| content.len() as u64)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, String> {
+        self.file(path)
+            .ok_or_else(|| format!("Failed to read {}: file not found", path.display()))
+    }
+
+    fn write(&self, path: &Path, content: &str) -> Result<(), String> {
+        self.files.borrow_mut().insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn BufRead + '_>, String> {
+        Ok(Box::new(Cursor::new(self.read_to_string(path)?)))
+    }
+
+    fn rewrite(&self, path: &Path, edit: &mut RewriteFn) -> Result<(), String> {
+        let mut reader = Cursor::new(self.read_to_string(path)?);
+        let mut written = Vec::new();
+        edit(&mut reader, &mut written)?;
+        let content = String::from_utf8(written)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        self.write(path, &content)
+    }
+
+    /// There is no `.gitignore` handling in memory; only `skip` applies
+    fn list_files(&self, root: &Path, skip: &Override) -> Result<Vec<PathBuf>, String> {
+        Ok(self
+            .files
+            .borrow()
+            .keys()
+            .filter(|path| path.starts_with(root))
+            .filter(|path| !path.strip_prefix(root).is_ok_and(|rel| rel.starts_with(".git")))
+            .filter(|path| !skip.matched(path, false).is_ignore())
+            .cloned()
+            .collect())
+    }
+}